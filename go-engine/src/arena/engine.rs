@@ -0,0 +1,122 @@
+use go_lib::board::go_state::GoState;
+use go_lib::book::opening_book::OpeningBook;
+use go_lib::board::stones::stone::Stone;
+use go_lib::go_rules::go_action::GoAction;
+use mcts_lib::explorator::Explorer;
+use mcts_lib::policy::policy::Policy;
+use mcts_lib::policy::score::Score;
+
+pub trait Engine {
+    fn name(&self) -> String;
+
+    fn new_game(&mut self, _size: usize) -> Result<(), String> {
+        Ok(())
+    }
+
+    // move for the side to move in state, None to resign
+    fn genmove(&mut self, state: &GoState) -> Result<Option<GoAction>, String>;
+
+    // a move played by the opponent
+    fn played(&mut self, _stone: Stone, _action: GoAction) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// plays the most visited root move after a fixed number of playouts
+pub struct MctsEngine<P: Policy<GoAction, GoState>, S: Score> {
+    pub name: String,
+    pub policy: P,
+    pub score: S,
+    pub playouts: usize,
+    // book counts seed the root statistics while in book
+    pub book: Option<OpeningBook>,
+}
+
+impl<P: Policy<GoAction, GoState>, S: Score> MctsEngine<P, S> {
+    pub fn new(name: &str, policy: P, score: S, playouts: usize) -> Self {
+        MctsEngine {
+            name: name.to_string(),
+            policy,
+            score,
+            playouts,
+            book: None,
+        }
+    }
+
+    pub fn with_book(mut self, book: OpeningBook) -> Self {
+        self.book = Some(book);
+        self
+    }
+}
+
+impl<P: Policy<GoAction, GoState>, S: Score> Engine for MctsEngine<P, S> {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn genmove(&mut self, state: &GoState) -> Result<Option<GoAction>, String> {
+        let mut explorer = Explorer::new(1, state.clone());
+        if let Some(book) = &self.book {
+            book.seed(state, explorer.mcts_mut());
+        }
+        for _ in 0..self.playouts {
+            explorer.explore(&self.policy, &self.score);
+        }
+        let best = explorer.mcts().visits().into_iter()
+            .max_by_key(|&(_, n)| n)
+            .map(|(a, _)| a)
+            .unwrap_or(GoAction::Pass);
+        Ok(Some(best))
+    }
+}
+
+// plays the move of a policy, without search
+pub struct PolicyEngine<P: Policy<GoAction, GoState>> {
+    pub name: String,
+    pub policy: P,
+}
+
+impl<P: Policy<GoAction, GoState>> PolicyEngine<P> {
+    pub fn new(name: &str, policy: P) -> Self {
+        PolicyEngine {
+            name: name.to_string(),
+            policy,
+        }
+    }
+}
+
+impl<P: Policy<GoAction, GoState>> Engine for PolicyEngine<P> {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn genmove(&mut self, state: &GoState) -> Result<Option<GoAction>, String> {
+        Ok(Some(self.policy.select(state)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use go_lib::board::go_state::GoState;
+    use go_lib::board::stones::stone::Stone;
+    use go_lib::book::opening_book::OpeningBook;
+    use go_lib::go_rules::go_action::GoAction;
+    use mcts_lib::policy::random_policy::RandomPolicy;
+    use mcts_lib::policy::win_score::WinScore;
+    use mcts_lib::rules::Rules;
+
+    use crate::arena::engine::{Engine, MctsEngine};
+
+    #[test]
+    fn book_seeds_the_search() {
+        let mut game = GoState::new(5);
+        game.apply_action(GoAction::Cell(1, 2));
+        let mut book = OpeningBook::new(5, 5, 1);
+        for _ in 0..20 {
+            book.add_game(&game, Stone::Black).unwrap();
+        }
+        let mut engine = MctsEngine::new("book", RandomPolicy::new(1), WinScore::new(), 10).with_book(book);
+        let action = engine.genmove(&GoState::new(5)).unwrap().unwrap();
+        assert!([(1, 2), (2, 1), (3, 2), (2, 3)].iter().any(|&(x, y)| action == GoAction::Cell(x, y)), "{:?}", action);
+    }
+}
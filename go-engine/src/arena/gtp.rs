@@ -0,0 +1,188 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use go_lib::board::go_state::GoState;
+use go_lib::board::stones::stone::Stone;
+use go_lib::go_rules::go_action::GoAction;
+
+use crate::arena::engine::Engine;
+
+// GTP columns skip the letter I, rows are counted from the bottom
+const COLUMNS: &str = "ABCDEFGHJKLMNOPQRSTUVWXYZ";
+
+pub fn vertex(action: GoAction, height: usize) -> String {
+    match action {
+        GoAction::Pass => String::from("pass"),
+        GoAction::Cell(x, y) => format!("{}{}", COLUMNS.chars().nth(x).unwrap(), height - y)
+    }
+}
+
+pub fn parse_vertex(text: &str, height: usize) -> Result<GoAction, String> {
+    let text = text.trim().to_uppercase();
+    if text == "PASS" {
+        return Ok(GoAction::Pass);
+    }
+    let mut chars = text.chars();
+    let x = chars.next()
+        .and_then(|c| COLUMNS.find(c))
+        .ok_or(format!("invalid vertex: {}", text))?;
+    let row: usize = chars.as_str().parse().map_err(|_| format!("invalid vertex: {}", text))?;
+    if row == 0 || row > height {
+        return Err(format!("vertex out of board: {}", text));
+    }
+    Ok(GoAction::Cell(x, height - row))
+}
+
+fn color(stone: Stone) -> &'static str {
+    match stone {
+        Stone::White => "W",
+        _ => "B"
+    }
+}
+
+// external engine speaking GTP over stdin/stdout
+pub struct GtpEngine {
+    name: String,
+    child: Child,
+    input: ChildStdin,
+    output: BufReader<ChildStdout>,
+    height: usize,
+    // sent with every new game, GoState scores without komi
+    pub komi: f32,
+}
+
+impl GtpEngine {
+    pub fn spawn(program: &str, args: &[&str]) -> Result<Self, String> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("{}: {}", program, e))?;
+        let input = child.stdin.take().unwrap();
+        let output = BufReader::new(child.stdout.take().unwrap());
+        let mut engine = GtpEngine {
+            name: program.to_string(),
+            child,
+            input,
+            output,
+            height: 19,
+            komi: 0.,
+        };
+        if let Ok(name) = engine.send("name") {
+            if !name.is_empty() {
+                engine.name = name;
+            }
+        }
+        Ok(engine)
+    }
+
+    pub fn with_komi(mut self, komi: f32) -> Self {
+        self.komi = komi;
+        self
+    }
+
+    // sends one command, returns the response without its '=' prefix
+    pub fn send(&mut self, command: &str) -> Result<String, String> {
+        log::debug!("gtp> {}", command);
+        writeln!(self.input, "{}", command)
+            .and_then(|_| self.input.flush())
+            .map_err(|e| format!("{}: {}", self.name, e))?;
+
+        // a response ends with an empty line
+        let mut lines = vec![];
+        loop {
+            let mut line = String::new();
+            let read = self.output.read_line(&mut line).map_err(|e| e.to_string())?;
+            if read == 0 {
+                return Err(format!("{}: engine closed its output", self.name));
+            }
+            let line = line.trim_end();
+            if line.is_empty() && !lines.is_empty() {
+                break;
+            }
+            if !line.is_empty() {
+                lines.push(line.to_string());
+            }
+        }
+        let response = lines.join("\n");
+        log::debug!("gtp< {}", response);
+        match response.chars().next() {
+            Some('=') => Ok(response[1..].trim().to_string()),
+            _ => Err(format!("{} {}: {}", self.name, command, response)),
+        }
+    }
+}
+
+impl Engine for GtpEngine {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn new_game(&mut self, size: usize) -> Result<(), String> {
+        self.height = size;
+        self.send(&format!("boardsize {}", size))?;
+        self.send("clear_board")?;
+        self.send(&format!("komi {}", self.komi))?;
+        Ok(())
+    }
+
+    fn genmove(&mut self, state: &GoState) -> Result<Option<GoAction>, String> {
+        let response = self.send(&format!("genmove {}", color(state.current_side)))?;
+        match response.to_lowercase().as_str() {
+            "resign" => Ok(None),
+            vertex => parse_vertex(vertex, self.height).map(Some)
+        }
+    }
+
+    fn played(&mut self, stone: Stone, action: GoAction) -> Result<(), String> {
+        self.send(&format!("play {} {}", color(stone), vertex(action, self.height)))
+            .map(|_| ())
+    }
+}
+
+impl Drop for GtpEngine {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use go_lib::board::go_state::GoState;
+    use go_lib::board::stones::stone::Stone;
+    use go_lib::go_rules::go_action::GoAction;
+
+    use crate::arena::engine::Engine;
+    use crate::arena::gtp::{GtpEngine, parse_vertex, vertex};
+
+    #[test]
+    fn vertices() {
+        assert_eq!(vertex(GoAction::Cell(0, 8), 9), "A1");
+        assert_eq!(vertex(GoAction::Cell(8, 0), 9), "J9");
+        assert_eq!(parse_vertex("j9", 9), Ok(GoAction::Cell(8, 0)));
+        assert_eq!(parse_vertex("PASS", 9), Ok(GoAction::Pass));
+        assert!(parse_vertex("A10", 9).is_err());
+        assert!(parse_vertex("I3", 9).is_err());
+    }
+
+    #[test]
+    fn pipe_engine() {
+        // a minimal GTP engine always answering c3 to genmove
+        let script = r#"while read cmd; do case "$cmd" in
+            name) printf "= shell\n\n";;
+            genmove*) printf "= C3\n\n";;
+            "komi 7.5") printf "=\n\n";;
+            komi*) printf "? unexpected komi\n\n";;
+            quit) printf "=\n\n"; exit 0;;
+            *) printf "=\n\n";;
+        esac; done"#;
+        let mut engine = GtpEngine::spawn("sh", &["-c", script]).unwrap().with_komi(7.5);
+        assert_eq!(engine.name(), "shell");
+        engine.new_game(5).unwrap();
+        engine.played(Stone::Black, GoAction::Cell(1, 1)).unwrap();
+        let state = GoState::new(5);
+        assert_eq!(engine.genmove(&state), Ok(Some(GoAction::Cell(2, 2))));
+    }
+}
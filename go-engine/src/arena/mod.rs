@@ -0,0 +1,4 @@
+pub mod engine;
+pub mod gtp;
+pub mod stats;
+pub mod tournament;
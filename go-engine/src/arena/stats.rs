@@ -0,0 +1,152 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+// z for a two sided 95% confidence interval
+const Z95: f32 = 1.96;
+
+// results seen by the first engine of a match
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct MatchStats {
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl MatchStats {
+    pub fn new() -> Self {
+        MatchStats::default()
+    }
+
+    pub fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    // expected score in [0, 1], a draw counts half
+    pub fn score(&self) -> f32 {
+        match self.games() {
+            0 => 0.5,
+            n => (self.wins as f32 + 0.5 * self.draws as f32) / n as f32
+        }
+    }
+
+    // variance of a single game score
+    pub fn variance(&self) -> f32 {
+        let n = self.games().max(1) as f32;
+        let s = self.score();
+        (self.wins as f32 * (1. - s).powi(2)
+            + self.draws as f32 * (0.5 - s).powi(2)
+            + self.losses as f32 * s.powi(2)) / n
+    }
+
+    pub fn score_interval(&self) -> (f32, f32) {
+        let margin = Z95 * (self.variance() / self.games().max(1) as f32).sqrt();
+        ((self.score() - margin).max(0.), (self.score() + margin).min(1.))
+    }
+
+    pub fn elo(&self) -> f32 {
+        MatchStats::elo_from_score(self.score())
+    }
+
+    pub fn elo_interval(&self) -> (f32, f32) {
+        let (low, high) = self.score_interval();
+        (MatchStats::elo_from_score(low), MatchStats::elo_from_score(high))
+    }
+
+    // infinite for a score of 0 or 1
+    pub fn elo_from_score(score: f32) -> f32 {
+        400. * (score / (1. - score)).log10()
+    }
+
+    pub fn score_from_elo(elo: f32) -> f32 {
+        1. / (1. + 10f32.powf(-elo / 400.))
+    }
+}
+
+impl Display for MatchStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (low, high) = self.score_interval();
+        let (elo_low, elo_high) = self.elo_interval();
+        write!(f, "+{} ={} -{} | score {:.1}% [{:.1}%, {:.1}%] | elo {:+.0} [{:+.0}, {:+.0}]",
+               self.wins, self.draws, self.losses,
+               100. * self.score(), 100. * low, 100. * high,
+               self.elo(), elo_low, elo_high)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SprtStatus {
+    // elo0 holds: the change is not an improvement
+    AcceptH0,
+    // elo1 holds
+    AcceptH1,
+    Continue,
+}
+
+// sequential probability ratio test between two elo hypotheses
+#[derive(Debug, Copy, Clone)]
+pub struct Sprt {
+    pub elo0: f32,
+    pub elo1: f32,
+    pub alpha: f32,
+    pub beta: f32,
+}
+
+impl Sprt {
+    pub fn new(elo0: f32, elo1: f32) -> Self {
+        assert!(elo0 < elo1);
+        Sprt { elo0, elo1, alpha: 0.05, beta: 0.05 }
+    }
+
+    pub fn bounds(&self) -> (f32, f32) {
+        ((self.beta / (1. - self.alpha)).ln(), ((1. - self.beta) / self.alpha).ln())
+    }
+
+    // log likelihood ratio, normal approximation of the game scores
+    pub fn llr(&self, stats: &MatchStats) -> f32 {
+        if stats.games() == 0 {
+            return 0.;
+        }
+        // one pseudo win and loss keep the variance positive
+        let stats = MatchStats { wins: stats.wins + 1, losses: stats.losses + 1, ..*stats };
+        let s0 = MatchStats::score_from_elo(self.elo0);
+        let s1 = MatchStats::score_from_elo(self.elo1);
+        (s1 - s0) * (2. * stats.score() - s0 - s1) * stats.games() as f32 / (2. * stats.variance())
+    }
+
+    pub fn status(&self, stats: &MatchStats) -> SprtStatus {
+        let (lower, upper) = self.bounds();
+        match self.llr(stats) {
+            llr if llr <= lower => SprtStatus::AcceptH0,
+            llr if llr >= upper => SprtStatus::AcceptH1,
+            _ => SprtStatus::Continue
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arena::stats::{MatchStats, Sprt, SprtStatus};
+
+    #[test]
+    fn elo_and_intervals() {
+        let stats = MatchStats { wins: 60, draws: 0, losses: 40 };
+        assert_eq!(stats.score(), 0.6);
+        assert!((stats.elo() - 70.4).abs() < 0.1);
+        let (low, high) = stats.score_interval();
+        assert!((high - low - 2. * 1.96 * 0.049).abs() < 1e-3);
+        assert!((MatchStats::score_from_elo(stats.elo()) - 0.6).abs() < 1e-5);
+
+        let even = MatchStats { wins: 10, draws: 10, losses: 10 };
+        assert_eq!(even.elo(), 0.);
+        assert_eq!(MatchStats { wins: 3, draws: 0, losses: 0 }.elo(), f32::INFINITY);
+    }
+
+    #[test]
+    fn sprt_decisions() {
+        let sprt = Sprt::new(0., 50.);
+        assert_eq!(sprt.status(&MatchStats::new()), SprtStatus::Continue);
+        assert_eq!(sprt.status(&MatchStats { wins: 12, draws: 0, losses: 8 }), SprtStatus::Continue);
+        assert_eq!(sprt.status(&MatchStats { wins: 700, draws: 0, losses: 300 }), SprtStatus::AcceptH1);
+        assert_eq!(sprt.status(&MatchStats { wins: 480, draws: 40, losses: 480 }), SprtStatus::AcceptH0);
+    }
+}
@@ -0,0 +1,186 @@
+use std::fs;
+use std::path::Path;
+
+use go_lib::board::go_state::GoState;
+use go_lib::board::stones::stone::Stone;
+use go_lib::display::display::GoDisplay;
+use go_lib::display::goshow::GoShow;
+use go_lib::go_rules::go_action::GoAction;
+use go_lib::go_rules::go_rules::GoRules;
+use go_lib::sgf::sgf_export::SGF;
+use mcts_lib::rules::{GameResult, Rules};
+
+use crate::arena::engine::Engine;
+use crate::arena::stats::{MatchStats, Sprt, SprtStatus};
+
+pub struct GameRecord {
+    pub black: String,
+    pub white: String,
+    // Stone::None for a draw
+    pub winner: Stone,
+    pub reason: String,
+    pub sgf: String,
+}
+
+// plays engine a against engine b, alternating colours
+pub struct Tournament {
+    pub size: usize,
+    pub games: usize,
+    pub max_moves: usize,
+    pub sprt: Option<Sprt>,
+    pub sgf_dir: Option<String>,
+}
+
+impl Tournament {
+    pub fn new(size: usize, games: usize) -> Self {
+        Tournament {
+            size,
+            games,
+            max_moves: 2 * size * size,
+            sprt: None,
+            sgf_dir: None,
+        }
+    }
+
+    pub fn run(&self, a: &mut dyn Engine, b: &mut dyn Engine) -> Result<MatchStats, String> {
+        if let Some(dir) = &self.sgf_dir {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir, e))?;
+        }
+        let mut stats = MatchStats::new();
+        for game in 0..self.games {
+            let a_is_black = game % 2 == 0;
+            let record = match a_is_black {
+                true => self.play(a, b)?,
+                false => self.play(b, a)?,
+            };
+            let a_stone = if a_is_black { Stone::Black } else { Stone::White };
+            match record.winner {
+                Stone::None => stats.draws += 1,
+                w if w == a_stone => stats.wins += 1,
+                _ => stats.losses += 1,
+            }
+            if let Some(dir) = &self.sgf_dir {
+                let path = Path::new(dir).join(format!("match_{:04}.sgf", game));
+                fs::write(&path, &record.sgf).map_err(|e| format!("{:?}: {}", path, e))?;
+            }
+            log::info!("game {}: {} vs {}, winner {:?} ({}) | {} {}",
+                       game, record.black, record.white, record.winner, record.reason, a.name(), stats);
+
+            if let Some(sprt) = &self.sprt {
+                let status = sprt.status(&stats);
+                if status != SprtStatus::Continue {
+                    log::info!("sprt [{}, {}]: {:?} after {} games (llr {:.2})",
+                               sprt.elo0, sprt.elo1, status, stats.games(), sprt.llr(&stats));
+                    break;
+                }
+            }
+        }
+        Ok(stats)
+    }
+
+    pub fn play(&self, black: &mut dyn Engine, white: &mut dyn Engine) -> Result<GameRecord, String> {
+        black.new_game(self.size)?;
+        white.new_game(self.size)?;
+        let mut state = GoState::new(self.size);
+        let mut forfeit = None;
+        while state.result().is_none() && state.history.len() < self.max_moves {
+            let side = state.current_side;
+            let answer = match side {
+                Stone::Black => black.genmove(&state)?,
+                _ => white.genmove(&state)?,
+            };
+            let action = match answer {
+                None => {
+                    forfeit = Some((side, String::from("resign")));
+                    break;
+                }
+                Some(action) => action,
+            };
+            if !Tournament::is_legal(&state, action) {
+                forfeit = Some((side, format!("illegal move {:?}", action)));
+                break;
+            }
+            state.apply_action(action);
+            match side {
+                Stone::Black => white.played(side, action)?,
+                _ => black.played(side, action)?,
+            }
+        }
+
+        state.update_score();
+        let resigned = forfeit.is_some();
+        let (winner, reason) = match forfeit {
+            Some((loser, reason)) => (loser.switch(), reason),
+            None => {
+                let winner = match state.result().unwrap_or(GameResult::Draw) {
+                    GameResult::Win => state.current_side,
+                    GameResult::Lose => state.current_side.switch(),
+                    _ => Stone::None
+                };
+                (winner, String::from("score"))
+            }
+        };
+
+        let mut sgf = GoDisplay::sgf(&state);
+        sgf.add_root_prop("PB", &black.name());
+        sgf.add_root_prop("PW", &white.name());
+        sgf.add_root_prop("RE", &SGF::result(&state, winner, resigned));
+        Ok(GameRecord {
+            black: black.name(),
+            white: white.name(),
+            winner,
+            reason,
+            sgf: sgf.to_string(),
+        })
+    }
+
+    fn is_legal(state: &GoState, action: GoAction) -> bool {
+        state.actions().contains(&action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use go_lib::board::go_state::GoState;
+    use go_lib::board::stones::stone::Stone;
+    use go_lib::go_rules::go_action::GoAction;
+    use mcts_lib::policy::random_policy::RandomPolicy;
+    use mcts_lib::policy::win_score::WinScore;
+
+    use crate::arena::engine::{Engine, MctsEngine};
+    use crate::arena::stats::Sprt;
+    use crate::arena::tournament::Tournament;
+
+    struct Resign;
+
+    impl Engine for Resign {
+        fn name(&self) -> String {
+            String::from("resign")
+        }
+
+        fn genmove(&mut self, _state: &GoState) -> Result<Option<GoAction>, String> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn alternate_colours() {
+        let mut mcts = MctsEngine::new("mcts", RandomPolicy::new(1), WinScore::new(), 5);
+        let tournament = Tournament::new(5, 4);
+        let record = tournament.play(&mut Resign, &mut mcts).unwrap();
+        assert_eq!(record.winner, Stone::White);
+        assert!(record.sgf.contains("RE[W+R]") && record.sgf.contains("PW[mcts]"));
+
+        let stats = tournament.run(&mut mcts, &mut Resign).unwrap();
+        assert_eq!((stats.wins, stats.losses), (4, 0));
+    }
+
+    #[test]
+    fn sprt_stops_early() {
+        let mut mcts = MctsEngine::new("mcts", RandomPolicy::new(1), WinScore::new(), 1);
+        let tournament = Tournament { sprt: Some(Sprt::new(0., 100.)), ..Tournament::new(5, 1000) };
+        let stats = tournament.run(&mut mcts, &mut Resign).unwrap();
+        assert!(stats.games() < 1000);
+        assert_eq!(stats.losses, 0);
+    }
+}
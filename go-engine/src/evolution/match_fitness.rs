@@ -0,0 +1,82 @@
+use go_lib::board::go_state::GoState;
+use go_lib::export::sample::Features;
+use go_lib::go_rules::go_action::GoAction;
+use mcts_lib::policy::policy::Policy;
+use mcts_lib::policy::random_policy::RandomPolicy;
+use mcts_lib::policy::win_score::WinScore;
+use mini_nn::conv2::Conv2;
+
+use crate::arena::engine::{Engine, MctsEngine, PolicyEngine};
+use crate::arena::stats::MatchStats;
+use crate::arena::tournament::Tournament;
+use crate::evolution::pattern_policy::PatternPolicy;
+
+#[derive(Clone)]
+pub enum Opponent {
+    // the fixed baseline
+    Random,
+    Pattern(Box<Conv2>),
+}
+
+// playing strength of a pattern: short matches of an engine driven by the pattern
+// against an opponent using the same search, colours alternate
+#[derive(Debug, Clone)]
+pub struct MatchFitness {
+    pub size: usize,
+    pub games: usize,
+    // MCTS playouts per move, 0 plays the policy moves directly
+    pub playouts: usize,
+    pub features: Features,
+}
+
+impl MatchFitness {
+    pub fn new(size: usize, games: usize, features: Features) -> Self {
+        MatchFitness {
+            size,
+            games,
+            playouts: 0,
+            features,
+        }
+    }
+
+    // every random choice of the match derives from the seed
+    pub fn play(&self, pattern: &Conv2, opponent: &Opponent, seed: u64) -> Result<MatchStats, String> {
+        let mut candidate = self.engine("candidate", PatternPolicy::new(pattern.clone(), self.features.clone(), seed));
+        let other_seed = seed.wrapping_add(1);
+        let mut other = match opponent {
+            Opponent::Random => self.engine("random", RandomPolicy::new(other_seed)),
+            Opponent::Pattern(p) => self.engine("pattern", PatternPolicy::new(p.as_ref().clone(), self.features.clone(), other_seed)),
+        };
+        Tournament::new(self.size, self.games).run(candidate.as_mut(), other.as_mut())
+    }
+
+    fn engine<P: Policy<GoAction, GoState> + 'static>(&self, name: &str, policy: P) -> Box<dyn Engine> {
+        match self.playouts {
+            0 => Box::new(PolicyEngine::new(name, policy)),
+            n => Box::new(MctsEngine::new(name, policy, WinScore::new(), n)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use go_lib::export::sample::Features;
+
+    use crate::evolution::match_fitness::{MatchFitness, Opponent};
+    use crate::evolution::pattern_policy::PatternPolicy;
+
+    #[test]
+    fn reproducible_matches() {
+        let features = Features::new(1);
+        let pattern = PatternPolicy::random_pattern(&features, 3, &mut rand::thread_rng());
+        let mut fitness = MatchFitness::new(5, 4, features);
+        let stats = fitness.play(&pattern, &Opponent::Random, 7).unwrap();
+        assert_eq!(stats.games(), 4);
+        assert_eq!(fitness.play(&pattern, &Opponent::Random, 7).unwrap(), stats);
+
+        fitness.playouts = 2;
+        fitness.games = 2;
+        let stats = fitness.play(&pattern, &Opponent::Pattern(Box::new(pattern.clone())), 1).unwrap();
+        assert_eq!(stats.games(), 2);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod match_fitness;
+pub mod neuroevolution;
+pub mod pattern_policy;
\ No newline at end of file
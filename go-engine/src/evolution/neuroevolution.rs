@@ -0,0 +1,178 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use mini_nn::algo::crossover::CrossOver;
+use mini_nn::algo::genetic_model::GeneticModel;
+use mini_nn::algo::genetic_trainer::GeneticTrainer;
+use mini_nn::algo::mutation::Mutation;
+use mini_nn::algo::population::diversity::Diversity;
+use mini_nn::algo::selection::Selection;
+use mini_nn::algo::selections::tournament::TournamentSelection;
+use mini_nn::conv2::Conv2;
+use mini_nn::framework::trainer::EpochMetrics;
+use rust_tools::parallel::parallel_map;
+
+use crate::arena::stats::MatchStats;
+use crate::evolution::match_fitness::{MatchFitness, Opponent};
+
+// evolves pattern playout policies on their match results: an individual scores its
+// loss rate against the random baseline and, optionally, the best pattern so far
+pub struct NeuroEvolution<Mut, Cr, Sel = TournamentSelection> {
+    pub trainer: GeneticTrainer<Mut, Cr, Sel>,
+    pub fitness: MatchFitness,
+    pub against_best: bool,
+    pub seed: u64,
+}
+
+impl<Mut, Cr, Sel> NeuroEvolution<Mut, Cr, Sel> {
+    pub fn new(trainer: GeneticTrainer<Mut, Cr, Sel>, fitness: MatchFitness) -> Self {
+        NeuroEvolution {
+            trainer,
+            fitness,
+            against_best: true,
+            seed: 0,
+        }
+    }
+
+    // games depend on the seed, the generation and the individual only,
+    // never on the thread running them
+    fn game_seed(seed: u64, generation: usize, index: usize) -> u64 {
+        fnv(&[seed, generation as u64, index as u64])
+    }
+
+    // selection, crossovers and mutations of a generation, no individual has the last index
+    fn breeding_seed(seed: u64, generation: usize) -> u64 {
+        fnv(&[seed, generation as u64, u64::MAX])
+    }
+
+    // loss rates of the patterns, individuals are spread over the threads of the trainer
+    pub fn evaluate(&self, patterns: &[&Conv2], opponents: &[Opponent], generation: usize) -> Result<Vec<f32>, String> {
+        let (fitness, seed) = (&self.fitness, self.seed);
+        parallel_map(patterns, self.trainer.threads, |i, pattern| {
+            let game_seed = NeuroEvolution::<Mut, Cr, Sel>::game_seed(seed, generation, i);
+            let mut total = MatchStats::new();
+            for (k, opponent) in opponents.iter().enumerate() {
+                let stats = fitness.play(pattern, opponent, game_seed.wrapping_add(2 * k as u64))?;
+                total.wins += stats.wins;
+                total.draws += stats.draws;
+                total.losses += stats.losses;
+            }
+            Ok(1. - total.score())
+        }).into_iter().collect()
+    }
+
+    // scores the population and the best pattern, then breeds the next generation
+    pub fn generation(&self, model: &mut GeneticModel<Conv2>) -> Result<EpochMetrics, String>
+        where Mut: Mutation<Conv2>, Cr: CrossOver<Conv2>, Sel: Selection
+    {
+        let mut opponents = vec![Opponent::Random];
+        if self.against_best && model.generation > 0 {
+            opponents.push(Opponent::Pattern(Box::new(model.best.adn.clone())));
+        }
+        let mut patterns: Vec<&Conv2> = model.population.population.iter().map(|x| &x.adn).collect();
+        patterns.push(&model.best.adn);
+        let scores = self.evaluate(&patterns, &opponents, model.generation)?;
+
+        for (x, &score) in model.population.population.iter_mut().zip(scores.iter()) {
+            x.score = score;
+        }
+        model.best.score = scores[scores.len() - 1];
+        let new_best = model.population.best().clone();
+        if new_best.score < model.best.score {
+            model.best = new_best;
+        }
+        model.diversity = Diversity::of_scores(&model.population);
+        log::info!("generation {}: best loss rate {:.3}, {}", model.generation, model.best.score, model.diversity);
+
+        let mut rng = StdRng::seed_from_u64(NeuroEvolution::<Mut, Cr, Sel>::breeding_seed(self.seed, model.generation));
+        self.trainer.next_generation(&mut model.population, &mut rng);
+        model.generation += 1;
+        Ok(EpochMetrics {
+            epoch: model.generation - 1,
+            learning_rate: 0.,
+            train_loss: model.best.score,
+            validation_loss: None,
+        })
+    }
+}
+
+// FNV-1a, stable across builds unlike DefaultHasher: a seed replays the same run
+fn fnv(values: &[u64]) -> u64 {
+    values.iter()
+        .flat_map(|v| v.to_le_bytes().to_vec())
+        .fold(0xcbf29ce484222325, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use go_lib::export::sample::Features;
+    use mini_nn::algo::crossovers::mix_cross::MixCross;
+    use mini_nn::algo::genetic_model::GeneticModel;
+    use mini_nn::algo::genetic_trainer::GeneticTrainer;
+    use mini_nn::algo::mutations::add_mut::AddMut;
+    use mini_nn::framework::parameters::Parameters;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::evolution::match_fitness::{MatchFitness, Opponent};
+    use crate::evolution::neuroevolution::{fnv, NeuroEvolution};
+    use crate::evolution::pattern_policy::PatternPolicy;
+
+    fn evolution(threads: usize) -> NeuroEvolution<AddMut, MixCross> {
+        let trainer = GeneticTrainer::new(vec![AddMut::new(0.5)], vec![MixCross {}]);
+        let mut res = NeuroEvolution::new(trainer, MatchFitness::new(5, 2, Features::new(1)));
+        res.trainer.threads = threads;
+        res
+    }
+
+    #[test]
+    fn threads_do_not_change_scores() {
+        let features = Features::new(1);
+        let mut rng = StdRng::seed_from_u64(1);
+        let patterns: Vec<_> = (0..5).map(|_| PatternPolicy::random_pattern(&features, 3, &mut rng)).collect();
+        let refs: Vec<_> = patterns.iter().collect();
+        let opponents = [Opponent::Random, Opponent::Pattern(Box::new(patterns[0].clone()))];
+        let one = evolution(1).evaluate(&refs, &opponents, 3).unwrap();
+        assert_eq!(evolution(3).evaluate(&refs, &opponents, 3).unwrap(), one);
+        assert!(one.iter().all(|&s| (0. ..=1.).contains(&s)));
+    }
+
+    #[test]
+    fn generations() {
+        let features = Features::new(1);
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut model = GeneticModel::new(|| PatternPolicy::random_pattern(&features, 3, &mut rng), 6);
+        let evolution = evolution(2);
+        for g in 0..3 {
+            let metrics = evolution.generation(&mut model).unwrap();
+            assert_eq!(metrics.epoch, g);
+        }
+        assert_eq!(model.population.len(), 6);
+        assert!(model.best.score <= 1.);
+    }
+
+    #[test]
+    fn seeded_runs_are_reproducible() {
+        let features = Features::new(1);
+        let run = |threads: usize| {
+            let mut rng = StdRng::seed_from_u64(7);
+            let mut model = GeneticModel::new(|| PatternPolicy::random_pattern(&features, 3, &mut rng), 4);
+            let evolution = evolution(threads);
+            for _ in 0..2 {
+                evolution.generation(&mut model).unwrap();
+            }
+            let values: Vec<Vec<f32>> = model.population.population.iter().map(|x| x.adn.flat_values()).collect();
+            (values, model.best.score)
+        };
+        assert_eq!(run(1), run(3));
+    }
+
+    #[test]
+    fn stable_seeds() {
+        // FNV-1a over the little endian bytes, the same on every build
+        assert_eq!(fnv(&[1, 2, 3]), 0xda2bfb225e0d1f05);
+        assert_eq!(NeuroEvolution::<AddMut, MixCross>::game_seed(1, 2, 3), 0xda2bfb225e0d1f05);
+        assert_ne!(NeuroEvolution::<AddMut, MixCross>::breeding_seed(1, 2),
+                   NeuroEvolution::<AddMut, MixCross>::game_seed(1, 2, 0));
+    }
+}
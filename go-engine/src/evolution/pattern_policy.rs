@@ -0,0 +1,104 @@
+use std::cell::RefCell;
+
+use rand::distributions::WeightedIndex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand::distributions::Distribution;
+
+use go_lib::board::go_state::GoState;
+use go_lib::board::group_access::GroupAccess;
+use go_lib::export::sample::Features;
+use go_lib::go_rules::go_action::GoAction;
+use mcts_lib::policy::policy::Policy;
+use mcts_lib::rules::Rules;
+use mini_nn::conv2::Conv2;
+
+// playout policy scoring every cell with a convolution of the feature planes,
+// moves are sampled from a softmax of the scores
+pub struct PatternPolicy {
+    pub pattern: Conv2,
+    pub features: Features,
+    pub temperature: f32,
+    rng: RefCell<StdRng>,
+}
+
+impl PatternPolicy {
+    // a [k, k, planes, 1] pattern, k odd
+    pub fn new(pattern: Conv2, features: Features, seed: u64) -> Self {
+        let dims = pattern.filter.dims();
+        assert_eq!(dims[2], features.planes(), "pattern planes");
+        assert!(dims[0] % 2 == 1 && dims[3] == 1, "pattern {:?}", dims);
+        PatternPolicy {
+            pattern,
+            features,
+            temperature: 1.,
+            rng: RefCell::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+
+    pub fn random_pattern<R: Rng>(features: &Features, kernel: usize, rng: &mut R) -> Conv2 {
+        Conv2::random(kernel, features.planes(), 1, rng)
+    }
+
+    // the bias shifts every cell alike, it does not change the distribution
+    pub fn scores(&self, state: &GoState) -> Vec<(GoAction, f32)> {
+        let goban = state.gg.goban();
+        let padding = self.pattern.filter.dims()[0] / 2;
+        let scores = self.features.encode(state).conv2d(&self.pattern.filter, padding, 1);
+        state.actions().into_iter()
+            .filter_map(|a| a.cell(goban).map(|c| (a, scores.get(c))))
+            .collect()
+    }
+}
+
+// passes only when no cell is left
+impl Policy<GoAction, GoState> for PatternPolicy {
+    fn select(&self, state: &GoState) -> GoAction {
+        let scores = self.scores(state);
+        let max = scores.iter().map(|&(_, s)| s).fold(f32::NEG_INFINITY, f32::max);
+        let weights = scores.iter().map(|&(_, s)| ((s - max) / self.temperature).exp());
+        match WeightedIndex::new(weights) {
+            Ok(dist) => scores[dist.sample(&mut *self.rng.borrow_mut())].0,
+            Err(_) => GoAction::Pass
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use go_lib::board::go_state::GoState;
+    use go_lib::export::sample::Features;
+    use go_lib::go_rules::go_action::GoAction;
+    use mcts_lib::policy::policy::Policy;
+    use mcts_lib::rules::Rules;
+
+    use crate::evolution::pattern_policy::PatternPolicy;
+
+    #[test]
+    fn plays_next_to_opponent_stones() {
+        let features = Features::new(1);
+        let mut pattern = PatternPolicy::random_pattern(&features, 3, &mut rand::thread_rng());
+        pattern.filter = pattern.filter.map(|_| 0.);
+        // opponent stones are plane 1
+        for &(x, y) in [(0, 1), (2, 1), (1, 0), (1, 2)].iter() {
+            pattern.filter.insert_nd(&[x, y, 1, 0], 10.);
+        }
+        let mut policy = PatternPolicy::new(pattern, features, 3);
+        policy.temperature = 0.1;
+
+        let mut state = GoState::new(7);
+        state.apply_action(GoAction::Cell(3, 3));
+        let neighbours = [GoAction::Cell(2, 3), GoAction::Cell(4, 3), GoAction::Cell(3, 2), GoAction::Cell(3, 4)];
+        for _ in 0..10 {
+            assert!(neighbours.contains(&policy.select(&state)));
+        }
+
+        // sampling is reproducible
+        let pattern = PatternPolicy::random_pattern(&policy.features, 3, &mut rand::thread_rng());
+        let moves = |seed| {
+            let p = PatternPolicy::new(pattern.clone(), Features::new(1), seed);
+            (0..20).map(|_| p.select(&state)).collect::<Vec<_>>()
+        };
+        assert_eq!(moves(5), moves(5));
+    }
+}
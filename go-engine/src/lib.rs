@@ -1,4 +1,7 @@
+pub mod arena;
 pub mod capture_policy;
+pub mod evolution;
+pub mod nn;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,266 @@
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use go_lib::board::go_state::GoState;
+use go_lib::board::grid::{GoCell, Grid};
+use go_lib::board::group_access::GroupAccess;
+use go_lib::board::symmetry::{Symmetry, Transform};
+use go_lib::export::sample::Features;
+use go_lib::go_rules::go_action::GoAction;
+use graph_lib::topology::Topology;
+use mcts_lib::policy::evaluator::Evaluator;
+use mcts_lib::policy::policy::Policy;
+use mcts_lib::rules::{GameResult, Rules};
+use mcts_lib::sim_result::SimResult;
+use mini_nn::layers::policy_value::PolicyValueNet;
+use ordered_float::OrderedFloat;
+use tensor_lib::autograd::variable::Variable;
+use tensor_lib::tensor::Tensor;
+
+use crate::nn::lru_cache::LruCache;
+use crate::nn::symmetry::Planes;
+
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    // move probabilities over the board cells, pass last
+    pub priors: Vec<f32>,
+    // expected outcome for the side to move, in [-1, 1]
+    pub value: f32,
+}
+
+impl Evaluation {
+    pub fn prior(&self, action: GoAction, goban: &Grid) -> f32 {
+        self.priors[action.cell(goban).unwrap_or(goban.vertex_number())]
+    }
+}
+
+// runs a policy/value network on the feature planes of a position: the priors drive
+// the expansion as a Policy, the value replaces the random playouts as an Evaluator
+pub struct NnEvaluator {
+    net: PolicyValueNet,
+    features: Features,
+    // averages the network outputs over the board symmetries
+    pub symmetries: bool,
+    // tries counted for a leaf evaluation, see SimResult::from_value
+    pub resolution: usize,
+    // evaluations of canonical positions, priors in the cells of the canonical board
+    cache: RefCell<LruCache<u64, Evaluation>>,
+}
+
+impl NnEvaluator {
+    pub fn new(mut net: PolicyValueNet, features: Features, cache_size: usize) -> Self {
+        net.set_training(false);
+        NnEvaluator {
+            net,
+            features,
+            symmetries: true,
+            resolution: 10,
+            cache: RefCell::new(LruCache::new(cache_size)),
+        }
+    }
+
+    pub fn net(&self) -> &PolicyValueNet {
+        &self.net
+    }
+
+    // (hits, misses)
+    pub fn cache_stats(&self) -> (usize, usize) {
+        let cache = self.cache.borrow();
+        (cache.hits, cache.misses)
+    }
+
+    pub fn clear_cache(&self) {
+        self.cache.borrow_mut().clear();
+    }
+
+    pub fn evaluation(&self, state: &GoState) -> Evaluation {
+        let (t, key) = self.position_key(state);
+        let goban = state.gg.goban();
+        if let Some(res) = self.cache.borrow_mut().get(&key) {
+            return NnEvaluator::map_priors(res, goban, |canonical, cell| canonical[t.symmetry.cell(cell, goban)]);
+        }
+        let res = self.infer(state);
+        // the symmetries of a board keep its dimensions
+        let inverse = t.symmetry.inverse();
+        let canonical = NnEvaluator::map_priors(&res, goban, |priors, cell| priors[inverse.cell(cell, goban)]);
+        self.cache.borrow_mut().insert(key, canonical);
+        res
+    }
+
+    // legal moves with their prior, normalized over them
+    pub fn priors(&self, state: &GoState) -> Vec<(GoAction, f32)> {
+        let evaluation = self.evaluation(state);
+        let goban = state.gg.goban();
+        let mut res: Vec<_> = state.actions().into_iter()
+            .map(|a| (a, evaluation.prior(a, goban)))
+            .collect();
+        let total: f32 = res.iter().map(|&(_, p)| p).sum();
+        if total > 0. {
+            res.iter_mut().for_each(|(_, p)| *p /= total);
+        }
+        res
+    }
+
+    // everything the feature planes see: stones, side to move, ko and the last moves, hashed in
+    // the frame of the canonical position when the outputs are averaged over the symmetries
+    pub fn position_key(&self, state: &GoState) -> (Transform, u64) {
+        let goban = state.gg.goban();
+        let (transforms, hash) = match self.symmetries {
+            true => state.canonical_transforms(false),
+            false => (vec![Transform::IDENTITY], state.position_hash()),
+        };
+        // a symmetric position keeps several transforms: the last moves break the tie
+        let last_moves = |t: &Transform| state.history.iter().rev().take(self.features.history)
+            .map(|&a| match t.symmetry.action(a, goban.width, goban.height) {
+                GoAction::Cell(x, y) => Some((x, y)),
+                GoAction::Pass => None,
+            })
+            .collect::<Vec<_>>();
+        let (t, moves) = transforms.iter()
+            .map(|t| (*t, last_moves(t)))
+            .min_by(|(_, a), (_, b)| a.cmp(b))
+            .unwrap();
+        let mut hasher = DefaultHasher::new();
+        hash.hash(&mut hasher);
+        moves.hash(&mut hasher);
+        (t, hasher.finish())
+    }
+
+    // priors[cell] = f(other priors, cell) for the board cells, pass unchanged
+    fn map_priors<F: Fn(&[f32], GoCell) -> f32>(evaluation: &Evaluation, goban: &Grid, f: F) -> Evaluation {
+        let pass = goban.vertex_number();
+        let mut priors: Vec<f32> = (0..pass).map(|cell| f(&evaluation.priors, cell)).collect();
+        priors.push(evaluation.priors[pass]);
+        Evaluation { priors, value: evaluation.value }
+    }
+
+    // one batch holding the position seen through every symmetry
+    fn infer(&self, state: &GoState) -> Evaluation {
+        let goban = state.gg.goban();
+        let (width, height) = (goban.width, goban.height);
+        let symmetries = match self.symmetries {
+            true => Symmetry::all(width, height),
+            false => vec![Symmetry::Identity],
+        };
+        let x = self.features.encode(state).reshape(&[width, height, self.features.planes()]);
+        let batch: Vec<Tensor> = symmetries.iter().map(|s| s.planes(&x)).collect();
+        let (policy, value) = self.net.forward(&Variable::constant(Tensor::stack(&batch)));
+        let (policy, value) = (policy.value(), value.value());
+
+        let pass = goban.vertex_number();
+        let n = symmetries.len() as f32;
+        let mut priors = vec![0.; pass + 1];
+        for (b, s) in symmetries.iter().enumerate() {
+            let (to_width, _) = s.dims(width, height);
+            for (cell, prior) in priors.iter_mut().enumerate().take(pass) {
+                let (x, y) = s.apply(cell % width, cell / width, width, height);
+                *prior += policy.get_nd(&[x + y * to_width, b]) / n;
+            }
+            priors[pass] += policy.get_nd(&[pass, b]) / n;
+        }
+        Evaluation {
+            priors,
+            value: value.sum() / n,
+        }
+    }
+}
+
+impl Policy<GoAction, GoState> for NnEvaluator {
+    fn select(&self, state: &GoState) -> GoAction {
+        self.priors(state).into_iter()
+            .max_by_key(|&(_, p)| OrderedFloat(p))
+            .map(|(a, _)| a)
+            .unwrap_or(GoAction::Pass)
+    }
+}
+
+impl Evaluator<GoAction, GoState> for NnEvaluator {
+    fn evaluate(&self, state: &GoState) -> SimResult {
+        let value = match state.result() {
+            None => self.evaluation(state).value,
+            Some(GameResult::Win) => 1.,
+            Some(GameResult::Lose) => -1.,
+            Some(_) => 0.,
+        };
+        SimResult::from_value(value, self.resolution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use go_lib::board::go_state::GoState;
+    use go_lib::board::grid::Grid;
+    use go_lib::board::symmetry::Symmetry;
+    use go_lib::export::sample::Features;
+    use go_lib::go_rules::go_action::GoAction;
+    use mcts_lib::explorator::Explorer;
+    use mcts_lib::policy::evaluator::Evaluator;
+    use mcts_lib::policy::policy::Policy;
+    use mcts_lib::policy::win_score::WinScore;
+    use mcts_lib::rules::Rules;
+    use mini_nn::layers::policy_value::PolicyValueNet;
+
+    use crate::nn::evaluator::NnEvaluator;
+
+    fn evaluator() -> NnEvaluator {
+        let features = Features::new(2);
+        NnEvaluator::new(PolicyValueNet::new(5, 5, features.planes(), 4, 1), features, 100)
+    }
+
+    fn position(moves: &[GoAction]) -> GoState {
+        let mut state = GoState::new(5);
+        moves.iter().for_each(|&a| state.apply_action(a));
+        state
+    }
+
+    #[test]
+    fn priors_over_legal_moves() {
+        let nn = evaluator();
+        let state = position(&[GoAction::Cell(1, 1), GoAction::Cell(2, 3)]);
+        let priors = nn.priors(&state);
+        assert_eq!(priors.len(), state.actions().len());
+        assert!((priors.iter().map(|&(_, p)| p).sum::<f32>() - 1.).abs() < 1e-4);
+        assert!(priors.iter().all(|&(a, _)| a != GoAction::Cell(1, 1)));
+        assert!(state.actions().contains(&nn.select(&state)));
+
+        let res = nn.evaluate(&state);
+        assert_eq!(res.tries, nn.resolution);
+        assert_eq!(nn.cache_stats(), (2, 1));
+    }
+
+    #[test]
+    fn symmetric_positions_agree() {
+        let nn = evaluator();
+        let moves = [GoAction::Cell(0, 1), GoAction::Cell(3, 2), GoAction::Cell(4, 4)];
+        let state = position(&moves);
+        let goban = Grid::new(5);
+        let a = nn.evaluation(&state);
+        for s in Symmetry::ALL.iter() {
+            let moved: Vec<_> = moves.iter().map(|&m| s.action(m, 5, 5)).collect();
+            let b = nn.evaluation(&position(&moved));
+            assert!((a.value - b.value).abs() < 1e-4, "{:?}", s);
+            for cell in 0..25 {
+                let action = GoAction::Cell(cell % 5, cell / 5);
+                let image = s.action(action, 5, 5);
+                assert!((a.prior(action, &goban) - b.prior(image, &goban)).abs() < 1e-4);
+            }
+        }
+        // one network run for the 8 symmetric positions
+        assert_eq!(nn.cache_stats(), (8, 1));
+    }
+
+    #[test]
+    fn mcts_leaf_evaluation() {
+        let nn = evaluator();
+        let state = GoState::new(5);
+        let mut explorer = Explorer::new(1, state.clone());
+        for _ in 0..20 {
+            explorer.explore_eval(&nn, &WinScore::new(), &nn);
+        }
+        let visits = explorer.mcts().visits();
+        assert_eq!(visits.iter().map(|&(_, n)| n).sum::<usize>(), 20 * nn.resolution);
+        let (best, _) = visits.into_iter().max_by_key(|&(_, n)| n).unwrap();
+        assert!(state.actions().contains(&best));
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+// bounded map dropping the least recently used entry when full
+pub struct LruCache<K, V> {
+    capacity: usize,
+    entries: HashMap<K, (V, u64)>,
+    // last use time of every key
+    uses: BTreeMap<u64, K>,
+    clock: u64,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        LruCache {
+            capacity,
+            entries: HashMap::new(),
+            uses: BTreeMap::new(),
+            clock: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.clock += 1;
+        match self.entries.get_mut(key) {
+            None => {
+                self.misses += 1;
+                None
+            }
+            Some((value, used)) => {
+                self.hits += 1;
+                self.uses.remove(used);
+                self.uses.insert(self.clock, key.clone());
+                *used = self.clock;
+                Some(value)
+            }
+        }
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.clock += 1;
+        if let Some((_, used)) = self.entries.remove(&key) {
+            self.uses.remove(&used);
+        } else if self.entries.len() == self.capacity {
+            let oldest = *self.uses.keys().next().unwrap();
+            let evicted = self.uses.remove(&oldest).unwrap();
+            self.entries.remove(&evicted);
+        }
+        self.uses.insert(self.clock, key.clone());
+        self.entries.insert(key, (value, self.clock));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.uses.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nn::lru_cache::LruCache;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, "a");
+        cache.insert(2, "b");
+        assert_eq!(cache.get(&1), Some(&"a"));
+        cache.insert(3, "c");
+        assert!(!cache.contains(&2));
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.len(), 2);
+
+        cache.insert(1, "d");
+        cache.insert(4, "e");
+        assert_eq!(cache.get(&1), Some(&"d"));
+        assert!(!cache.contains(&3));
+        assert_eq!((cache.hits, cache.misses), (2, 1));
+    }
+}
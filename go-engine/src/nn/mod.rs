@@ -0,0 +1,3 @@
+pub mod evaluator;
+pub mod lru_cache;
+pub mod symmetry;
\ No newline at end of file
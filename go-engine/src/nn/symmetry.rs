@@ -0,0 +1,54 @@
+use tensor_lib::tensor::Tensor;
+
+use go_lib::board::symmetry::Symmetry;
+
+pub trait Planes {
+    // transforms the first two dimensions of planes [width, height, ...]
+    fn planes(&self, planes: &Tensor) -> Tensor;
+}
+
+impl Planes for Symmetry {
+    fn planes(&self, planes: &Tensor) -> Tensor {
+        let dims = planes.dims();
+        let (width, height) = (dims[0], dims[1]);
+        let (to_width, to_height) = self.dims(width, height);
+        let area = width * height;
+        let mut values = vec![0.; planes.len()];
+        for (i, v) in planes.iter().enumerate() {
+            let (x, y) = self.apply(i % width, (i / width) % height, width, height);
+            values[x + y * to_width + (i / area) * area] = v;
+        }
+        let mut to_dims = dims.to_vec();
+        to_dims[0] = to_width;
+        to_dims[1] = to_height;
+        Tensor::from_vec(values, &to_dims)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::tensor::Tensor;
+
+    use go_lib::board::symmetry::Symmetry;
+
+    use crate::nn::symmetry::Planes;
+
+    #[test]
+    fn planes_follow_cells() {
+        // one marked cell per plane
+        let mut x = Tensor::zeros(&[4, 3, 2]);
+        x.insert_nd(&[1, 0, 0], 1.);
+        x.insert_nd(&[3, 2, 1], 2.);
+        for s in Symmetry::all(4, 3) {
+            let t = s.planes(&x);
+            let (x0, y0) = s.apply(1, 0, 4, 3);
+            let (x1, y1) = s.apply(3, 2, 4, 3);
+            assert_eq!(t.get_nd(&[x0, y0, 0]), 1.);
+            assert_eq!(t.get_nd(&[x1, y1, 1]), 2.);
+            assert_eq!(t.sum(), 3.);
+        }
+        let t = Symmetry::Transpose.planes(&x);
+        assert_eq!(t.dims(), &[3, 4, 2]);
+        assert_eq!(t.get_nd(&[2, 3, 1]), 2.);
+    }
+}
@@ -7,6 +7,14 @@ pub const SEED: u64 = 645;
 pub const SIM_FACTOR: usize = 1;
 pub const GOBAN_SIZE: usize = 9;
 
+pub const SELF_PLAY_GAMES: usize = 8;
+pub const SELF_PLAY_PLAYOUTS: usize = 200;
+pub const SELF_PLAY_DIR: &str = "self_play";
+pub const MATCH_DIR: &str = "match";
+// games are scored without komi, external engines are told the same
+pub const MATCH_KOMI: f32 = 0.;
+pub const SELF_PLAY_LOG_LEVEL: LevelFilter = LevelFilter::Info;
+
 // pub const LOG_LEVEL: LevelFilter = LevelFilter::Info;
 // pub const LOG_LEVEL: LevelFilter = LevelFilter::Debug;
 pub const LOG_LEVEL: LevelFilter = LevelFilter::Trace;
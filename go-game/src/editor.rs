@@ -1,6 +1,292 @@
+use std::fs;
+use std::io;
+use std::io::{BufRead, Write};
+
+use go_engine::arena::gtp::{parse_vertex, vertex};
 use go_lib::board::go_state::GoState;
+use go_lib::board::group_access::GroupAccess;
+use go_lib::board::stats::full_stats::FullStats;
+use go_lib::board::stones::stone::Stone;
+use go_lib::display::display::GoDisplay;
+use go_lib::display::goshow::GoShow;
+use go_lib::go_rules::go_action::GoAction;
+use go_lib::go_rules::go_rules::GoRules;
+use go_lib::sgf::sgf_export::SGF;
+use mcts_lib::explorator::Explorer;
+use mcts_lib::mcts::Mcts;
+use mcts_lib::policy::random_policy::RandomPolicy;
+use mcts_lib::policy::win_score::WinScore;
+use mcts_lib::rules::Rules;
+
+use constants::SEED;
 
+const HELP: &str = "\
+<vertex> | play <vertex>  play C4 (GTP) or Cc (board labels), pass
+undo                      take back the last move
+new [size]                empty board
+load <file> | save <file> SGF
+show                      board
+groups                    groups and their liberties
+liberties <vertex>        group at a vertex
+score                     territory and captures
+mcts <n>                  n more MCTS iterations on the current position
+top [k]                   best candidate moves of the search
+quit";
+
+// interactive board: one command per line, the search tree is kept until the position changes
 pub struct GoEditor {
-    state: GoState
+    state: GoState,
+    explorer: Option<Explorer<GoAction, GoState>>,
+    policy: RandomPolicy,
+    score: WinScore,
+}
+
+impl GoEditor {
+    pub fn new(size: usize) -> Self {
+        GoEditor {
+            state: GoState::new(size),
+            explorer: None,
+            policy: RandomPolicy::new(SEED),
+            score: WinScore::new(),
+        }
+    }
+
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, out: &mut W) -> io::Result<()> {
+        writeln!(out, "{}", self.show())?;
+        for line in input.lines() {
+            match self.execute(&line?) {
+                Ok(Some(text)) => writeln!(out, "{}", text)?,
+                Ok(None) => break,
+                Err(e) => writeln!(out, "error: {}", e)?,
+            }
+            out.flush()?;
+        }
+        Ok(())
+    }
+
+    // answer to one command, None to quit
+    pub fn execute(&mut self, line: &str) -> Result<Option<String>, String> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let arg = |i: usize| words.get(i).cloned().ok_or(format!("{}: missing argument", words[0]));
+        let number = |i: usize, default: usize| match words.get(i) {
+            None => Ok(default),
+            Some(w) => w.parse::<usize>().map_err(|e| format!("{}: {}", w, e)),
+        };
+        let answer = match words.first().map(|w| w.to_lowercase()).as_deref() {
+            None | Some("show") => self.show(),
+            Some("help") => HELP.to_string(),
+            Some("quit") | Some("exit") => return Ok(None),
+            Some("play") => self.play(arg(1)?)?,
+            Some("undo") => self.undo()?,
+            Some("new") => {
+                let size = number(1, self.state.gg.goban().width)?;
+                self.set_state(GoState::new(size))
+            }
+            Some("load") => self.set_state(SGF::load(arg(1)?)?),
+            Some("save") => {
+                let path = arg(1)?;
+                fs::write(path, GoDisplay::sgf(&self.state).to_string()).map_err(|e| format!("{}: {}", path, e))?;
+                format!("saved {}", path)
+            }
+            Some("groups") => self.groups(),
+            Some("liberties") => self.liberties(arg(1)?)?,
+            Some("score") => self.score(),
+            Some("mcts") => {
+                self.search(number(1, 1000)?);
+                self.top(5)
+            }
+            Some("top") => self.top(number(1, 5)?),
+            Some(_) => self.play(words[0])?,
+        };
+        Ok(Some(answer))
+    }
+
+    fn show(&self) -> String {
+        GoDisplay::board(&self.state).to_screen_str()
+    }
+
+    fn set_state(&mut self, state: GoState) -> String {
+        self.state = state;
+        self.explorer = None;
+        self.show()
+    }
+
+    // GTP vertices, or the column and line letters of the displayed board
+    fn parse(&self, text: &str) -> Result<GoAction, String> {
+        let goban = self.state.gg.goban();
+        let chars = text.chars().collect::<Vec<_>>();
+        match chars.as_slice() {
+            &[x, y] if x.is_ascii_alphabetic() && y.is_ascii_lowercase() => {
+                let (x, y) = (x.to_ascii_uppercase() as usize - 'A' as usize, y as usize - 'a' as usize);
+                match x < goban.width && y < goban.height {
+                    true => Ok(GoAction::Cell(x, y)),
+                    false => Err(format!("vertex out of board: {}", text)),
+                }
+            }
+            _ => parse_vertex(text, goban.height),
+        }
+    }
+
+    fn play(&mut self, text: &str) -> Result<String, String> {
+        let action = self.parse(text)?;
+        if self.state.result().is_some() {
+            return Err(String::from("the game is over"));
+        }
+        let cell = action.cell(self.state.gg.goban());
+        if let Some(c) = cell {
+            if self.state.gg.stone_at(c) != Stone::None {
+                return Err(format!("{} is occupied", text));
+            }
+            if self.state.ko == Some(c) {
+                return Err(format!("{} retakes the ko", text));
+            }
+        }
+        if !self.state.actions().contains(&action) {
+            return Err(format!("{} is not a legal move", text));
+        }
+        let mut state = self.state.clone();
+        state.apply_action(action);
+        // the rules remove a stone without liberties at once
+        if let Some(c) = cell {
+            if state.gg.stone_at(c) == Stone::None {
+                return Err(format!("{} is a suicide", text));
+            }
+        }
+        Ok(self.set_state(state))
+    }
+
+    // the game is replayed from its setup without its last move
+    fn undo(&mut self) -> Result<String, String> {
+        let mut history = self.state.history.clone();
+        history.pop().ok_or("no move to undo")?;
+        let mut state = self.state.clone();
+        state.reset();
+        for action in history {
+            state.apply_action(action);
+        }
+        Ok(self.set_state(state))
+    }
+
+    fn groups(&self) -> String {
+        [Stone::Black, Stone::White].iter()
+            .flat_map(|&s| self.state.gg.groups_by_stone(s).iter())
+            .map(|g| format!("{} liberties: {}", GoDisplay::grouprc(&self.state, g), g.borrow().liberties))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn liberties(&self, text: &str) -> Result<String, String> {
+        let goban = self.state.gg.goban();
+        let cell = self.parse(text)?.cell(goban).ok_or("pass has no group")?;
+        let group = self.state.gg.group_at(cell);
+        if group.borrow().stone == Stone::None {
+            return Err(format!("no stone at {}", text));
+        }
+        Ok(format!("{}\n{} stones, {} liberties",
+                   GoDisplay::group_layout(&self.state, group).to_screen_str(),
+                   group.borrow().stones(), group.borrow().liberties))
+    }
+
+    fn score(&self) -> String {
+        let mut state = self.state.clone();
+        state.update_score();
+        [Stone::Black, Stone::White].iter()
+            .map(|&s| {
+                let score = state.stats.score(s);
+                format!("{}: {} territory + {} captures = {}", s, score.territory, score.captures, score.score())
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn search(&mut self, iterations: usize) {
+        let state = &self.state;
+        let explorer = self.explorer.get_or_insert_with(|| Explorer::new(1, state.clone()));
+        for _ in 0..iterations {
+            explorer.explore(&self.policy, &self.score);
+        }
+    }
+
+    // most visited moves, win rates for the side to move
+    fn top(&self, count: usize) -> String {
+        let explorer = match &self.explorer {
+            None => return String::from("no search yet: mcts <n>"),
+            Some(explorer) => explorer,
+        };
+        let mut visits = explorer.mcts().visits();
+        visits.sort_by_key(|&(_, n)| std::cmp::Reverse(n));
+        let total = explorer.mcts().root().value.borrow().tries;
+        let mut lines = vec![format!("{} iterations", total)];
+        lines.extend(visits.iter().take(count).filter(|&&(_, n)| n > 0).map(|&(action, n)| {
+            let rate = explorer.mcts().win_rate(action).unwrap_or(0.);
+            format!("{:>5} {:>6} visits {:>5.1}%", vertex(action, self.state.gg.goban().height), n, 100. * rate)
+        }));
+        lines.join("\n")
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use go_lib::board::group_access::GroupAccess;
+    use go_lib::board::stones::stone::Stone;
+    use go_lib::go_rules::go_action::GoAction;
+
+    use editor::GoEditor;
+
+    #[test]
+    fn edit_and_analyse() {
+        let mut editor = GoEditor::new(5);
+        editor.execute("C3").unwrap();
+        editor.execute("play Bb").unwrap();
+        assert_eq!(editor.state.history, vec![GoAction::Cell(2, 2), GoAction::Cell(1, 1)]);
+        assert!(editor.execute("c3").is_err());
+        assert!(editor.execute("Z9").is_err());
+        assert_eq!(editor.execute("Cc"), Err(String::from("Cc is occupied")));
+
+        let liberties = editor.execute("liberties C3").unwrap().unwrap();
+        assert!(liberties.ends_with("1 stones, 4 liberties"), "{}", liberties);
+        assert_eq!(editor.execute("groups").unwrap().unwrap().lines().count(), 2);
+        assert!(editor.execute("score").unwrap().unwrap().contains("captures"));
+
+        editor.execute("undo").unwrap();
+        assert_eq!(editor.state.history.len(), 1);
+        assert_eq!(editor.state.gg.stone_at(6), Stone::None);
+
+        assert!(editor.execute("top").unwrap().unwrap().starts_with("no search"));
+        let top = editor.execute("mcts 50").unwrap().unwrap();
+        assert!(top.starts_with("50 iterations") && top.lines().count() == 6, "{}", top);
+        editor.execute("pass").unwrap();
+        assert!(editor.execute("top").unwrap().unwrap().starts_with("no search"));
+        assert_eq!(editor.execute("quit"), Ok(None));
+    }
+
+    #[test]
+    fn illegal_moves() {
+        let mut editor = GoEditor::new(5);
+        for vertex in ["Ee", "Ba", "Ec", "Ab"].iter() {
+            editor.execute(vertex).unwrap();
+        }
+        assert_eq!(editor.execute("Aa"), Err(String::from("Aa is a suicide")));
+        assert_eq!(editor.state.history.len(), 4);
+
+        let goban = editor.state.gg.goban();
+        editor.state.ko = Some(goban.cell(3, 3));
+        assert_eq!(editor.execute("Dd"), Err(String::from("Dd retakes the ko")));
+        assert!(editor.execute("Cc").is_ok());
+    }
+
+    #[test]
+    fn sgf_files() {
+        let path = std::env::temp_dir().join(format!("go_editor_{}.sgf", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut editor = GoEditor::new(7);
+        editor.execute("D4").unwrap();
+        editor.execute(&format!("save {}", path)).unwrap();
+        editor.execute("new 9").unwrap();
+        assert_eq!(editor.state.gg.goban().width, 9);
+        editor.execute(&format!("load {}", path)).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(editor.state.history, vec![GoAction::Cell(3, 3)]);
+        assert!(editor.execute("load /no/such/file.sgf").is_err());
+    }
+}
@@ -1,5 +1,6 @@
 extern crate chrono;
 extern crate env_logger;
+extern crate go_engine;
 extern crate go_lib;
 extern crate log;
 extern crate mcts_lib;
@@ -11,16 +12,19 @@ use std::fs::File;
 use std::io::Write;
 use std::ops::Deref;
 use std::path::Path;
+use std::thread;
 
-use constants::{BENCH, GOBAN_SIZE, LOG_LEVEL, SEED, SIM_FACTOR};
+use constants::{BENCH, GOBAN_SIZE, LOG_LEVEL, SEED, SELF_PLAY_DIR, SELF_PLAY_GAMES, SELF_PLAY_LOG_LEVEL, SELF_PLAY_PLAYOUTS, SIM_FACTOR};
 use go_lib::board::go_state::GoState;
 use go_lib::board::group_access::GroupAccess;
+use go_lib::board::stats::ownership::Ownership;
 use go_lib::board::stones::stone::Stone;
 use go_lib::display::display::GoDisplay;
 use go_lib::display::goshow::GoShow;
 use go_lib::go_rules::go_action::GoAction;
 use go_lib::go_rules::go_rules::GoRules;
 use go_lib::mcts::capture_policy::CapturePolicy;
+use go_lib::mcts::self_play::SelfPlay;
 use go_lib::sgf::sgf_export::SGF;
 use mcts_lib::explorator::Explorer;
 use mcts_lib::mcts::Mcts;
@@ -36,8 +40,42 @@ use simulator::show_best_variant;
 mod editor;
 mod constants;
 mod simulator;
+mod self_play;
+mod tournament;
 
 pub fn main() {
+    // go-game selfplay [games] [workers] [dir]
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(|a| a.as_str()) == Some("selfplay") {
+        init_logs(SELF_PLAY_LOG_LEVEL);
+        let arg = |i: usize, default: usize| args.get(i).and_then(|a| a.parse().ok()).unwrap_or(default);
+        let workers = thread::available_parallelism().map_or(1, |n| n.get());
+        let dir = args.get(4).map_or(SELF_PLAY_DIR, |d| d.as_str());
+        let config = SelfPlay::new(GOBAN_SIZE, SELF_PLAY_PLAYOUTS);
+        if let Err(e) = self_play::run(&config, arg(2, SELF_PLAY_GAMES), arg(3, workers), dir) {
+            log::error!("self-play failed: {}", e);
+        }
+        return;
+    }
+    if args.get(1).map(|a| a.as_str()) == Some("match") {
+        init_logs(SELF_PLAY_LOG_LEVEL);
+        if let Err(e) = tournament::run(&args[2..]) {
+            log::error!("match failed: {}", e);
+        }
+        return;
+    }
+
+    // go-game edit [size]
+    if args.get(1).map(|a| a.as_str()) == Some("edit") {
+        init_logs(SELF_PLAY_LOG_LEVEL);
+        let size = args.get(2).and_then(|a| a.parse().ok()).unwrap_or(GOBAN_SIZE);
+        let stdin = std::io::stdin();
+        if let Err(e) = editor::GoEditor::new(size).run(stdin.lock(), &mut std::io::stdout()) {
+            log::error!("editor failed: {}", e);
+        }
+        return;
+    }
+
     init_logs(LOG_LEVEL);
     simulator::reload_sgf();
 
@@ -53,9 +91,10 @@ pub fn main() {
     );
 
     let mut stats = SimResult::new();
+    let mut ownership = Ownership::new(explorer.mcts().state().gg.goban());
     let mut bench = Bench::with_speed("Go MCTS", SIM_FACTOR as f32);
     while bench.for_iterations(100) {
-        let res = explorer.explore(&random_policy, &selection_score);
+        let res = explorer.explore_with(&random_policy, &selection_score, &mut |s| ownership.add(s));
         stats.merge(res.value.borrow().deref());
         if bench.loops % 1000 == 0 {
             explorer.mcts_mut().selection(&selection_score);
@@ -64,5 +103,6 @@ pub fn main() {
     }
     log::info!("results: {}", stats);
     show_best_variant(&mut explorer);
-    simulator::save_sgf(explorer.mcts().state())
+    simulator::save_sgf(explorer.mcts().state());
+    simulator::save_ownership(&explorer, &ownership);
 }
\ No newline at end of file
@@ -0,0 +1,63 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use go_lib::board::go_state::GoState;
+use go_lib::board::group_access::GroupAccess;
+use go_lib::export::dataset::{DatasetHeader, DatasetWriter};
+use go_lib::mcts::self_play::{SelfPlay, SelfPlayGame};
+
+use constants::SEED;
+
+// plays games on worker threads, the main thread writes one SGF per game
+// and appends the training samples to dir/samples.gosd
+pub fn run(config: &SelfPlay, games: usize, workers: usize, dir: &str) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir, e))?;
+    let goban = GoState::new(config.size).gg.goban().clone();
+    let samples_path = Path::new(dir).join("samples.gosd");
+    let mut writer = DatasetWriter::append(samples_path.to_str().unwrap(), DatasetHeader::new(&goban, &config.features))
+        .map_err(|e| format!("{:?}: {}", samples_path, e))?;
+
+    // previous runs in the same directory are kept
+    let first = fs::read_dir(dir).map_err(|e| e.to_string())?
+        .filter(|e| e.as_ref().is_ok_and(|e| e.file_name().to_string_lossy().ends_with(".sgf")))
+        .count();
+    let next_game = Arc::new(AtomicUsize::new(0));
+    let (sender, receiver) = mpsc::channel::<(usize, SelfPlayGame)>();
+    let handles = (0..workers.max(1)).map(|_| {
+        let config = config.clone();
+        let next_game = next_game.clone();
+        let sender = sender.clone();
+        thread::spawn(move || loop {
+            let index = next_game.fetch_add(1, Ordering::SeqCst);
+            if index >= games {
+                break;
+            }
+            let game = config.play(SEED + (first + index) as u64);
+            if sender.send((index, game)).is_err() {
+                break;
+            }
+        })
+    }).collect::<Vec<_>>();
+    drop(sender);
+
+    for (index, game) in receiver {
+        let sgf_path = Path::new(dir).join(format!("game_{:05}.sgf", first + index));
+        fs::write(&sgf_path, &game.sgf).map_err(|e| format!("{:?}: {}", sgf_path, e))?;
+        for sample in game.samples.iter() {
+            writer.write(sample).map_err(|e| e.to_string())?;
+        }
+        writer.flush().map_err(|e| e.to_string())?;
+        log::info!("game {}: {} moves, winner {:?}{}, {} samples",
+                   index, game.moves, game.winner,
+                   if game.resigned { " by resignation" } else { "" },
+                   writer.len);
+    }
+    for handle in handles {
+        handle.join().map_err(|_| String::from("self-play worker panicked"))?;
+    }
+    Ok(())
+}
@@ -7,8 +7,10 @@ use std::path::Path;
 
 use constants::{BENCH, GOBAN_SIZE, SIM_FACTOR};
 use go_lib::board::go_state::GoState;
+use go_lib::board::stats::ownership::Ownership;
 use go_lib::display::display::GoDisplay;
 use go_lib::display::goshow::GoShow;
+use go_lib::export::heatmap::Heatmap;
 use go_lib::go_rules::go_action::GoAction;
 use go_lib::go_rules::go_rules::GoRules;
 use mcts_lib::explorator::Explorer;
@@ -50,6 +52,12 @@ pub fn save_sgf(state: &GoState) {
     }
 }
 
+pub fn save_ownership(explorator: &Explorer<GoAction, GoState>, ownership: &Ownership) {
+    let mut root = explorator.mcts().state().clone();
+    root.reset();
+    GoDisplay::board_ownership(&root, ownership).show();
+    Heatmap::from_ownership(ownership, 16).write_image("ownership.png");
+}
 
 pub fn show_best_variant(explorator: &mut Explorer<GoAction, GoState>) {
     explorator.mcts_mut().state_mut().update_score();
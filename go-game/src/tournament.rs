@@ -0,0 +1,34 @@
+use go_engine::arena::engine::{Engine, MctsEngine};
+use go_engine::arena::gtp::GtpEngine;
+use go_engine::arena::stats::{MatchStats, Sprt};
+use go_engine::arena::tournament::Tournament;
+use mcts_lib::policy::random_policy::RandomPolicy;
+use mcts_lib::policy::win_score::WinScore;
+
+use constants::{GOBAN_SIZE, MATCH_DIR, MATCH_KOMI, SEED};
+
+// go-game match <games> <playouts a> <playouts b | gtp command...>
+pub fn run(args: &[String]) -> Result<MatchStats, String> {
+    let number = |i: usize, default: usize| args.get(i).and_then(|a| a.parse().ok()).unwrap_or(default);
+    let playouts = number(1, 100);
+    let mut a = MctsEngine::new(&format!("mcts-{}", playouts), RandomPolicy::new(SEED), WinScore::new(), playouts);
+    let mut b: Box<dyn Engine> = match args.get(2) {
+        Some(arg) if arg.parse::<usize>().is_err() => {
+            let program = args[2..].iter().map(|a| a.as_str()).collect::<Vec<_>>();
+            Box::new(GtpEngine::spawn(program[0], &program[1..])?.with_komi(MATCH_KOMI))
+        }
+        _ => {
+            let playouts = number(2, 100);
+            Box::new(MctsEngine::new(&format!("mcts-{}", playouts), RandomPolicy::new(SEED + 1), WinScore::new(), playouts))
+        }
+    };
+
+    let tournament = Tournament {
+        sprt: Some(Sprt::new(0., 50.)),
+        sgf_dir: Some(MATCH_DIR.to_string()),
+        ..Tournament::new(GOBAN_SIZE, number(0, 100))
+    };
+    let stats = tournament.run(&mut a, b.as_mut())?;
+    log::info!("{} vs {}: {}", a.name(), b.name(), stats);
+    Ok(stats)
+}
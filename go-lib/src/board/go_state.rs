@@ -28,20 +28,53 @@ use graph_lib::topology::Topology;
 use mcts_lib::rules::{GameResult, Rules};
 use rust_tools::screen::layout::layout::{L, Layout, LayoutRc};
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct GoState {
     pub current_side: Stone,
     pub pass_sequence: usize,
     pub ko: Option<GoCell>,
     pub stats: BoardStats,
     pub history: Vec<GoAction>,
+    //setup position
+    pub start_side: Stone,
+    pub setup: Vec<(GoAction, Stone)>,
     //stones
     pub gg: BoardGroups,
 }
 
+impl Clone for GoState {
+    fn clone(&self) -> Self {
+        GoState {
+            current_side: self.current_side,
+            pass_sequence: self.pass_sequence,
+            ko: self.ko,
+            stats: self.stats,
+            history: self.history.clone(),
+            start_side: self.start_side,
+            setup: self.setup.clone(),
+            gg: self.gg.clone(),
+        }
+    }
+
+    // resets a position in place (MCTS selection), reusing the buffers of self
+    fn clone_from(&mut self, source: &Self) {
+        self.current_side = source.current_side;
+        self.pass_sequence = source.pass_sequence;
+        self.ko = source.ko;
+        self.stats = source.stats;
+        self.history.clone_from(&source.history);
+        self.start_side = source.start_side;
+        self.setup.clone_from(&source.setup);
+        self.gg.clone_from(&source.gg);
+    }
+}
+
 impl GoState {
     pub fn new(size: usize) -> Self {
-        let goban = Grid::new(size);
+        GoState::from_goban(Grid::new(size))
+    }
+
+    pub fn from_goban(goban: Grid) -> Self {
         let stats = BoardStats::new(&goban);
         let mut board = GoState {
             current_side: Stone::Black,
@@ -49,6 +82,8 @@ impl GoState {
             ko: None,
             stats,
             history: vec![],
+            start_side: Stone::Black,
+            setup: vec![],
 
             gg: BoardGroups::new(goban),
         };
@@ -98,3 +133,113 @@ impl GoState {
         // self.stats.assert_eq(&BoardStats::from_board(self));
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use bit_set::BitSet;
+
+    use board::go_state::GoState;
+    use board::grid::Grid;
+    use board::group_access::GroupAccess;
+    use board::stones::stone::Stone;
+    use go_rules::go_action::GoAction;
+    use graph_lib::topology::Topology;
+    use mcts_lib::rules::Rules;
+
+    #[test]
+    fn torus_capture() {
+        let mut state = GoState::from_goban(Grid::torus(5, 5));
+        for &(x, y) in [(0, 0), (4, 0), (2, 2), (1, 0), (3, 3), (0, 4), (2, 3), (0, 1)].iter() {
+            state.apply_action(GoAction::Cell(x, y));
+        }
+
+        let cell = state.gg.goban().cell(0, 0);
+        assert_eq!(state.gg.stone_at(cell), Stone::None);
+        assert_eq!(state.stats(Stone::Black).captured, 1);
+    }
+
+    #[test]
+    fn clone_is_deep() {
+        let mut state = GoState::new(5);
+        state.apply_action(GoAction::Cell(1, 1));
+        let mut copy = state.clone();
+        copy.apply_action(GoAction::Cell(2, 1));
+        copy.apply_action(GoAction::Cell(1, 2));
+
+        assert_eq!(state.stats(Stone::Black).stones, 1);
+        assert_eq!(state.stats(Stone::White).stones, 0);
+        assert_eq!(state.gg.stone_at(state.gg.goban().cell(1, 2)), Stone::None);
+        assert_eq!(state.gg.group_at(state.gg.goban().cell(1, 1)).borrow().stones(), 1);
+        assert_eq!(copy.gg.group_at(copy.gg.goban().cell(1, 1)).borrow().stones(), 2);
+    }
+
+    // plays the first legal move until the end, then checks the groups against the topology
+    fn play_out(mut state: GoState) -> GoState {
+        let max_moves = 10 * state.gg.goban().vertex_number();
+        while state.result().is_none() {
+            assert!(state.history.len() < max_moves, "the game does not end");
+            let action = state.actions()[0];
+            state.apply_action(action);
+        }
+
+        let goban = state.gg.goban();
+        for &stone in [Stone::Black, Stone::White].iter() {
+            let played = state.history.iter().enumerate()
+                .filter(|&(i, &a)| a != GoAction::Pass && (i % 2 == 0) == (stone == Stone::Black))
+                .count();
+            let stats = state.stats(stone);
+            assert_eq!(stats.stones + stats.captured, played);
+
+            for group in state.gg.groups_by_stone(stone) {
+                let group = group.borrow();
+                let liberties = group.cells.iter()
+                    .flat_map(|c| goban.edges(c).iter())
+                    .filter(|&c| state.gg.stone_at(c) == Stone::None)
+                    .collect::<BitSet>();
+                assert!(group.cells.iter().all(|c| state.gg.stone_at(c) == stone));
+                assert_eq!(group.liberties, liberties.len());
+                assert!(group.liberties > 0);
+            }
+        }
+        state
+    }
+
+    #[test]
+    fn clone_from_replaces_the_position() {
+        let mut source = GoState::new(5);
+        source.apply_action(GoAction::Cell(1, 1));
+        let mut state = GoState::new(5);
+        for &(x, y) in [(2, 2), (3, 3), (2, 3)].iter() {
+            state.apply_action(GoAction::Cell(x, y));
+        }
+        state.clone_from(&source);
+        assert_eq!(state.history, source.history);
+        assert_eq!(state.stats(Stone::Black).stones, 1);
+        assert_eq!(state.stats(Stone::White).stones, 0);
+        assert_eq!(state.gg.stone_at(state.gg.goban().cell(2, 2)), Stone::None);
+
+        // the groups are not shared with the source
+        state.apply_action(GoAction::Cell(1, 2));
+        state.apply_action(GoAction::Cell(2, 1));
+        assert_eq!(source.gg.group_at(source.gg.goban().cell(1, 1)).borrow().liberties, 4);
+        assert_eq!(state.gg.group_at(state.gg.goban().cell(1, 1)).borrow().stones(), 2);
+    }
+
+    #[test]
+    fn rect_board_game() {
+        let state = play_out(GoState::from_goban(Grid::rect(7, 3)));
+        assert_eq!(state.gg.goban().vertex_number(), 21);
+        assert!(state.stats(Stone::Black).stones + state.stats(Stone::White).stones > 0);
+    }
+
+    #[test]
+    fn graph_board_game() {
+        let edges = (0..7).map(|i| (i, (i + 1) % 7)).collect::<Vec<_>>();
+        let state = play_out(GoState::from_goban(Grid::from_edges(7, &edges)));
+        // a ring: every stone has at most two neighbours
+        let goban = state.gg.goban();
+        goban.apply(|c| assert_eq!(goban.edges(c).len(), 2));
+        assert!(state.stats(Stone::Black).stones + state.stats(Stone::White).stones > 0);
+    }
+}
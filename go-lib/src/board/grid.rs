@@ -1,84 +1,134 @@
 use std::hash::Hash;
 use std::iter::FromIterator;
+use std::sync::Arc;
 
 use bit_set::BitSet;
 
 use graph_lib::topology::Topology;
+use rust_tools::screen::dimension::Dimension;
+use rust_tools::screen::smart_index::SmartIndex;
 
 pub type GoCell = usize;
 
 #[derive(Debug, Clone)]
 pub struct Grid {
-    pub size: usize,
+    pub width: usize,
+    pub height: usize,
     cells: BitSet,
-    links: Vec<BitSet>,
+    // never changes once built: copies of the grid share it
+    links: Arc<Vec<BitSet>>,
 }
 
 
 impl Grid {
     pub fn new(size: usize) -> Self {
-        let cells = 0..(size * size);
+        Grid::rect(size, size)
+    }
+
+    pub fn rect(width: usize, height: usize) -> Self {
+        let mut res = Grid::layout(width, height, width * height);
+        res.links = Arc::new(res.cells.iter().map(|c| res.rect_links(c)).collect());
+        res
+    }
+
+    pub fn torus(width: usize, height: usize) -> Self {
+        let mut res = Grid::layout(width, height, width * height);
+        res.links = Arc::new(res.cells.iter().map(|c| res.torus_links(c)).collect());
+        res
+    }
+
+    // vertices are displayed row by row, in a square-ish layout
+    pub fn from_edges(vertex_number: usize, edges: &[(GoCell, GoCell)]) -> Self {
+        let width = (vertex_number as f32).sqrt().ceil().max(1.) as usize;
+        let height = vertex_number.div_ceil(width);
+        let mut res = Grid::layout(width, height, vertex_number);
+        let mut links = vec![BitSet::new(); vertex_number];
+        for &(a, b) in edges {
+            assert!(a < vertex_number && b < vertex_number, "edge ({}, {}) out of board", a, b);
+            if a != b {
+                links[a].insert(b);
+                links[b].insert(a);
+            }
+        }
+        res.links = Arc::new(links);
+        res
+    }
+
+    pub fn from_topology<T: Topology>(graph: &T) -> Self {
+        let vertices = graph.vertices().iter().collect::<Vec<_>>();
+        let index = |v: usize| vertices.binary_search(&v).ok();
+        let mut edges = vec![];
+        for (a, &v) in vertices.iter().enumerate() {
+            for b in graph.edges(v).iter().filter_map(index) {
+                edges.push((a, b));
+            }
+        }
+        Grid::from_edges(vertices.len(), &edges)
+    }
+
+    fn layout(width: usize, height: usize, vertex_number: usize) -> Self {
+        assert!(vertex_number <= width * height);
         Grid {
-            size,
-            cells: BitSet::from_iter(cells.clone()),
-            links: cells.clone()
-                .map(|c| Grid::links(size, c))
-                .collect(),
+            width,
+            height,
+            cells: BitSet::from_iter(0..vertex_number),
+            links: Arc::new(vec![BitSet::new(); vertex_number]),
         }
     }
 
     pub fn get_liberties(&self, cell: GoCell) -> usize {
-        let (x, y) = self.xy(cell);
-        let horizontal_border = x == 0 || x == self.size - 1;
-        let vertical_border = y == 0 || y == self.size - 1;
-        if horizontal_border && vertical_border {
-            2
-        } else if horizontal_border || vertical_border {
-            3
-        } else {
-            4
-        }
+        self.links[cell].len()
     }
 
     pub fn cell(&self, x: usize, y: usize) -> GoCell {
-        x + y * self.size
+        x + y * self.width
     }
 
     pub fn xy(&self, cell: GoCell) -> (usize, usize) {
-        let x = cell as usize % self.size;
-        let y = cell as usize / self.size;
+        let x = cell as usize % self.width;
+        let y = cell as usize / self.width;
         (x, y)
     }
 
-    fn links(size: usize, cell: GoCell) -> BitSet {
-        let size = size as i32;
-        let limit = (size * size) as i32;
-        let c = cell as i32;
+    pub fn is_square(&self) -> bool {
+        self.width == self.height
+    }
 
-        let same_line1 = |x: i32| (x % size - c % size).abs() <= 1;
-        let in_board = |x: i32| x >= 0 && x < limit;
+    fn rect_links(&self, cell: GoCell) -> BitSet {
+        let (x, y) = self.xy(cell);
+        let (x, y) = (x as i32, y as i32);
+        let (w, h) = (self.width as i32, self.height as i32);
 
-        let res = BitSet::from_iter([c - 1, c + 1, c - size, c + size].iter()
-            .filter(|&x| in_board(*x))
-            .filter(|&x| same_line1(*x))
-            .map(|&x| x as usize));
+        let res = BitSet::from_iter([(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)].iter()
+            .filter(|&&(x, y)| x >= 0 && x < w && y >= 0 && y < h)
+            .map(|&(x, y)| self.cell(x as usize, y as usize)));
 
         assert!(res.len() <= 4);
         res
     }
 
-    fn diagonals(size: usize, cell: GoCell) -> BitSet {
-        let size = size as i32;
-        let limit = (size * size) as i32;
-        let c = cell as i32;
+    fn torus_links(&self, cell: GoCell) -> BitSet {
+        let (x, y) = self.xy(cell);
+        let (x, y) = (x as i32, y as i32);
 
-        let same_line1 = |x: i32| (x % size - c % size).abs() <= 1;
-        let in_board = |x: i32| x >= 0 && x < limit;
+        let mut res = BitSet::from_iter([(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)].iter()
+            .map(|&(x, y)| self.index(x, y)));
+        res.remove(cell);
 
-        let res = BitSet::from_iter([c - 1 - size, c + 1 - size, c - 1 + size, c + 1 + size].iter()
-            .filter(|&x| in_board(*x))
-            .filter(|&x| same_line1(*x))
-            .map(|&x| x as usize));
+        assert!(res.len() <= 4);
+        res
+    }
+
+    // corners shared with two linked neighbours: wraps on a torus, stops at the edges of a rect board
+    fn diagonals(&self, cell: GoCell) -> BitSet {
+        let (x, y) = self.xy(cell);
+        let (x, y) = (x as i32, y as i32);
+        let linked = |a: GoCell, b: GoCell| a < self.links.len() && self.links[a].contains(b);
+
+        let res = BitSet::from_iter([(-1, -1), (1, -1), (-1, 1), (1, 1)].iter()
+            .map(|&(dx, dy)| (self.index(x + dx, y), self.index(x, y + dy), self.index(x + dx, y + dy)))
+            .filter(|&(h, v, d)| linked(cell, h) && linked(h, d) && linked(cell, v) && linked(v, d))
+            .map(|(_, _, d)| d));
         assert!(res.len() <= 4);
         res
     }
@@ -95,9 +145,20 @@ impl Topology for Grid {
     }
 }
 
+impl Dimension for Grid {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
+    use std::iter::FromIterator;
     use std::ops::Deref;
     use std::sync::Arc;
 
@@ -106,7 +167,7 @@ mod tests {
     use board::grid::Grid;
     use graph_lib::algo::flood::Flood;
     use graph_lib::graph::GFlood;
-    use graph_lib::topology::Topology;
+    use graph_lib::topology::{SubGraph, Topology};
 
     use crate::board::go_state::GoState;
 
@@ -125,4 +186,60 @@ mod tests {
             assert_eq!(y, y2);
         });
     }
+
+    #[test]
+    fn rect_liberties() {
+        let goban = Grid::rect(5, 3);
+
+        assert_eq!(goban.vertex_number(), 15);
+        assert_eq!(goban.get_liberties(goban.cell(0, 0)), 2);
+        assert_eq!(goban.get_liberties(goban.cell(4, 2)), 2);
+        assert_eq!(goban.get_liberties(goban.cell(2, 0)), 3);
+        assert_eq!(goban.get_liberties(goban.cell(4, 1)), 3);
+        assert_eq!(goban.get_liberties(goban.cell(2, 1)), 4);
+    }
+
+    #[test]
+    fn torus_liberties() {
+        let goban = Grid::torus(5, 4);
+
+        goban.apply(|c| assert_eq!(goban.get_liberties(c), 4));
+        assert!(goban.edges(goban.cell(0, 0)).contains(goban.cell(4, 0)));
+        assert!(goban.edges(goban.cell(0, 0)).contains(goban.cell(0, 3)));
+    }
+
+    #[test]
+    fn diagonals() {
+        let rect = Grid::rect(5, 4);
+        assert_eq!(rect.diagonals(rect.cell(0, 0)), BitSet::from_iter(vec![rect.cell(1, 1)]));
+        assert_eq!(rect.diagonals(rect.cell(2, 1)).len(), 4);
+
+        let torus = Grid::torus(5, 4);
+        let corners = [(4, 3), (1, 3), (4, 1), (1, 1)].iter().map(|&(x, y)| torus.cell(x, y));
+        assert_eq!(torus.diagonals(torus.cell(0, 0)), BitSet::from_iter(corners));
+    }
+
+    #[test]
+    fn graph_board() {
+        // a ring of 6 vertices
+        let edges = (0..6).map(|i| (i, (i + 1) % 6)).collect::<Vec<_>>();
+        let goban = Grid::from_edges(6, &edges);
+
+        assert_eq!(goban.vertex_number(), 6);
+        assert!(goban.width * goban.height >= 6);
+        goban.apply(|c| assert_eq!(goban.get_liberties(c), 2));
+    }
+
+    #[test]
+    fn sub_graph_board() {
+        let full = Grid::new(4);
+        let corner = SubGraph::from(&full, &|c| {
+            let (x, y) = full.xy(c);
+            x < 2 && y < 2
+        });
+        let goban = Grid::from_topology(&corner);
+
+        assert_eq!(goban.vertex_number(), 4);
+        goban.apply(|c| assert_eq!(goban.get_liberties(c), 2));
+    }
 }
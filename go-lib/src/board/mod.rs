@@ -4,3 +4,4 @@ pub mod stats;
 pub mod stones;
 pub mod group_access;
 pub mod group_manipulation;
+pub mod symmetry;
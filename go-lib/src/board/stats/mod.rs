@@ -1,3 +1,4 @@
 pub mod full_stats;
 pub mod stone_stats;
-pub mod stone_score;
\ No newline at end of file
+pub mod stone_score;
+pub mod ownership;
@@ -0,0 +1,181 @@
+use bit_set::BitSet;
+
+use board::go_state::GoState;
+use board::grid::{GoCell, Grid};
+use board::group_access::GroupAccess;
+use board::stones::stone::Stone;
+use go_rules::go::Go;
+use graph_lib::topology::Topology;
+
+// per cell owner frequencies, gathered from final positions (e.g. MCTS simulations)
+#[derive(Debug, Clone)]
+pub struct Ownership {
+    pub goban: Grid,
+    pub samples: usize,
+    black: Vec<usize>,
+    white: Vec<usize>,
+}
+
+impl Ownership {
+    pub fn new(goban: &Grid) -> Self {
+        let size = goban.vertex_number();
+        Ownership {
+            goban: goban.clone(),
+            samples: 0,
+            black: vec![0; size],
+            white: vec![0; size],
+        }
+    }
+
+    // stones belong to their color, empty areas to the only color surrounding them
+    pub fn owner(state: &GoState, cell: GoCell) -> Stone {
+        match state.gg.stone_at(cell) {
+            Stone::None => Go::new(&state.gg).get_owner(state.gg.group_at(cell).clone()),
+            stone => stone
+        }
+    }
+
+    pub fn add(&mut self, state: &GoState) {
+        assert_eq!(state.gg.goban().vertex_number(), self.black.len());
+        for c in state.gg.goban().vertices().iter() {
+            match Ownership::owner(state, c) {
+                Stone::Black => self.black[c] += 1,
+                Stone::White => self.white[c] += 1,
+                Stone::None => {}
+            }
+        }
+        self.samples += 1;
+    }
+
+    pub fn probability(&self, cell: GoCell, stone: Stone) -> f32 {
+        let count = match stone {
+            Stone::Black => self.black[cell],
+            Stone::White => self.white[cell],
+            Stone::None => self.samples - self.black[cell] - self.white[cell],
+        };
+        count as f32 / self.samples.max(1) as f32
+    }
+
+    // in [-1, 1]: positive for black, negative for white
+    pub fn balance(&self, cell: GoCell) -> f32 {
+        self.probability(cell, Stone::Black) - self.probability(cell, Stone::White)
+    }
+
+    pub fn values(&self) -> Vec<f32> {
+        (0..self.black.len()).map(|c| self.balance(c)).collect()
+    }
+
+    pub fn territory(&self, stone: Stone) -> usize {
+        (0..self.black.len())
+            .filter(|&c| self.probability(c, stone) > 0.5)
+            .count()
+    }
+}
+
+// static estimate: every stone radiates an influence halved at each step
+#[derive(Debug, Clone)]
+pub struct Influence {
+    pub values: Vec<f32>,
+}
+
+impl Influence {
+    const DISTANCE: usize = 4;
+    const THRESHOLD: f32 = 0.5;
+
+    pub fn new(state: &GoState) -> Self {
+        let goban = state.gg.goban();
+        let mut values = vec![0.; goban.vertex_number()];
+        for c in goban.vertices().iter() {
+            let sign = match state.gg.stone_at(c) {
+                Stone::Black => 1.,
+                Stone::White => -1.,
+                Stone::None => continue,
+            };
+            let mut visited = BitSet::new();
+            let mut front = BitSet::new();
+            visited.insert(c);
+            front.insert(c);
+            let mut power = 2.;
+            for _ in 0..=Influence::DISTANCE {
+                let mut next = BitSet::new();
+                for x in front.iter() {
+                    values[x] += sign * power;
+                    next.union_with(goban.edges(x));
+                }
+                next.difference_with(&visited);
+                // influence does not go through stones
+                next = next.iter().filter(|&x| state.gg.stone_at(x) == Stone::None).collect();
+                visited.union_with(&next);
+                front = next;
+                power /= 2.;
+            }
+        }
+        Influence { values }
+    }
+
+    pub fn owner(&self, cell: GoCell) -> Stone {
+        match self.values[cell] {
+            v if v > Influence::THRESHOLD => Stone::Black,
+            v if v < -Influence::THRESHOLD => Stone::White,
+            _ => Stone::None
+        }
+    }
+
+    pub fn territory(&self, state: &GoState, stone: Stone) -> usize {
+        (0..self.values.len())
+            .filter(|&c| state.gg.stone_at(c) == Stone::None && self.owner(c) == stone)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use board::go_state::GoState;
+    use board::group_access::GroupAccess;
+    use board::stats::ownership::{Influence, Ownership};
+    use board::stones::stone::Stone;
+    use display::display::GoDisplay;
+    use display::goshow::GoShow;
+    use go_rules::go_action::GoAction;
+    use mcts_lib::rules::Rules;
+
+    #[test]
+    fn ownership_frequencies() {
+        let mut state = GoState::new(5);
+        let mut ownership = Ownership::new(state.gg.goban());
+        ownership.add(&state);
+
+        // black and white walls on the b and d columns, c is neutral
+        for y in 0..5 {
+            state.apply_action(GoAction::Cell(1, y));
+            state.apply_action(GoAction::Cell(3, y));
+        }
+        ownership.add(&state);
+
+        let goban = state.gg.goban();
+        assert_eq!(ownership.samples, 2);
+        assert_eq!(ownership.probability(goban.cell(0, 2), Stone::Black), 0.5);
+        assert_eq!(ownership.probability(goban.cell(0, 2), Stone::None), 0.5);
+        assert_eq!(ownership.balance(goban.cell(4, 0)), -0.5);
+        // neutral points are owned by nobody
+        assert_eq!(ownership.probability(goban.cell(2, 2), Stone::None), 1.);
+        assert_eq!(ownership.territory(Stone::Black), 0);
+
+        let text = GoDisplay::board_ownership(&state, &ownership).to_screen_str();
+        assert!(text.contains("+50") && text.contains("-50"));
+    }
+
+    #[test]
+    fn influence() {
+        let mut state = GoState::new(9);
+        state.apply_action(GoAction::Cell(2, 2));
+        state.apply_action(GoAction::Cell(6, 6));
+        let influence = Influence::new(&state);
+        let goban = state.gg.goban();
+
+        assert_eq!(influence.owner(goban.cell(2, 3)), Stone::Black);
+        assert_eq!(influence.owner(goban.cell(6, 5)), Stone::White);
+        assert_eq!(influence.owner(goban.cell(4, 4)), Stone::None);
+        assert_eq!(influence.territory(&state, Stone::Black), influence.territory(&state, Stone::White));
+    }
+}
@@ -25,7 +25,7 @@ use rust_tools::screen::layout::layout::L;
 
 use crate::board::group_manipulation::GroupManipulation;
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct BoardGroups {
     id_gen: usize,
     goban: Grid,
@@ -134,6 +134,43 @@ impl BoardGroups {
     }
 }
 
+// groups are shared (Rc) between cells: a copy must not alias the original groups
+impl Clone for BoardGroups {
+    fn clone(&self) -> Self {
+        let mut res = BoardGroups {
+            id_gen: 0,
+            goban: self.goban.clone(),
+            groups: vec![],
+            blacks: IndexSet::new(),
+            whites: IndexSet::new(),
+            nones: IndexSet::new(),
+            empty_cells: BitSet::new(),
+        };
+        res.clone_from(self);
+        res
+    }
+
+    // keeps the allocations of self, only the groups themselves are copied
+    fn clone_from(&mut self, source: &Self) {
+        let mut copies: HashMap<usize, GoGroupRc> = HashMap::with_capacity(source.groups.len());
+        let mut copy = |g: &GoGroupRc| copies
+            .entry(g.borrow().id)
+            .or_insert_with(|| g.deep_clone())
+            .clone();
+        self.id_gen = source.id_gen;
+        self.goban.clone_from(&source.goban);
+        self.groups.clear();
+        self.groups.extend(source.groups.iter().map(&mut copy));
+        self.blacks.clear();
+        self.blacks.extend(source.blacks.iter().map(&mut copy));
+        self.whites.clear();
+        self.whites.extend(source.whites.iter().map(&mut copy));
+        self.nones.clear();
+        self.nones.extend(source.nones.iter().map(&mut copy));
+        self.empty_cells.clone_from(&source.empty_cells);
+    }
+}
+
 impl GroupManipulation for BoardGroups {
     fn place_stone(&mut self, cell: GoCell, stone: Stone) -> GoGroupRc {
         assert_eq!(self.stone_at(cell), Stone::None);
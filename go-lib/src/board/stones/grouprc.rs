@@ -19,6 +19,10 @@ impl GoGroupRc {
         GoGroupRc(Rc::clone(&self.0))
     }
 
+    pub fn deep_clone(&self) -> Self {
+        GoGroupRc::from(self.borrow().clone())
+    }
+
     pub fn borrow(&self) -> Ref<GoGroup> {
         self.0.borrow()
     }
@@ -0,0 +1,328 @@
+use board::go_state::GoState;
+use board::grid::{GoCell, Grid};
+use board::group_access::GroupAccess;
+use board::stones::stone::Stone;
+use go_rules::go_action::GoAction;
+use graph_lib::topology::Topology;
+use mcts_lib::rules::Rules;
+
+// the 8 transforms of the square, coordinates are (x, y) with y going down
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipX,
+    FlipY,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipX,
+        Symmetry::FlipY,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    // a rectangular board only keeps the transforms not swapping its axes
+    pub fn all(width: usize, height: usize) -> Vec<Symmetry> {
+        Symmetry::ALL.iter()
+            .cloned()
+            .filter(|s| width == height || !s.swaps_axes())
+            .collect()
+    }
+
+    pub fn swaps_axes(&self) -> bool {
+        matches!(self, Symmetry::Rotate90 | Symmetry::Rotate270 | Symmetry::Transpose | Symmetry::AntiTranspose)
+    }
+
+    pub fn inverse(&self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            s => *s
+        }
+    }
+
+    // dimensions of the transformed board
+    pub fn dims(&self, width: usize, height: usize) -> (usize, usize) {
+        match self.swaps_axes() {
+            true => (height, width),
+            false => (width, height)
+        }
+    }
+
+    // width and height are the ones of the board before the transform
+    pub fn apply(&self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        let (right, bottom) = (width - 1, height - 1);
+        match self {
+            Symmetry::Identity => (x, y),
+            Symmetry::Rotate90 => (bottom - y, x),
+            Symmetry::Rotate180 => (right - x, bottom - y),
+            Symmetry::Rotate270 => (y, right - x),
+            Symmetry::FlipX => (right - x, y),
+            Symmetry::FlipY => (x, bottom - y),
+            Symmetry::Transpose => (y, x),
+            Symmetry::AntiTranspose => (bottom - y, right - x),
+        }
+    }
+
+    pub fn action(&self, action: GoAction, width: usize, height: usize) -> GoAction {
+        match action {
+            GoAction::Pass => GoAction::Pass,
+            GoAction::Cell(x, y) => {
+                let (x, y) = self.apply(x, y, width, height);
+                GoAction::Cell(x, y)
+            }
+        }
+    }
+
+    // cell of the transformed board
+    pub fn cell(&self, cell: GoCell, goban: &Grid) -> GoCell {
+        let (x, y) = goban.xy(cell);
+        let (x, y) = self.apply(x, y, goban.width, goban.height);
+        x + y * self.dims(goban.width, goban.height).0
+    }
+}
+
+// a board symmetry, optionally swapping the colours of the stones and of the side to move
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Transform {
+    pub symmetry: Symmetry,
+    pub invert: bool,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform { symmetry: Symmetry::Identity, invert: false };
+
+    pub fn new(symmetry: Symmetry, invert: bool) -> Self {
+        Transform { symmetry, invert }
+    }
+
+    pub fn all(goban: &Grid, colours: bool) -> Vec<Transform> {
+        let inverts: &[bool] = if colours { &[false, true] } else { &[false] };
+        inverts.iter()
+            .flat_map(|&invert| goban.symmetries().into_iter().map(move |s| Transform::new(s, invert)))
+            .collect()
+    }
+
+    pub fn inverse(&self) -> Transform {
+        Transform::new(self.symmetry.inverse(), self.invert)
+    }
+
+    pub fn stone(&self, stone: Stone) -> Stone {
+        match self.invert {
+            true => stone.switch(),
+            false => stone
+        }
+    }
+}
+
+// what a move can depend on: the stones in cell order, the side to move and the ko
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PositionKey {
+    pub width: usize,
+    pub height: usize,
+    pub stones: Vec<Stone>,
+    pub side: Stone,
+    pub ko: Option<GoCell>,
+}
+
+impl PositionKey {
+    // FNV-1a, stable across builds: hashes may be stored on disk
+    pub fn hash_value(&self) -> u64 {
+        let values = [self.width, self.height, self.side as usize, self.ko.map_or(usize::MAX, |c| c)];
+        values.iter().cloned()
+            .chain(self.stones.iter().map(|&s| s as usize))
+            .flat_map(|v| (v as u64).to_le_bytes().to_vec())
+            .fold(0xcbf29ce484222325, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+    }
+}
+
+impl Grid {
+    // transforms mapping the board onto itself, only the identity for a graph board
+    pub fn symmetries(&self) -> Vec<Symmetry> {
+        match self.is_rect() || self.is_torus() {
+            true => Symmetry::all(self.width, self.height),
+            false => vec![Symmetry::Identity]
+        }
+    }
+
+    pub fn transform(&self, symmetry: Symmetry) -> Grid {
+        let (width, height) = symmetry.dims(self.width, self.height);
+        match (symmetry, self.is_torus()) {
+            (Symmetry::Identity, _) => self.clone(),
+            (_, true) => Grid::torus(width, height),
+            _ => {
+                assert!(self.is_rect(), "{:?} does not map a graph board", symmetry);
+                Grid::rect(width, height)
+            }
+        }
+    }
+
+    fn is_rect(&self) -> bool {
+        self.same_links(&Grid::rect(self.width, self.height))
+    }
+
+    fn is_torus(&self) -> bool {
+        self.same_links(&Grid::torus(self.width, self.height))
+    }
+
+    fn same_links(&self, other: &Grid) -> bool {
+        self.vertex_number() == other.vertex_number()
+            && self.vertices().iter().all(|c| self.edges(c) == other.edges(c))
+    }
+}
+
+impl GoState {
+    // the same game played on the transformed board: setup and moves are replayed
+    pub fn transform(&self, t: Transform) -> GoState {
+        let goban = self.gg.goban();
+        let (width, height) = (goban.width, goban.height);
+        let mut res = GoState::from_goban(goban.transform(t.symmetry));
+        for &(action, stone) in self.setup.iter() {
+            if let GoAction::Cell(x, y) = t.symmetry.action(action, width, height) {
+                let cell = res.gg.goban().cell(x, y);
+                res.setup_stone(cell, t.stone(stone)).expect("a symmetric setup is valid");
+            }
+        }
+        if t.stone(self.start_side) != res.start_side {
+            res.set_side(t.stone(self.start_side));
+        }
+        for &action in self.history.iter() {
+            res.apply_action(t.symmetry.action(action, width, height));
+        }
+        res
+    }
+
+    pub fn position_key(&self, t: Transform) -> PositionKey {
+        let goban = self.gg.goban();
+        let (width, height) = t.symmetry.dims(goban.width, goban.height);
+        let mut stones = vec![Stone::None; goban.vertex_number()];
+        for cell in goban.vertices().iter() {
+            stones[t.symmetry.cell(cell, goban)] = t.stone(self.gg.stone_at(cell));
+        }
+        PositionKey {
+            width,
+            height,
+            stones,
+            side: t.stone(self.current_side),
+            ko: self.ko.map(|c| t.symmetry.cell(c, goban)),
+        }
+    }
+
+    pub fn position_hash(&self) -> u64 {
+        self.position_key(Transform::IDENTITY).hash_value()
+    }
+
+    // transform leading to the smallest key among the equivalent positions,
+    // the hash of that key is shared by all of them
+    pub fn canonical(&self, colours: bool) -> (Transform, u64) {
+        let (transforms, hash) = self.canonical_transforms(colours);
+        (transforms[0], hash)
+    }
+
+    // every transform leading to the canonical key: more than one for a symmetric position
+    pub fn canonical_transforms(&self, colours: bool) -> (Vec<Transform>, u64) {
+        let keys: Vec<_> = Transform::all(self.gg.goban(), colours).into_iter()
+            .map(|t| (t, self.position_key(t)))
+            .collect();
+        let min = keys.iter().map(|(_, key)| key).min().unwrap();
+        let transforms = keys.iter().filter(|(_, key)| key == min).map(|&(t, _)| t).collect();
+        (transforms, min.hash_value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use board::go_state::GoState;
+    use board::grid::Grid;
+    use board::group_access::GroupAccess;
+    use board::stones::stone::Stone;
+    use board::symmetry::{Symmetry, Transform};
+    use go_rules::go_action::GoAction;
+    use mcts_lib::rules::Rules;
+
+    fn game(goban: Grid, moves: &[(usize, usize)]) -> GoState {
+        let mut state = GoState::from_goban(goban);
+        for &(x, y) in moves {
+            state.apply_action(GoAction::Cell(x, y));
+        }
+        state
+    }
+
+    #[test]
+    fn inverse_round_trip() {
+        for s in Symmetry::ALL.iter() {
+            for &(x, y) in [(0, 0), (1, 3), (4, 2)].iter() {
+                let (tx, ty) = s.apply(x, y, 5, 5);
+                assert_eq!(s.inverse().apply(tx, ty, 5, 5), (x, y), "{:?}", s);
+            }
+        }
+        let images: HashSet<_> = Symmetry::ALL.iter().map(|s| s.action(GoAction::Cell(1, 0), 5, 5)).collect();
+        assert_eq!(images.len(), 8);
+        assert_eq!(Symmetry::Rotate90.action(GoAction::Pass, 5, 5), GoAction::Pass);
+        assert_eq!(Symmetry::all(5, 3).len(), 4);
+
+        let goban = Grid::rect(4, 3);
+        assert_eq!(Symmetry::Transpose.cell(goban.cell(3, 1), &goban), 1 + 3 * 3);
+        assert_eq!(goban.symmetries().len(), 4);
+        assert_eq!(Grid::torus(5, 5).symmetries().len(), 8);
+        let ring: Vec<_> = (0..6).map(|i| (i, (i + 1) % 6)).collect();
+        assert_eq!(Grid::from_edges(6, &ring).symmetries(), vec![Symmetry::Identity]);
+    }
+
+    #[test]
+    fn transformed_games() {
+        // white captures the black stone at (0, 0)
+        let state = game(Grid::rect(5, 4), &[(0, 0), (1, 0), (3, 3), (0, 1), (4, 2)]);
+        for t in Transform::all(state.gg.goban(), true) {
+            let res = state.transform(t);
+            assert_eq!(res.position_key(Transform::IDENTITY), state.position_key(t), "{:?}", t);
+            assert_eq!(res.stats(t.stone(Stone::Black)).captured, 1);
+            assert_eq!(res.transform(t.inverse()).position_hash(), state.position_hash());
+        }
+
+        let mut handicap = GoState::new(5);
+        handicap.free_handicap(&[0, 24]);
+        handicap.apply_action(GoAction::Cell(2, 2));
+        let inverted = handicap.transform(Transform::new(Symmetry::FlipX, true));
+        assert_eq!(inverted.start_side, Stone::Black);
+        assert_eq!(inverted.gg.stone_at(4), Stone::White);
+        assert_eq!(inverted.gg.stone_at(12), Stone::Black);
+    }
+
+    #[test]
+    fn canonical_positions() {
+        let a = game(Grid::new(5), &[(1, 0), (2, 2), (3, 4)]);
+        let b = game(Grid::new(5), &[(4, 1), (2, 2), (0, 3)]);
+        let c = game(Grid::new(5), &[(1, 0), (2, 2), (3, 3)]);
+        assert_ne!(a.position_hash(), b.position_hash());
+        assert_eq!(a.canonical(false).1, b.canonical(false).1);
+        assert_ne!(a.canonical(false).1, c.canonical(false).1);
+
+        let (t, hash) = b.canonical(false);
+        assert_eq!(b.transform(t).position_hash(), hash);
+        assert_eq!(GoState::new(5).canonical_transforms(false).0.len(), 8);
+        assert_eq!(a.canonical_transforms(false).0.len(), 2);
+        assert_eq!(c.canonical_transforms(false).0.len(), 1);
+
+        // the same stones with the colours and the side to move swapped
+        let mut d = GoState::new(5);
+        d.set_side(Stone::White);
+        for &(x, y) in [(1, 0), (2, 2), (3, 4)].iter() {
+            d.apply_action(GoAction::Cell(x, y));
+        }
+        assert_ne!(a.canonical(false).1, d.canonical(false).1);
+        assert_eq!(a.canonical(true).1, d.canonical(true).1);
+    }
+}
@@ -0,0 +1,76 @@
+use board::go_state::GoState;
+use book::opening_book::OpeningBook;
+use go_rules::go_action::GoAction;
+use mcts_lib::policy::policy::Policy;
+
+// plays the most played book move while in book, the fallback policy afterwards
+pub struct BookPolicy<P> {
+    pub book: OpeningBook,
+    pub fallback: P,
+    // rarer moves are not trusted
+    pub min_games: u32,
+}
+
+impl<P> BookPolicy<P> {
+    pub fn new(book: OpeningBook, fallback: P) -> Self {
+        BookPolicy {
+            book,
+            fallback,
+            min_games: 3,
+        }
+    }
+
+    pub fn book_move(&self, state: &GoState) -> Option<GoAction> {
+        self.book.moves(state).into_iter()
+            .filter(|(_, stats)| stats.games >= self.min_games)
+            .max_by(|(_, a), (_, b)| a.games.cmp(&b.games).then(a.score().partial_cmp(&b.score()).unwrap()))
+            .map(|(action, _)| action)
+    }
+}
+
+impl<P: Policy<GoAction, GoState>> Policy<GoAction, GoState> for BookPolicy<P> {
+    fn select(&self, state: &GoState) -> GoAction {
+        self.book_move(state).unwrap_or_else(|| self.fallback.select(state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use board::go_state::GoState;
+    use board::stones::stone::Stone;
+    use book::book_policy::BookPolicy;
+    use book::opening_book::OpeningBook;
+    use go_rules::go_action::GoAction;
+    use mcts_lib::policy::policy::Policy;
+    use mcts_lib::policy::random_policy::RandomPolicy;
+    use mcts_lib::rules::Rules;
+
+    fn game(moves: &[(usize, usize)]) -> GoState {
+        let mut state = GoState::new(5);
+        for &(x, y) in moves {
+            state.apply_action(GoAction::Cell(x, y));
+        }
+        state
+    }
+
+    #[test]
+    fn leaves_the_book() {
+        let mut book = OpeningBook::new(5, 5, 2);
+        for _ in 0..3 {
+            book.add_game(&game(&[(1, 1), (2, 2)]), Stone::White).unwrap();
+        }
+        for _ in 0..2 {
+            book.add_game(&game(&[(2, 2), (1, 2)]), Stone::Black).unwrap();
+        }
+        let policy = BookPolicy::new(book, RandomPolicy::new(1));
+        assert_eq!(policy.book_move(&GoState::new(5)), Some(GoAction::Cell(1, 1)));
+
+        // the book answer is found from another corner
+        let mut state = game(&[(3, 1)]);
+        assert_eq!(policy.select(&state), GoAction::Cell(2, 2));
+        state.apply_action(GoAction::Cell(2, 2));
+        assert_eq!(policy.book_move(&state), None);
+        assert!(state.actions().contains(&policy.select(&state)));
+        assert_eq!(policy.book_move(&game(&[(2, 2)])), None);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod opening_book;
+pub mod book_policy;
\ No newline at end of file
@@ -0,0 +1,383 @@
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, BufWriter, Read, Write};
+
+use board::go_state::GoState;
+use board::group_access::GroupAccess;
+use board::stones::stone::Stone;
+use board::symmetry::Transform;
+use go_rules::go_action::GoAction;
+use mcts::self_play::SelfPlayGame;
+use mcts_lib::mymcts::MyMcts;
+use mcts_lib::rules::Rules;
+use mcts_lib::sim_result::SimResult;
+use sgf::sgf_export::SGF;
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+// results of a move for the side playing it
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct MoveStats {
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+}
+
+impl MoveStats {
+    // a draw counts half
+    pub fn score(&self) -> f32 {
+        (self.wins as f32 + 0.5 * self.draws as f32) / self.games.max(1) as f32
+    }
+
+    pub fn sim_result(&self) -> SimResult {
+        SimResult {
+            tries: self.games as usize,
+            wins: self.wins as usize,
+            draws: self.draws as usize,
+            loses: (self.games - self.wins - self.draws) as usize,
+        }
+    }
+
+    fn add(&mut self, side: Stone, winner: Stone) {
+        self.games += 1;
+        match winner {
+            Stone::None => self.draws += 1,
+            w if w == side => self.wins += 1,
+            _ => {}
+        }
+    }
+}
+
+// statistics of the first moves of games, per canonical position: symmetric positions
+// share one entry, moves being stored on the canonical board. Colours are never swapped,
+// moving first makes the two sides different.
+//
+// file layout (little endian): magic, version, width, height, depth, positions,
+// then per position its hash, its number of moves and for each of them the cell
+// (u16::MAX for a pass), games, wins and draws
+pub struct OpeningBook {
+    pub width: usize,
+    pub height: usize,
+    // moves recorded per game
+    pub depth: usize,
+    positions: HashMap<u64, Vec<(GoAction, MoveStats)>>,
+}
+
+impl OpeningBook {
+    pub const MAGIC: &'static [u8; 4] = b"GOBK";
+    pub const VERSION: u16 = 1;
+    const PASS: u16 = u16::MAX;
+
+    pub fn new(width: usize, height: usize, depth: usize) -> Self {
+        OpeningBook {
+            width,
+            height,
+            depth,
+            positions: HashMap::new(),
+        }
+    }
+
+    // number of positions
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    fn fits(&self, state: &GoState) -> bool {
+        let goban = state.gg.goban();
+        (goban.width, goban.height) == (self.width, self.height)
+    }
+
+    // the smallest image of the move by the transforms leading to the canonical position,
+    // so that equivalent moves of a symmetric position share their statistics
+    fn canonical_move(state: &GoState, transforms: &[Transform], action: GoAction) -> GoAction {
+        let goban = state.gg.goban();
+        transforms.iter()
+            .map(|t| t.symmetry.action(action, goban.width, goban.height))
+            .min_by_key(|a| (a.y(), a.x()))
+            .unwrap()
+    }
+
+    pub fn add_move(&mut self, state: &GoState, action: GoAction, winner: Stone) {
+        let (transforms, hash) = state.canonical_transforms(false);
+        let action = OpeningBook::canonical_move(state, &transforms, action);
+        let moves = self.positions.entry(hash).or_default();
+        let index = match moves.iter().position(|&(a, _)| a == action) {
+            Some(i) => i,
+            None => {
+                moves.push((action, MoveStats::default()));
+                moves.len() - 1
+            }
+        };
+        moves[index].1.add(state.current_side, winner);
+    }
+
+    // the first moves of a game, replayed from its setup
+    pub fn add_game(&mut self, game: &GoState, winner: Stone) -> Result<(), String> {
+        if !self.fits(game) {
+            let goban = game.gg.goban();
+            return Err(format!("{}x{} game in a {}x{} book", goban.width, goban.height, self.width, self.height));
+        }
+        let mut state = game.clone();
+        state.reset();
+        for &action in game.history.iter().take(self.depth) {
+            self.add_move(&state, action, winner);
+            state.apply_action(action);
+        }
+        Ok(())
+    }
+
+    // games without a known result or of another size are skipped,
+    // returns the number of games added
+    pub fn add_sgf(&mut self, text: &str) -> Result<usize, String> {
+        let mut count = 0;
+        for game in SGF::parse_collection(text)? {
+            let added = SGF::winner(&game)
+                .and_then(|winner| SGF::state(&game).and_then(|state| self.add_game(&state, winner)));
+            match added {
+                Ok(()) => count += 1,
+                Err(e) => log::debug!("game skipped: {}", e),
+            }
+        }
+        Ok(count)
+    }
+
+    // every .sgf file of a directory
+    pub fn add_sgf_dir(&mut self, dir: &str) -> Result<usize, String> {
+        let mut paths = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir, e))?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "sgf"))
+            .collect::<Vec<_>>();
+        paths.sort();
+        let mut count = 0;
+        for path in paths {
+            let text = fs::read_to_string(&path).map_err(|e| format!("{:?}: {}", path, e))?;
+            count += self.add_sgf(&text).map_err(|e| format!("{:?}: {}", path, e))?;
+        }
+        Ok(count)
+    }
+
+    pub fn add_self_play(&mut self, game: &SelfPlayGame) -> Result<(), String> {
+        self.add_game(&SGF::read(&game.sgf)?, game.winner)
+    }
+
+    // book moves of a position, on its own board
+    pub fn moves(&self, state: &GoState) -> Vec<(GoAction, MoveStats)> {
+        if !self.fits(state) {
+            return vec![];
+        }
+        let (transforms, hash) = state.canonical_transforms(false);
+        let back = transforms[0].symmetry.inverse();
+        let (width, height) = transforms[0].symmetry.dims(self.width, self.height);
+        let legal = state.actions();
+        self.positions.get(&hash)
+            .map(|moves| moves.iter()
+                .map(|&(a, stats)| (back.action(a, width, height), stats))
+                .filter(|(a, _)| legal.contains(a))
+                .collect())
+            .unwrap_or_default()
+    }
+
+    // book counts seen as earlier simulations of the root moves, false out of book
+    pub fn seed(&self, state: &GoState, mcts: &mut MyMcts<GoAction, GoState>) -> bool {
+        let moves = self.moves(state);
+        let found = !moves.is_empty();
+        mcts.seed_root(moves.iter().map(|(a, stats)| (*a, stats.sim_result())).collect());
+        found
+    }
+
+    fn cell(&self, action: GoAction) -> u16 {
+        match action {
+            GoAction::Pass => OpeningBook::PASS,
+            GoAction::Cell(x, y) => (x + y * self.width) as u16,
+        }
+    }
+
+    fn action(&self, cell: u16) -> GoAction {
+        match cell {
+            OpeningBook::PASS => GoAction::Pass,
+            c => GoAction::Cell(c as usize % self.width, c as usize / self.width),
+        }
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(OpeningBook::MAGIC)?;
+        for &v in [OpeningBook::VERSION as usize, self.width, self.height, self.depth].iter() {
+            writer.write_all(&(v as u16).to_le_bytes())?;
+        }
+        writer.write_all(&(self.positions.len() as u32).to_le_bytes())?;
+        let mut hashes = self.positions.keys().cloned().collect::<Vec<_>>();
+        hashes.sort_unstable();
+        for hash in hashes {
+            let moves = &self.positions[&hash];
+            writer.write_all(&hash.to_le_bytes())?;
+            writer.write_all(&(moves.len() as u16).to_le_bytes())?;
+            for &(action, stats) in moves.iter() {
+                writer.write_all(&self.cell(action).to_le_bytes())?;
+                for &v in [stats.games, stats.wins, stats.draws].iter() {
+                    writer.write_all(&v.to_le_bytes())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut header = [0u8; 16];
+        reader.read_exact(&mut header)?;
+        if &header[0..4] != OpeningBook::MAGIC {
+            return Err(invalid("not an opening book".to_string()));
+        }
+        let u16_at = |i: usize| u16::from_le_bytes([header[i], header[i + 1]]);
+        let version = u16_at(4);
+        if version > OpeningBook::VERSION {
+            return Err(invalid(format!("unsupported book version {}", version)));
+        }
+        let (width, height) = (u16_at(6) as usize, u16_at(8) as usize);
+        if width == 0 || height == 0 {
+            return Err(invalid(format!("empty {}x{} board", width, height)));
+        }
+        let mut book = OpeningBook::new(width, height, u16_at(10) as usize);
+        let positions = u32::from_le_bytes([header[12], header[13], header[14], header[15]]);
+
+        let mut u16_buf = [0u8; 2];
+        let mut u32_buf = [0u8; 4];
+        let mut u64_buf = [0u8; 8];
+        for _ in 0..positions {
+            reader.read_exact(&mut u64_buf)?;
+            let hash = u64::from_le_bytes(u64_buf);
+            reader.read_exact(&mut u16_buf)?;
+            let count = u16::from_le_bytes(u16_buf);
+            let mut moves = Vec::with_capacity(count as usize);
+            for _ in 0..count {
+                reader.read_exact(&mut u16_buf)?;
+                let cell = u16::from_le_bytes(u16_buf);
+                if cell != OpeningBook::PASS && cell as usize >= width * height {
+                    return Err(invalid(format!("cell {} out of a {}x{} board", cell, width, height)));
+                }
+                let action = book.action(cell);
+                let mut values = [0u32; 3];
+                for v in values.iter_mut() {
+                    reader.read_exact(&mut u32_buf)?;
+                    *v = u32::from_le_bytes(u32_buf);
+                }
+                if values[1] as u64 + values[2] as u64 > values[0] as u64 {
+                    return Err(invalid(format!("{} wins and {} draws out of {} games", values[1], values[2], values[0])));
+                }
+                moves.push((action, MoveStats { games: values[0], wins: values[1], draws: values[2] }));
+            }
+            book.positions.insert(hash, moves);
+        }
+        Ok(book)
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn load(path: &str) -> io::Result<Self> {
+        OpeningBook::read(&mut BufReader::new(File::open(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use board::go_state::GoState;
+    use board::stones::stone::Stone;
+    use board::symmetry::{Symmetry, Transform};
+    use book::opening_book::{MoveStats, OpeningBook};
+    use go_rules::go_action::GoAction;
+    use mcts_lib::explorator::Explorer;
+    use mcts_lib::rules::Rules;
+
+    fn game(moves: &[(usize, usize)]) -> GoState {
+        let mut state = GoState::new(5);
+        for &(x, y) in moves {
+            state.apply_action(GoAction::Cell(x, y));
+        }
+        state
+    }
+
+    #[test]
+    fn symmetric_games_share_entries() {
+        let mut book = OpeningBook::new(5, 5, 2);
+        let first = game(&[(1, 1), (2, 3), (3, 3)]);
+        book.add_game(&first, Stone::Black).unwrap();
+        book.add_game(&first.transform(Transform::new(Symmetry::Rotate90, false)), Stone::White).unwrap();
+        assert_eq!(book.len(), 2);
+
+        // the first move is seen on every corner of the empty board
+        let root = book.moves(&GoState::new(5));
+        assert_eq!(root.len(), 1);
+        assert_eq!(root[0].1, MoveStats { games: 2, wins: 1, draws: 0 });
+
+        let after = book.moves(&game(&[(3, 3)]));
+        assert_eq!(after.len(), 1);
+        assert_eq!(after[0].1.wins, 1);
+        assert!(book.moves(&game(&[(2, 2)])).is_empty());
+        assert!(book.add_game(&GoState::new(7), Stone::Black).is_err());
+    }
+
+    #[test]
+    fn sgf_and_files() {
+        let mut book = OpeningBook::new(5, 5, 4);
+        let sgf = "(;SZ[5]RE[B+3];B[cc];W[bc];B[cb])(;SZ[5]RE[W+R];B[cc];W[cb])(;SZ[5];B[cc])(;SZ[7]RE[B+R];B[dd])";
+        assert_eq!(book.add_sgf(sgf), Ok(2));
+        let root = book.moves(&GoState::new(5));
+        assert_eq!(root, vec![(GoAction::Cell(2, 2), MoveStats { games: 2, wins: 1, draws: 0 })]);
+
+        let mut buf = vec![];
+        book.write(&mut buf).unwrap();
+        assert_eq!(buf.len(), 16 + book.len() * 10 + 3 * 14);
+        let loaded = OpeningBook::read(&mut buf.as_slice()).unwrap();
+        assert_eq!((loaded.width, loaded.height, loaded.depth), (5, 5, 4));
+        assert_eq!(loaded.len(), book.len());
+        assert_eq!(loaded.moves(&game(&[(2, 2)])).len(), 1);
+        assert!(OpeningBook::read(&mut &buf[1..]).is_err());
+    }
+
+    #[test]
+    fn corrupted_files() {
+        let mut book = OpeningBook::new(5, 5, 1);
+        book.add_game(&game(&[(2, 2)]), Stone::Black).unwrap();
+        let mut buf = vec![];
+        book.write(&mut buf).unwrap();
+        let corrupted = |offset: usize, bytes: &[u8]| {
+            let mut data = buf.clone();
+            data[offset..offset + bytes.len()].copy_from_slice(bytes);
+            OpeningBook::read(&mut data.as_slice()).err().map(|e| e.kind())
+        };
+        assert_eq!(corrupted(0, &[]), None);
+        // width, then the cell and the wins of the single move
+        assert_eq!(corrupted(6, &[0, 0]), Some(io::ErrorKind::InvalidData));
+        assert_eq!(corrupted(26, &[25, 0]), Some(io::ErrorKind::InvalidData));
+        assert_eq!(corrupted(32, &[2, 0, 0, 0]), Some(io::ErrorKind::InvalidData));
+    }
+
+    #[test]
+    fn seeded_search() {
+        let mut book = OpeningBook::new(5, 5, 1);
+        for _ in 0..10 {
+            book.add_game(&game(&[(2, 2)]), Stone::Black).unwrap();
+        }
+        let state = GoState::new(5);
+        let mut explorer = Explorer::new(1, state.clone());
+        assert!(book.seed(&state, explorer.mcts_mut()));
+        let visits = explorer.mcts().visits();
+        assert_eq!(visits.len(), state.actions().len());
+        assert!(visits.contains(&(GoAction::Cell(2, 2), 10)));
+        assert_eq!(explorer.mcts().win_rate(GoAction::Cell(2, 2)), Some(1.));
+    }
+}
@@ -25,8 +25,8 @@ pub struct BoardMap<T> {
 impl BoardMap<GoGroupRc> {
     pub fn from_board<T>(board: &GoState, cell_size: usize) -> BoardMap<T> {
         // log::info!("BOARDMAP::FROM_BOARD");
-        let width = board.gg.goban().size;
-        let height = board.gg.goban().size;
+        let width = board.gg.goban().width;
+        let height = board.gg.goban().height;
         let size = width * height;
         let mut res = BoardMap {
             width,
@@ -43,7 +43,7 @@ impl BoardMap<GoGroupRc> {
     pub fn new(board: &GoState, cell_size: usize) -> BoardMap<GoGroupRc> {
         let mut res = BoardMap::from_board(board, cell_size);
         for i in 0..board.gg.goban().vertex_number() {
-            res.map.insert(i,Some(board.gg.group_at(i).clone()));
+            res.map[i] = Some(board.gg.group_at(i).clone());
         }
         res
     }
@@ -79,24 +79,26 @@ impl<T> BoardMap<T> {
 impl<T: Display> BoardMap<T> {
     pub fn init_screen(&self, range: &Range2) -> Screen {
         let cell_size = self.cell_size;
+        // the range may start anywhere on the board
+        let (x0, y0) = (range.x().start, range.y().start);
         let w = range.x().len() * cell_size + 4;
         let h = range.y().len() + 3;
         let mut screen = Screen::new(w, h);
         let sep = vec!['-'; cell_size];
         for (x, &y) in iproduct!(range.x(), &[0, h - 2]) {
-            screen.put_slice(screen.at(3 + cell_size * x, y), sep.as_slice());
+            screen.put_slice(screen.at(3 + cell_size * (x - x0), y), sep.as_slice());
         }
         for (&x, y) in iproduct!(&[2, w - 1], range.y()) {
-            screen.put(screen.at(x, y + 1), '|');
+            screen.put(screen.at(x, y - y0 + 1), '|');
         }
         for (&x, &y) in iproduct!(&[2, w - 1], &[0, h - 2]) {
             screen.put(screen.at(x, y), '+');
         }
         for y in range.y() {
-            screen.put_str(screen.at(0, y + 1), &GoDisplay::line(y));
+            screen.put_str(screen.at(0, y - y0 + 1), &GoDisplay::line(y));
         }
         for x in range.x() {
-            screen.put_str(screen.at(1 + cell_size + x * cell_size, h - 1), &GoDisplay::column(x));
+            screen.put_str(screen.at(1 + cell_size + (x - x0) * cell_size, h - 1), &GoDisplay::column(x));
         }
         screen
     }
@@ -104,9 +106,10 @@ impl<T: Display> BoardMap<T> {
     pub(crate) fn write_screen(&self, range: &Range2) -> Screen {
         let cell_size = self.cell_size;
         let mut screen = self.init_screen(range);
+        let (x0, y0) = (range.x().start, range.y().start);
         for (x, y) in iproduct!(range.x(), range.y()) {
-            let y_off = y + 1;
-            let x_off = x * cell_size + 3;
+            let y_off = y - y0 + 1;
+            let x_off = (x - x0) * cell_size + 3;
             match self.get(x, y) {
                 None => {
                     let delta = cell_size - 1;
@@ -127,3 +130,25 @@ impl<T: Display> BoardMap<T> {
         screen
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use board::go_state::GoState;
+    use display::display::GoDisplay;
+    use display::range::Range2;
+    use go_rules::go_action::GoAction;
+    use mcts_lib::rules::Rules;
+
+    #[test]
+    fn sub_range_screen() {
+        let mut state = GoState::new(5);
+        state.apply_action(GoAction::Cell(1, 1));
+        state.apply_action(GoAction::Cell(3, 2));
+        let range = Range2::empty().merge((1, 1)).merge((3, 2));
+        let screen = GoDisplay::history_screen(&state, &range).to_string();
+        // the last column and line of the range are drawn
+        let lines: Vec<&str> = screen.lines().collect();
+        assert_eq!(lines[1..3], ["b |   0   .   .|", "c |   .   .   1|"], "\n{}", screen);
+        assert_eq!(lines[4].trim_end(), "     B   C   D");
+    }
+}
@@ -8,6 +8,7 @@ use itertools::Itertools;
 use board::grid::Grid;
 use board::group_access::GroupAccess;
 use board::stats::full_stats::{BoardStats, FullStats};
+use board::stats::ownership::{Influence, Ownership};
 use board::stats::stone_score::StoneScore;
 use board::stats::stone_stats::StoneStats;
 use board::stones::group::GoGroup;
@@ -73,16 +74,54 @@ impl GoDisplay {
         log::trace!("hist board: cell_size={}", hist.cell_size);
         hist.write_screen(range)
     }
+
+    // black ownership in percent (negative for white)
+    pub fn ownership_screen(board: &GoState, ownership: &Ownership, range: &Range2) -> Screen {
+        let mut owners = BoardMap::from_board(board, 5);
+        for c in board.gg.goban().vertices().iter() {
+            owners.map[c] = Some(format!("{:+.0}", 100. * ownership.balance(c)));
+        }
+        owners.write_screen(range)
+    }
+
+    fn panels(board: &GoState, range: &Range2) -> Vec<LayoutRc> {
+        let classic = BoardMap::new(board, 3)
+            .map(|g| stone_str(g.clone()))
+            .write_screen(range);
+
+        let group_ids = BoardMap::new(board, 6)
+            .map(|g| group_id(g.clone()))
+            .write_screen(range);
+
+        let history = Self::history_screen(board, range);
+
+        vec![
+            L::str(&classic.to_string()),
+            L::str(&history.to_string()),
+            L::str(&group_ids.to_string())
+        ]
+    }
+
+    fn territory_str(board: &GoState, ownership: &Ownership) -> String {
+        let influence = Influence::new(board);
+        format!("ownership ({} simulations): {}={} {}={} | influence: {}={} {}={}",
+                ownership.samples,
+                Stone::Black, ownership.territory(Stone::Black),
+                Stone::White, ownership.territory(Stone::White),
+                Stone::Black, influence.territory(board, Stone::Black),
+                Stone::White, influence.territory(board, Stone::White))
+    }
 }
 
 
 impl GoShow for GoDisplay {
     fn sgf(board: &GoState) -> Sequence {
-        SGF::game(board.gg.goban().size, Stone::Black, board.history.as_slice())
+        let goban = board.gg.goban();
+        SGF::setup_game(goban.width, goban.height, &board.setup, board.start_side, board.history.as_slice())
     }
 
     fn board(board: &GoState) -> LayoutRc {
-        let range = Range2::board(board.gg.goban().size);
+        let range = Range2::rect(board.gg.goban().width, board.gg.goban().height);
         L::vert(vec![
             Self::board_range(board, range),
             L::str(&board.stats_str())
@@ -90,21 +129,17 @@ impl GoShow for GoDisplay {
     }
 
     fn board_range(board: &GoState, range: Range2) -> LayoutRc {
-        let classic = BoardMap::new(board, 3)
-            .map(|g| stone_str(g.clone()))
-            .write_screen(&range);
-
-        let group_ids = BoardMap::new(board, 6)
-            .map(|g| group_id(g.clone()))
-            .write_screen(&range);
-
-        let history = Self::history_screen(board, &range);
-
+        L::hori(Self::panels(board, &range))
+    }
 
-        L::hori(vec![
-            L::str(&classic.to_string()),
-            L::str(&history.to_string()),
-            L::str(&group_ids.to_string())
+    fn board_ownership(board: &GoState, ownership: &Ownership) -> LayoutRc {
+        let range = Range2::rect(board.gg.goban().width, board.gg.goban().height);
+        let mut panels = Self::panels(board, &range);
+        panels.push(L::str(&Self::ownership_screen(board, ownership, &range).to_string()));
+        L::vert(vec![
+            L::hori(panels),
+            L::str(&board.stats_str()),
+            L::str(&Self::territory_str(board, ownership))
         ])
     }
 
@@ -3,6 +3,7 @@ use std::ops::Deref;
 use bit_set::BitSet;
 
 use crate::board::go_state::GoState;
+use board::stats::ownership::Ownership;
 use board::stones::group::GoGroup;
 use board::stones::grouprc::GoGroupRc;
 use board::stones::stone::Stone;
@@ -15,6 +16,7 @@ pub trait GoShow {
     fn sgf(board: &GoState) -> Sequence;
     fn board(board: &GoState) -> LayoutRc;
     fn board_range(board: &GoState, range: Range2) -> LayoutRc;
+    fn board_ownership(board: &GoState, ownership: &Ownership) -> LayoutRc;
     fn group_layout(board: &GoState, group: &GoGroupRc) -> LayoutRc;
     fn group(board: &GoState, group: &GoGroup) -> String;
 
@@ -8,9 +8,13 @@ pub struct Range2 {
 
 impl Range2 {
     pub fn board(size: usize) -> Range2 {
+        Range2::rect(size, size)
+    }
+
+    pub fn rect(width: usize, height: usize) -> Range2 {
         Range2 {
-            _x: 0..size,
-            _y: 0..size,
+            _x: 0..width,
+            _y: 0..height,
         }
     }
 
@@ -46,7 +50,7 @@ impl Range2 {
             None => value,
             Some(x) => x.max(value)
         };
-        a..b
+        a..b + 1
     }
 }
 
@@ -63,5 +67,10 @@ mod tests {
         assert_eq!(r.size(), 0);
         assert_eq!(r.x().collect_vec(), vec![]);
         assert_eq!(r.y().collect_vec(), vec![]);
+
+        let r = r.merge((2, 3));
+        assert_eq!(r.size(), 1);
+        let r = r.merge((4, 1));
+        assert_eq!((r.x(), r.y()), (2..5, 1..4));
     }
 }
\ No newline at end of file
@@ -0,0 +1,344 @@
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use board::grid::Grid;
+use export::npy::Npy;
+use export::sample::{Features, GoSample};
+use graph_lib::topology::Topology;
+use tensor_lib::structs::shape4::Shape4;
+use tensor_lib::structs::view4::View4;
+use tensor_lib::tensor::Tensor;
+use tensor_lib::traits::view::View;
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn invalid_input(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+// file layout (little endian): magic, version, width, height, planes, moves,
+// then fixed size records: planes as one byte per value, policy and value as f32
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct DatasetHeader {
+    pub version: u16,
+    pub width: usize,
+    pub height: usize,
+    pub planes: usize,
+    pub moves: usize,
+}
+
+impl DatasetHeader {
+    pub const MAGIC: &'static [u8; 4] = b"GOSD";
+    pub const VERSION: u16 = 1;
+    pub const SIZE: usize = 16;
+
+    pub fn new(goban: &Grid, features: &Features) -> Self {
+        DatasetHeader {
+            version: DatasetHeader::VERSION,
+            width: goban.width,
+            height: goban.height,
+            planes: features.planes(),
+            moves: goban.vertex_number() + 1,
+        }
+    }
+
+    pub fn x_size(&self) -> usize {
+        self.width * self.height * self.planes
+    }
+
+    pub fn record_size(&self) -> usize {
+        self.x_size() + 4 * (self.moves + 1)
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let dims = [self.width, self.height, self.planes];
+        if dims.iter().any(|&dim| dim > u16::MAX as usize) || self.moves > u32::MAX as usize {
+            return Err(invalid_input(format!("{}x{}x{} planes and {} moves do not fit in a dataset header",
+                                             self.width, self.height, self.planes, self.moves)));
+        }
+        writer.write_all(DatasetHeader::MAGIC)?;
+        writer.write_all(&self.version.to_le_bytes())?;
+        for &dim in dims.iter() {
+            writer.write_all(&(dim as u16).to_le_bytes())?;
+        }
+        writer.write_all(&(self.moves as u32).to_le_bytes())
+    }
+
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut buf = [0u8; DatasetHeader::SIZE];
+        reader.read_exact(&mut buf)?;
+        if &buf[0..4] != DatasetHeader::MAGIC {
+            return Err(invalid("not a go sample dataset".to_string()));
+        }
+        let u16_at = |i: usize| u16::from_le_bytes([buf[i], buf[i + 1]]);
+        let version = u16_at(4);
+        if version > DatasetHeader::VERSION {
+            return Err(invalid(format!("unsupported dataset version {}", version)));
+        }
+        Ok(DatasetHeader {
+            version,
+            width: u16_at(6) as usize,
+            height: u16_at(8) as usize,
+            planes: u16_at(10) as usize,
+            moves: u32::from_le_bytes([buf[12], buf[13], buf[14], buf[15]]) as usize,
+        })
+    }
+
+    fn encode(&self, sample: &GoSample, buf: &mut Vec<u8>) -> io::Result<()> {
+        if sample.x.shape().len() != self.x_size() {
+            return Err(invalid_input(format!("{} feature values in a dataset of {}", sample.x.shape().len(), self.x_size())));
+        }
+        if sample.policy.shape().len() != self.moves {
+            return Err(invalid_input(format!("{} policy values in a dataset of {} moves", sample.policy.shape().len(), self.moves)));
+        }
+        buf.clear();
+        buf.extend((0..self.x_size()).map(|i| (sample.x.get(i) > 0.5) as u8));
+        for i in 0..self.moves {
+            buf.extend_from_slice(&sample.policy.get(i).to_le_bytes());
+        }
+        buf.extend_from_slice(&sample.value.get(0).to_le_bytes());
+        Ok(())
+    }
+
+    fn decode(&self, buf: &[u8]) -> GoSample {
+        let f32_at = |i: usize| f32::from_le_bytes([buf[i], buf[i + 1], buf[i + 2], buf[i + 3]]);
+        let x = buf[..self.x_size()].iter().map(|&b| b as f32).collect();
+        let policy = (0..self.moves).map(|i| f32_at(self.x_size() + 4 * i)).collect();
+        GoSample::new(
+            Tensor::from_buffer(x, View4::new(Shape4::vec3(self.width, self.height, self.planes))),
+            Tensor::from_buffer(policy, View4::new(Shape4::vec1(self.moves))),
+            Tensor::new(Shape4::vec1(1), f32_at(self.x_size() + 4 * self.moves)),
+        )
+    }
+}
+
+// append only: records are never rewritten
+pub struct DatasetWriter<W: Write> {
+    writer: W,
+    pub header: DatasetHeader,
+    pub len: usize,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> DatasetWriter<W> {
+    pub fn new(mut writer: W, header: DatasetHeader) -> io::Result<Self> {
+        header.write(&mut writer)?;
+        Ok(DatasetWriter::resume(writer, header, 0))
+    }
+
+    fn resume(writer: W, header: DatasetHeader, len: usize) -> Self {
+        DatasetWriter {
+            writer,
+            header,
+            len,
+            buf: Vec::with_capacity(header.record_size()),
+        }
+    }
+
+    pub fn write(&mut self, sample: &GoSample) -> io::Result<()> {
+        self.header.encode(sample, &mut self.buf)?;
+        self.writer.write_all(&self.buf)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl DatasetWriter<BufWriter<File>> {
+    pub fn create(path: &str, header: DatasetHeader) -> io::Result<Self> {
+        DatasetWriter::new(BufWriter::new(File::create(path)?), header)
+    }
+
+    // opens an existing dataset (or creates it) and writes after its last record
+    pub fn append(path: &str, header: DatasetHeader) -> io::Result<Self> {
+        let mut file = OpenOptions::new().read(true).append(true).create(true).open(path)?;
+        let size = file.metadata()?.len() as usize;
+        if size == 0 {
+            return DatasetWriter::new(BufWriter::new(file), header);
+        }
+        let existing = DatasetHeader::read(&mut file)?;
+        if existing != header {
+            return Err(invalid(format!("dataset header mismatch: {:?} != {:?}", existing, header)));
+        }
+        let records = size - DatasetHeader::SIZE;
+        if !records.is_multiple_of(header.record_size()) {
+            return Err(invalid(format!("truncated dataset {}", path)));
+        }
+        Ok(DatasetWriter::resume(BufWriter::new(file), header, records / header.record_size()))
+    }
+}
+
+pub struct DatasetReader {
+    reader: BufReader<File>,
+    pub header: DatasetHeader,
+    pub len: usize,
+}
+
+impl DatasetReader {
+    pub fn open(path: &str) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let header = DatasetHeader::read(&mut reader)?;
+        let size = reader.get_ref().metadata()?.len() as usize;
+        // a partially written last record is ignored
+        let len = (size - DatasetHeader::SIZE) / header.record_size();
+        Ok(DatasetReader { reader, header, len })
+    }
+
+    pub fn read_chunk(&mut self, start: usize, count: usize) -> io::Result<Vec<GoSample>> {
+        let count = count.min(self.len.saturating_sub(start));
+        let size = self.header.record_size();
+        self.reader.seek(SeekFrom::Start((DatasetHeader::SIZE + start * size) as u64))?;
+        let mut buf = vec![0u8; size];
+        let mut res = Vec::with_capacity(count);
+        for _ in 0..count {
+            self.reader.read_exact(&mut buf)?;
+            res.push(self.header.decode(&buf));
+        }
+        Ok(res)
+    }
+
+    pub fn get(&mut self, index: usize) -> io::Result<GoSample> {
+        assert!(index < self.len);
+        Ok(self.read_chunk(index, 1)?.remove(0))
+    }
+
+    pub fn export_npz(&mut self, path: &str) -> io::Result<()> {
+        let samples = self.read_chunk(0, self.len)?;
+        Npy::save_npz(path, &samples)
+    }
+
+    // sequential reads, chunk by chunk
+    pub fn stream(&mut self, chunk: usize) -> DatasetStream<'_> {
+        DatasetStream::new(self, chunk, None)
+    }
+
+    // chunks are visited in random order and shuffled in memory
+    pub fn shuffled(&mut self, chunk: usize, seed: u64) -> DatasetStream<'_> {
+        DatasetStream::new(self, chunk, Some(StdRng::seed_from_u64(seed)))
+    }
+}
+
+pub struct DatasetStream<'a> {
+    reader: &'a mut DatasetReader,
+    chunk: usize,
+    starts: Vec<usize>,
+    buffer: Vec<GoSample>,
+    rng: Option<StdRng>,
+}
+
+impl<'a> DatasetStream<'a> {
+    fn new(reader: &'a mut DatasetReader, chunk: usize, mut rng: Option<StdRng>) -> Self {
+        assert!(chunk > 0);
+        let mut starts: Vec<usize> = (0..reader.len).step_by(chunk).collect();
+        match rng.as_mut() {
+            Some(rng) => starts.shuffle(rng),
+            None => starts.reverse(),
+        }
+        DatasetStream { reader, chunk, starts, buffer: vec![], rng }
+    }
+}
+
+impl<'a> Iterator for DatasetStream<'a> {
+    type Item = io::Result<GoSample>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() {
+            let start = self.starts.pop()?;
+            match self.reader.read_chunk(start, self.chunk) {
+                Ok(chunk) => self.buffer = chunk,
+                Err(e) => return Some(Err(e)),
+            }
+            match self.rng.as_mut() {
+                Some(rng) => self.buffer.shuffle(rng),
+                None => self.buffer.reverse(),
+            }
+        }
+        self.buffer.pop().map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::io;
+
+    use board::go_state::GoState;
+    use board::group_access::GroupAccess;
+    use export::dataset::{DatasetHeader, DatasetReader, DatasetWriter};
+    use export::sample::{Features, GoSample};
+    use go_rules::go_action::GoAction;
+    use mcts_lib::rules::Rules;
+
+    fn samples(n: usize) -> Vec<GoSample> {
+        let features = Features::new(2);
+        let mut state = GoState::new(5);
+        (0..n).map(|i| {
+            let sample = GoSample::from_state(&features, &state, &[(GoAction::Cell(i % 5, 0), 1)], i as f32);
+            state.apply_action(GoAction::Cell(i % 5, i / 5 + 1));
+            sample
+        }).collect()
+    }
+
+    #[test]
+    fn write_append_read() {
+        let path = env::temp_dir().join("go_lib_dataset.gosd");
+        let path = path.to_str().unwrap();
+        let _ = fs::remove_file(path);
+        let samples = samples(10);
+        let header = DatasetHeader::new(GoState::new(5).gg.goban(), &Features::new(2));
+        assert_eq!(header.record_size(), 5 * 5 * 9 + 4 * 27);
+
+        let mut writer = DatasetWriter::append(path, header).unwrap();
+        samples[..6].iter().for_each(|s| writer.write(s).unwrap());
+        writer.flush().unwrap();
+        drop(writer);
+        let mut writer = DatasetWriter::append(path, header).unwrap();
+        assert_eq!(writer.len, 6);
+        samples[6..].iter().for_each(|s| writer.write(s).unwrap());
+        writer.flush().unwrap();
+
+        let mut reader = DatasetReader::open(path).unwrap();
+        assert_eq!(reader.header, header);
+        assert_eq!(reader.len, 10);
+        let sample = reader.get(7).unwrap();
+        assert_eq!(sample.value.get(0), 7.);
+        assert_eq!(sample.policy.get(2), 1.);
+        assert_eq!(format!("{:?}", sample.x), format!("{:?}", samples[7].x));
+
+        let values: Vec<f32> = reader.stream(4).map(|s| s.unwrap().value.get(0)).collect();
+        assert_eq!(values, (0..10).map(|i| i as f32).collect::<Vec<_>>());
+
+        let mut shuffled: Vec<f32> = reader.shuffled(3, 42).map(|s| s.unwrap().value.get(0)).collect();
+        assert_ne!(shuffled, values);
+        shuffled.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(shuffled, values);
+
+        let other = DatasetHeader::new(GoState::new(7).gg.goban(), &Features::new(2));
+        assert!(DatasetWriter::append(path, other).is_err());
+    }
+
+    #[test]
+    fn invalid_shapes() {
+        let header = DatasetHeader::new(GoState::new(5).gg.goban(), &Features::new(1));
+        let mut writer = DatasetWriter::new(vec![], header).unwrap();
+        let err = writer.write(&samples(1)[0]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(writer.len, 0);
+
+        let mut huge = header;
+        huge.width = u16::MAX as usize + 1;
+        let err = DatasetWriter::new(vec![], huge).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
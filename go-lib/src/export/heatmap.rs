@@ -0,0 +1,78 @@
+use image::{Rgb, RgbImage};
+
+use board::grid::Grid;
+use board::stats::ownership::Ownership;
+use export::raw_board::BoardCollection;
+use graph_lib::topology::Topology;
+
+// one square per cell: red for black, green for white (as BoardCollection), blue off board
+#[derive(Debug)]
+pub struct Heatmap {
+    data: RgbImage,
+}
+
+impl Heatmap {
+    // values in [-1, 1]: positive for black, negative for white
+    pub fn new(goban: &Grid, values: &[f32], cell_size: usize) -> Self {
+        assert_eq!(values.len(), goban.vertex_number());
+        let mut data = RgbImage::from_pixel(
+            (goban.width * cell_size) as u32,
+            (goban.height * cell_size) as u32,
+            BoardCollection::NONE,
+        );
+        for c in goban.vertices().iter() {
+            let color = Heatmap::color(values[c]);
+            let (x, y) = goban.xy(c);
+            for (dx, dy) in iproduct!(0..cell_size, 0..cell_size) {
+                data.put_pixel((x * cell_size + dx) as u32, (y * cell_size + dy) as u32, color);
+            }
+        }
+        Heatmap { data }
+    }
+
+    pub fn from_ownership(ownership: &Ownership, cell_size: usize) -> Self {
+        Heatmap::new(&ownership.goban, &ownership.values(), cell_size)
+    }
+
+    // neutral cells are dark, owned cells get the owner color
+    fn color(value: f32) -> Rgb<u8> {
+        let value = value.clamp(-1., 1.);
+        let full = match value >= 0. {
+            true => BoardCollection::BLACK,
+            false => BoardCollection::WHITE,
+        };
+        let mut res = full;
+        for channel in res.0.iter_mut() {
+            *channel = (value.abs() * *channel as f32) as u8;
+        }
+        res
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Rgb<u8> {
+        *self.data.get_pixel(x, y)
+    }
+
+    pub fn write_image(&self, path: &str) {
+        self.data.save(path).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use board::grid::Grid;
+    use export::heatmap::Heatmap;
+    use export::raw_board::BoardCollection;
+
+    #[test]
+    fn heatmap_colors() {
+        let goban = Grid::rect(3, 2);
+        let heatmap = Heatmap::new(&goban, &[1., -1., 0., 0.5, 0., 0.], 4);
+        assert_eq!(heatmap.pixel(0, 0), BoardCollection::BLACK);
+        assert_eq!(heatmap.pixel(5, 3), BoardCollection::WHITE);
+        assert_eq!(heatmap.pixel(9, 1).0, [0, 0, 0]);
+        assert_eq!(heatmap.pixel(2, 6).0, [127, 0, 0]);
+        heatmap.write_image(env::temp_dir().join("heatmap.png").to_str().unwrap());
+    }
+}
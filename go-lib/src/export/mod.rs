@@ -1,2 +1,5 @@
 pub mod sample;
-pub mod raw_board;
\ No newline at end of file
+pub mod raw_board;
+pub mod heatmap;
+pub mod dataset;
+pub mod npy;
@@ -0,0 +1,117 @@
+use std::fs::File;
+use std::io;
+use std::io::{BufWriter, Write};
+
+use itertools::Itertools;
+use zip::CompressionMethod;
+use zip::write::FileOptions;
+
+use export::sample::GoSample;
+use tensor_lib::tensor::Tensor;
+use tensor_lib::traits::view::View;
+
+// NumPy .npy (version 1.0, little endian f32, C order) and uncompressed .npz archives
+pub struct Npy;
+
+impl Npy {
+    const MAGIC: &'static [u8] = b"\x93NUMPY\x01\x00";
+    const ALIGN: usize = 64;
+
+    pub fn header(shape: &[usize]) -> Vec<u8> {
+        let dims = match shape.len() {
+            1 => format!("({},)", shape[0]),
+            _ => format!("({})", shape.iter().join(", ")),
+        };
+        let mut dict = format!("{{'descr': '<f4', 'fortran_order': False, 'shape': {}, }}", dims);
+        // magic + header length + dict + '\n' is padded to a multiple of 64 bytes
+        let len = Npy::MAGIC.len() + 2 + dict.len() + 1;
+        dict.push_str(&" ".repeat((Npy::ALIGN - len % Npy::ALIGN) % Npy::ALIGN));
+        dict.push('\n');
+
+        let mut res = Npy::MAGIC.to_vec();
+        res.extend_from_slice(&(dict.len() as u16).to_le_bytes());
+        res.extend_from_slice(dict.as_bytes());
+        res
+    }
+
+    pub fn write<W: Write>(writer: &mut W, shape: &[usize], tensor: &Tensor) -> io::Result<()> {
+        let len = tensor.shape().len();
+        assert_eq!(shape.iter().product::<usize>(), len);
+        writer.write_all(&Npy::header(shape))?;
+        for i in 0..len {
+            writer.write_all(&tensor.get(i).to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    pub fn save(path: &str, shape: &[usize], tensor: &Tensor) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        Npy::write(&mut writer, shape, tensor)?;
+        writer.flush()
+    }
+
+    // x: (n, planes, height, width), policy: (n, moves), value: (n,)
+    pub fn arrays(samples: &[GoSample]) -> Vec<(&'static str, Vec<usize>, Tensor)> {
+        let batch = GoSample::stack(samples);
+        let n = samples.len();
+        let x = batch.x.shape();
+        let (w, h, planes) = (x.x().unwrap(), x.y().unwrap(), x.z().unwrap());
+        let moves = batch.policy.shape().x().unwrap();
+        vec![
+            ("x", vec![n, planes, h, w], batch.x),
+            ("policy", vec![n, moves], batch.policy),
+            ("value", vec![n], batch.value),
+        ]
+    }
+
+    pub fn save_npz(path: &str, samples: &[GoSample]) -> io::Result<()> {
+        let mut zip = zip::ZipWriter::new(File::create(path)?);
+        let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+        for (name, shape, tensor) in Npy::arrays(samples) {
+            zip.start_file(format!("{}.npy", name), options)?;
+            Npy::write(&mut zip, &shape, &tensor)?;
+        }
+        zip.finish()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::File;
+    use std::io::Read;
+
+    use board::go_state::GoState;
+    use export::npy::Npy;
+    use export::sample::{Features, GoSample};
+    use go_rules::go_action::GoAction;
+
+    #[test]
+    fn npy_header() {
+        let header = Npy::header(&[2, 9, 5, 5]);
+        assert_eq!(header.len() % 64, 0);
+        assert_eq!(&header[..8], b"\x93NUMPY\x01\x00");
+        let dict = String::from_utf8(header[10..].to_vec()).unwrap();
+        assert!(dict.starts_with("{'descr': '<f4', 'fortran_order': False, 'shape': (2, 9, 5, 5), }"));
+        assert!(dict.ends_with('\n'));
+        assert!(String::from_utf8_lossy(&Npy::header(&[3])).contains("'shape': (3,)"));
+    }
+
+    #[test]
+    fn npz_archive() {
+        let state = GoState::new(5);
+        let sample = GoSample::from_state(&Features::new(2), &state, &[(GoAction::Pass, 1)], 1.);
+        let path = env::temp_dir().join("go_lib_samples.npz");
+        let path = path.to_str().unwrap();
+        Npy::save_npz(path, &[sample.clone(), sample]).unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(path).unwrap()).unwrap();
+        assert_eq!(archive.len(), 3);
+        let mut value = vec![];
+        archive.by_name("value.npy").unwrap().read_to_end(&mut value).unwrap();
+        let header = Npy::header(&[2]).len();
+        assert_eq!(value.len(), header + 2 * 4);
+        assert_eq!(&value[header..header + 4], &1f32.to_le_bytes());
+    }
+}
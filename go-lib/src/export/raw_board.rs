@@ -16,9 +16,9 @@ pub struct BoardCollection {
 }
 
 impl BoardCollection {
-    const BLACK: Rgb<u8> = Rgb([255, 0, 0]);
-    const WHITE: Rgb<u8> = Rgb([0, 255, 0]);
-    const NONE: Rgb<u8> = Rgb([0, 0, 255]);
+    pub(crate) const BLACK: Rgb<u8> = Rgb([255, 0, 0]);
+    pub(crate) const WHITE: Rgb<u8> = Rgb([0, 255, 0]);
+    pub(crate) const NONE: Rgb<u8> = Rgb([0, 0, 255]);
 
     pub fn new(width: usize, height: usize) -> Self {
         BoardCollection {
@@ -53,19 +53,20 @@ impl BoardCollection {
 
 #[cfg(test)]
 mod tests {
+    use std::env;
     use std::io::Cursor;
 
     use board::grid::Grid;
-    use board::raw_board::BoardCollection;
+    use export::raw_board::BoardCollection;
     use board::stones::stone::Stone;
 
     #[test]
     fn test_raw_board() {
-        let mut board = BoardCollection::new(&Grid::new(4));
+        let mut board = BoardCollection::new(4, 4);
         board.insert(0, 1, Stone::Black);
         board.insert(2, 2, Stone::White);
         board.insert(3, 1, Stone::Black);
-        board.write_image("output.png");
+        board.write_image(env::temp_dir().join("output.png").to_str().unwrap());
 
         println!("{:?}", board);
     }
@@ -1,18 +1,211 @@
+use board::go_state::GoState;
+use board::grid::Grid;
+use board::group_access::GroupAccess;
 use board::stones::stone::Stone;
+use go_rules::go::Go;
+use go_rules::go_action::GoAction;
+use graph_lib::topology::Topology;
+use mcts_lib::rules::{GameResult, Rules};
+use tensor_lib::structs::offset4::Offset4;
+use tensor_lib::structs::shape4::Shape4;
+use tensor_lib::tensor::Tensor;
+use tensor_lib::traits::view::View;
 
-struct GoSample {
-    x: Vec<Stone>,
-    y: Vec<f32>,
+// planes (x, y, plane): own stones, opponent stones, liberties 1/2/3+,
+// the last moves (most recent first), ko point, black to move
+#[derive(Debug, Clone)]
+pub struct Features {
+    pub history: usize,
+}
+
+impl Features {
+    const STONES: usize = 2;
+    const LIBERTIES: usize = 3;
+
+    pub fn new(history: usize) -> Self {
+        Features { history }
+    }
+
+    pub fn planes(&self) -> usize {
+        Features::STONES + Features::LIBERTIES + self.history + 2
+    }
+
+    pub fn shape(&self, goban: &Grid) -> Shape4 {
+        Shape4::vec3(goban.width, goban.height, self.planes())
+    }
+
+    pub fn encode(&self, state: &GoState) -> Tensor {
+        let goban = state.gg.goban();
+        let mut x = Tensor::new(self.shape(goban), 0.);
+        let mut set = |cell: usize, plane: usize| {
+            let (cx, cy) = goban.xy(cell);
+            x.insert_at(Offset4::new(cx, cy, plane, 0), 1.);
+        };
+
+        let go = Go::new(&state.gg);
+        for &(stone, plane) in [(state.current_side, 0), (state.current_side.switch(), 1)].iter() {
+            for group in state.gg.groups_by_stone(stone).iter() {
+                let cells = &group.borrow().cells;
+                let mut liberties = go.adjacent_cells(cells);
+                liberties.intersect_with(&state.gg.empty_cells);
+                let liberty_plane = Features::STONES + liberties.len().clamp(1, Features::LIBERTIES) - 1;
+                for c in cells.iter() {
+                    set(c, plane);
+                    set(c, liberty_plane);
+                }
+            }
+        }
+
+        let moves = Features::STONES + Features::LIBERTIES;
+        for (i, action) in state.history.iter().rev().take(self.history).enumerate() {
+            if let Some(c) = action.cell(goban) {
+                set(c, moves + i);
+            }
+        }
+        if let Some(c) = state.ko {
+            set(c, moves + self.history);
+        }
+        if state.current_side == Stone::Black {
+            goban.vertices().iter().for_each(|c| set(c, moves + self.history + 1));
+        }
+        x
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GoSample {
+    pub x: Tensor,
+    // visit distribution over cells, pass last
+    pub policy: Tensor,
+    // final outcome for the side to move: 1 win, 0 draw, -1 lose
+    pub value: Tensor,
 }
 
 impl GoSample {
-    pub fn new(x: Vec<Stone>, y: Vec<f32>) -> Self {
+    pub fn new(x: Tensor, policy: Tensor, value: Tensor) -> Self {
         GoSample {
             x,
-            y
+            policy,
+            value,
         }
     }
+
+    pub fn from_state(features: &Features, state: &GoState, visits: &[(GoAction, usize)], value: f32) -> Self {
+        GoSample::new(
+            features.encode(state),
+            GoSample::policy(state.gg.goban(), visits),
+            Tensor::new(Shape4::vec1(1), value),
+        )
+    }
+
+    pub fn policy(goban: &Grid, visits: &[(GoAction, usize)]) -> Tensor {
+        let pass = goban.vertex_number();
+        let mut res = Tensor::new(Shape4::vec1(pass + 1), 0.);
+        let total: usize = visits.iter().map(|&(_, n)| n).sum();
+        for &(action, n) in visits {
+            let index = action.cell(goban).unwrap_or(pass);
+            res.insert(index, n as f32 / total.max(1) as f32);
+        }
+        res
+    }
+
+    // outcome of a finished game, seen by one side
+    pub fn outcome(final_state: &GoState, side: Stone) -> f32 {
+        let result = match final_state.result() {
+            Some(r) if side == final_state.current_side => r,
+            Some(r) => r.switch(),
+            None => GameResult::Draw
+        };
+        match result {
+            GameResult::Win => 1.,
+            GameResult::Lose => -1.,
+            _ => 0.
+        }
+    }
+
+    // samples stacked along the last dimension (x: w*h*planes*n, policy: moves*n, value: 1*n)
+    pub fn stack(samples: &[GoSample]) -> GoSample {
+        assert!(!samples.is_empty());
+        let first = &samples[0];
+        let n = samples.len();
+        let x = first.x.shape();
+        let stack = |shape: Shape4, get: &dyn Fn(&GoSample) -> &Tensor| {
+            let mut res = Tensor::new(shape, 0.);
+            let size = get(first).shape().len();
+            for (i, sample) in samples.iter().enumerate() {
+                let tensor = get(sample);
+                assert_eq!(tensor.shape(), get(first).shape());
+                for j in 0..size {
+                    res.insert(i * size + j, tensor.get(j));
+                }
+            }
+            res
+        };
+        GoSample::new(
+            stack(Shape4::vec4(x.x().unwrap(), x.y().unwrap(), x.z().unwrap(), n), &|s| &s.x),
+            stack(Shape4::vec2(first.policy.shape().len(), n), &|s| &s.policy),
+            stack(Shape4::vec2(1, n), &|s| &s.value),
+        )
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use board::go_state::GoState;
+    use board::stones::stone::Stone;
+    use export::sample::{Features, GoSample};
+    use go_rules::go_action::GoAction;
+    use go_rules::go_rules::GoRules;
+    use mcts_lib::rules::Rules;
+    use tensor_lib::structs::offset4::Offset4;
+    use tensor_lib::traits::view::View;
+
+    #[test]
+    fn feature_planes() {
+        let mut state = GoState::new(5);
+        for &a in [GoAction::Cell(1, 1), GoAction::Cell(1, 2), GoAction::Cell(2, 2), GoAction::Pass].iter() {
+            state.apply_action(a);
+        }
+        let features = Features::new(2);
+        let x = features.encode(&state);
+        assert_eq!(features.planes(), 9);
+        assert_eq!(x.shape().len(), 5 * 5 * 9);
+
+        // black to move: own stones are black
+        assert_eq!(x.get_at(Offset4::new(1, 1, 0, 0)), 1.);
+        assert_eq!(x.get_at(Offset4::new(2, 2, 0, 0)), 1.);
+        assert_eq!(x.get_at(Offset4::new(1, 2, 1, 0)), 1.);
+        assert_eq!(x.get_at(Offset4::new(1, 2, 0, 0)), 0.);
+        // white b3 has 2 liberties, black stones 3
+        assert_eq!(x.get_at(Offset4::new(1, 2, 3, 0)), 1.);
+        assert_eq!(x.get_at(Offset4::new(1, 1, 4, 0)), 1.);
+        // last moves: pass, then c3
+        assert_eq!((0..25).map(|i| x.get(5 * 25 + i)).sum::<f32>(), 0.);
+        assert_eq!(x.get_at(Offset4::new(2, 2, 6, 0)), 1.);
+        assert_eq!(x.get_at(Offset4::new(3, 4, 8, 0)), 1.);
+    }
 
-impl GoSample {}
\ No newline at end of file
+    #[test]
+    fn targets() {
+        let state = GoState::new(3);
+        let visits = [(GoAction::Cell(0, 0), 3), (GoAction::Cell(2, 1), 1), (GoAction::Pass, 4)];
+        let sample = GoSample::from_state(&Features::new(1), &state, &visits, -1.);
+        assert_eq!(sample.policy.shape().len(), 10);
+        assert_eq!(sample.policy.get(0), 0.375);
+        assert_eq!(sample.policy.get(5), 0.125);
+        assert_eq!(sample.policy.get(9), 0.5);
+
+        let batch = GoSample::stack(&[sample.clone(), sample]);
+        assert_eq!(batch.x.shape().t().unwrap(), 2);
+        assert_eq!(batch.policy.get(10 + 9), 0.5);
+        assert_eq!(batch.value.get(1), -1.);
+
+        let mut end = GoState::new(3);
+        end.apply_action(GoAction::Cell(1, 1));
+        end.apply_action(GoAction::Pass);
+        end.apply_action(GoAction::Pass);
+        end.update_score();
+        assert_eq!(GoSample::outcome(&end, Stone::Black), 1.);
+        assert_eq!(GoSample::outcome(&end, Stone::White), -1.);
+    }
+}
@@ -74,10 +74,46 @@ impl<'a, T: GroupAccess> Go<'a, T> {
             Stone::None
         } else if white {
             Stone::White
-        } else {
+        } else if black {
             Stone::Black
+        } else {
+            Stone::None
         };
         owner
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use board::go_state::GoState;
+    use board::group_access::GroupAccess;
+    use board::stones::stone::Stone;
+    use go_rules::go::Go;
+    use go_rules::go_action::GoAction;
+    use mcts_lib::rules::{GameResult, Rules};
+
+    fn territory(state: &GoState, stone: Stone) -> usize {
+        Go::new(&state.gg).count_territory(stone)
+    }
+
+    #[test]
+    fn empty_regions_have_no_owner() {
+        // no bordering stone: nobody owns the empty board
+        let mut state = GoState::new(5);
+        let cell = state.gg.goban().cell(2, 2);
+        assert_eq!(Go::new(&state.gg).get_owner(state.gg.group_at(cell).clone()), Stone::None);
+        assert_eq!(territory(&state, Stone::Black), 0);
+        state.apply_action(GoAction::Pass);
+        state.apply_action(GoAction::Pass);
+        assert!(matches!(state.result(), Some(GameResult::Draw)));
+
+        // a lone stone owns the whole board, until the other colour touches it
+        let mut state = GoState::new(5);
+        state.apply_action(GoAction::Cell(2, 2));
+        assert_eq!(territory(&state, Stone::Black), 24);
+        state.apply_action(GoAction::Cell(0, 0));
+        assert_eq!(territory(&state, Stone::Black), 0);
+        assert_eq!(territory(&state, Stone::White), 0);
+    }
+}
@@ -44,12 +44,17 @@ impl Rules<GoAction> for GoState {
     }
 
     fn reset(&mut self) {
-        self.current_side = Stone::Black;
+        self.current_side = self.start_side;
         self.pass_sequence = 0;
         self.ko = None;
         self.history.clear();
         self.gg.reset();
         self.stats = BoardStats::new(self.gg.goban());
+        for (action, stone) in self.setup.clone() {
+            if let Some(cell) = action.cell(self.gg.goban()) {
+                self.edit_stone(cell, stone);
+            }
+        }
     }
 
     fn result(&self) -> Option<GameResult> {
@@ -107,6 +112,7 @@ pub trait GoRules {
     fn update_score(&mut self);
 
     fn play_at(&mut self, cell: GoCell, stone: Stone);
+    fn place_at(&mut self, cell: GoCell, stone: Stone) -> GoGroupRc;
     fn try_capture(&mut self, group: GoGroupRc);
     fn add_group(&mut self, group: &GoGroupRc);
 }
@@ -119,6 +125,18 @@ impl GoRules for GoState {
     }
 
     fn play_at(&mut self, cell: GoCell, stone: Stone) {
+        let fusion_group = self.place_at(cell, stone);
+        for g in self.gg.adjacent_enemies_groups(cell, stone) {
+            self.try_capture(g);
+        }
+        log::trace!("AFTER ENEMY_KILL:\n{}", self.stats_str());
+
+        self.try_capture(fusion_group);
+        log::trace!("AFTER AUTO_KILL:\n{}", self.stats_str());
+    }
+
+    // puts the stone on the board without capturing anything
+    fn place_at(&mut self, cell: GoCell, stone: Stone) -> GoGroupRc {
         log::trace!("PLACE STONE:\n{}", self.stats_str());
 
         //split old empty group
@@ -152,13 +170,10 @@ impl GoRules for GoState {
         }
 
         for g in self.gg.adjacent_enemies_groups(cell, stone) {
-            g.borrow_mut().liberties-=1;
-            self.try_capture(g);
+            self.gg.update_liberties(&g);
         }
-        log::trace!("AFTER ENEMY_KILL:\n{}", self.stats_str());
-
-        self.try_capture(fusion_group.clone());
-        log::trace!("AFTER AUTO_KILL:\n{}", self.stats_str());
+        self.gg.update_liberties(&fusion_group);
+        fusion_group
     }
 
     fn try_capture(&mut self, group: GoGroupRc) {
@@ -175,6 +190,11 @@ impl GoRules for GoState {
             }
             self.stats.capture(&group);
             self.gg.capture(&group);
+            // the stones around the captured group get their liberties back
+            let adjacents = Go::new(&self.gg).adjacent_cells(&group.borrow().cells);
+            for c in adjacents.iter().filter(|&c| self.gg.stone_at(c) != Stone::None) {
+                self.gg.update_liberties(self.gg.group_at(c));
+            }
         }
     }
 
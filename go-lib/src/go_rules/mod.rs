@@ -1,3 +1,4 @@
 pub mod go;
 pub mod go_action;
-pub mod go_rules;
\ No newline at end of file
+pub mod go_rules;
+pub mod setup;
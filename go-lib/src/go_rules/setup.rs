@@ -0,0 +1,212 @@
+use itertools::Itertools;
+
+use board::go_state::GoState;
+use board::grid::{GoCell, Grid};
+use board::group_access::GroupAccess;
+use board::stats::full_stats::BoardStats;
+use board::stones::stone::Stone;
+use display::display::GoDisplay;
+use display::goshow::GoShow;
+use go_rules::go_action::GoAction;
+use go_rules::go_rules::GoRules;
+use graph_lib::topology::Topology;
+
+impl GoState {
+    // setup stones never capture: a setup leaving a group without liberties is refused
+    pub fn setup_stone(&mut self, cell: GoCell, stone: Stone) -> Result<(), String> {
+        assert!(self.history.is_empty(), "setup stones must be placed before the first move");
+        let old = self.gg.stone_at(cell);
+        self.edit_stone(cell, stone);
+        let (x, y) = self.gg.goban().xy(cell);
+        let dead = self.gg.adjacent_groups(cell).into_iter()
+            .chain(Some(self.gg.group_at(cell).clone()))
+            .any(|g| g.borrow().stone != Stone::None && g.borrow().liberties == 0);
+        if dead {
+            self.edit_stone(cell, old);
+            return Err(format!("setup {:?} at {} leaves a group without liberties", stone, GoDisplay::cell((x, y))));
+        }
+        self.setup.push((GoAction::Cell(x, y), stone));
+        self.check_correctness();
+        Ok(())
+    }
+
+    pub fn setup_stones(&mut self, cells: &[GoCell], stone: Stone) -> Result<(), String> {
+        for &cell in cells {
+            self.setup_stone(cell, stone)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_side(&mut self, stone: Stone) {
+        assert!(self.history.is_empty(), "side to move can only be set before the first move");
+        assert_ne!(stone, Stone::None);
+        self.start_side = stone;
+        self.current_side = stone;
+    }
+
+    pub fn fixed_handicap(&mut self, stones: usize) {
+        let cells = Handicap::fixed(self.gg.goban(), stones);
+        self.free_handicap(&cells);
+    }
+
+    pub fn free_handicap(&mut self, cells: &[GoCell]) {
+        assert!(cells.len() >= 2, "handicap needs at least 2 stones");
+        self.setup_stones(cells, Stone::Black).expect("handicap stones");
+        self.set_side(Stone::White);
+    }
+
+    pub(crate) fn edit_stone(&mut self, cell: GoCell, stone: Stone) {
+        let old = self.gg.stone_at(cell);
+        if old == stone {
+            return;
+        }
+        if old == Stone::None {
+            self.place_at(cell, stone);
+            return;
+        }
+        // removing (or replacing) a stone may split its group: rebuild the whole board
+        let mut stones = self.gg.goban().vertices().iter()
+            .filter(|&c| c != cell)
+            .map(|c| (c, self.gg.stone_at(c)))
+            .filter(|&(_, s)| s != Stone::None)
+            .collect_vec();
+        if stone != Stone::None {
+            stones.push((cell, stone));
+        }
+        self.rebuild(&stones);
+    }
+
+    fn rebuild(&mut self, stones: &[(GoCell, Stone)]) {
+        let mut stats = BoardStats::new(self.gg.goban());
+        stats.round = self.stats.round;
+        for &s in [Stone::Black, Stone::White].iter() {
+            stats.for_stone_mut(s).captured = self.stats(s).captured;
+        }
+        self.stats = stats;
+        self.gg.reset();
+        for &(cell, stone) in stones {
+            self.place_at(cell, stone);
+        }
+    }
+}
+
+pub struct Handicap {}
+
+impl Handicap {
+    pub fn max_stones(goban: &Grid) -> usize {
+        let size = goban.width.min(goban.height);
+        if size < 7 {
+            0
+        } else if size == 7 || goban.width % 2 != 1 || goban.height % 2 != 1 {
+            4
+        } else {
+            9
+        }
+    }
+
+    // standard placement (same as GTP fixed_handicap)
+    pub fn fixed(goban: &Grid, stones: usize) -> Vec<GoCell> {
+        assert!(stones >= 2 && stones <= Handicap::max_stones(goban),
+                "invalid handicap {} for a {}x{} board", stones, goban.width, goban.height);
+        let edge = if goban.width.min(goban.height) < 13 { 2 } else { 3 };
+        let (x1, x2, xm) = (edge, goban.width - 1 - edge, goban.width / 2);
+        let (y1, y2, ym) = (edge, goban.height - 1 - edge, goban.height / 2);
+
+        let mut res = vec![(x1, y2), (x2, y1), (x1, y1), (x2, y2)];
+        res.truncate(stones.min(4));
+        if stones >= 6 {
+            res.push((x1, ym));
+            res.push((x2, ym));
+        }
+        if stones >= 8 {
+            res.push((xm, y2));
+            res.push((xm, y1));
+        }
+        if stones % 2 == 1 && stones >= 5 {
+            res.push((xm, ym));
+        }
+        res.iter()
+            .map(|&(x, y)| goban.cell(x, y))
+            .collect_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use board::go_state::GoState;
+    use board::grid::Grid;
+    use board::group_access::GroupAccess;
+    use board::stones::stone::Stone;
+    use go_rules::go_action::GoAction;
+    use go_rules::setup::Handicap;
+    use mcts_lib::rules::Rules;
+
+    #[test]
+    fn fixed_handicap() {
+        for stones in 2..10 {
+            let cells = Handicap::fixed(&Grid::new(19), stones);
+            assert_eq!(cells.len(), stones);
+            let mut state = GoState::new(19);
+            state.fixed_handicap(stones);
+            assert_eq!(state.stats(Stone::Black).stones, stones);
+            assert_eq!(state.stats(Stone::Black).groups, stones);
+            assert_eq!(state.current_side, Stone::White);
+        }
+        assert_eq!(Handicap::max_stones(&Grid::new(7)), 4);
+        assert_eq!(Handicap::max_stones(&Grid::new(5)), 0);
+    }
+
+    #[test]
+    fn remove_setup_stone() {
+        let mut state = GoState::new(5);
+        let goban = Grid::new(5);
+        let line = (0..5).map(|x| goban.cell(x, 2)).collect::<Vec<_>>();
+        state.setup_stones(&line, Stone::White).unwrap();
+        assert_eq!(state.stats(Stone::White).groups, 1);
+        assert_eq!(state.stats(Stone::None).groups, 2);
+
+        // removing the middle stone splits the wall and joins the empty areas
+        state.setup_stone(goban.cell(2, 2), Stone::None).unwrap();
+        assert_eq!(state.stats(Stone::White).stones, 4);
+        assert_eq!(state.stats(Stone::White).groups, 2);
+        assert_eq!(state.stats(Stone::None).groups, 1);
+
+        state.setup_stone(goban.cell(0, 2), Stone::Black).unwrap();
+        assert_eq!(state.gg.stone_at(goban.cell(0, 2)), Stone::Black);
+        assert_eq!(state.stats(Stone::White).stones, 3);
+    }
+
+    #[test]
+    fn setup_never_captures() {
+        let mut state = GoState::new(5);
+        let goban = Grid::new(5);
+        state.setup_stone(goban.cell(0, 0), Stone::White).unwrap();
+        state.setup_stone(goban.cell(1, 0), Stone::Black).unwrap();
+        // filling the last liberty of the white stone is refused, nothing is captured
+        assert!(state.setup_stone(goban.cell(0, 1), Stone::Black).is_err());
+        assert_eq!(state.gg.stone_at(goban.cell(0, 1)), Stone::None);
+        assert_eq!(state.gg.stone_at(goban.cell(0, 0)), Stone::White);
+        assert_eq!(state.stats(Stone::White).captured, 0);
+        assert_eq!(state.setup.len(), 2);
+
+        // an atari is fine
+        state.setup_stone(goban.cell(2, 2), Stone::Black).unwrap();
+        assert_eq!(state.gg.group_at(goban.cell(0, 0)).borrow().liberties, 1);
+        assert!(state.setup_stone(goban.cell(1, 0), Stone::White).is_ok());
+        assert_eq!(state.gg.group_at(goban.cell(0, 0)).borrow().liberties, 3);
+    }
+
+    #[test]
+    fn reset_keeps_setup() {
+        let mut state = GoState::new(9);
+        state.fixed_handicap(3);
+        state.apply_action(GoAction::Cell(4, 4));
+        state.apply_action(GoAction::Cell(5, 5));
+        state.reset();
+
+        assert!(state.history.is_empty());
+        assert_eq!(state.current_side, Stone::White);
+        assert_eq!(state.stats(Stone::Black).stones, 3);
+        assert_eq!(state.stats(Stone::White).stones, 0);
+    }
+}
@@ -8,7 +8,10 @@ extern crate log;
 extern crate mcts_lib;
 extern crate proc_macro;
 extern crate rust_tools;
+extern crate tensor_lib;
 extern crate image;
+extern crate rand;
+extern crate zip;
 
 pub mod board;
 pub mod sgf;
@@ -16,6 +19,8 @@ pub mod display;
 pub mod mcts;
 pub mod go_rules;
 pub mod export;
+pub mod tactics;
+pub mod book;
 
 #[cfg(test)]
 mod tests {
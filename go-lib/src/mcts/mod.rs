@@ -1 +1,2 @@
 pub mod capture_policy;
+pub mod self_play;
@@ -0,0 +1,219 @@
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use board::go_state::GoState;
+use board::stones::stone::Stone;
+use display::display::GoDisplay;
+use display::goshow::GoShow;
+use export::sample::{Features, GoSample};
+use go_rules::go_action::GoAction;
+use go_rules::go_rules::GoRules;
+use mcts_lib::explorator::Explorer;
+use mcts_lib::mcts::Mcts;
+use mcts_lib::policy::random_policy::RandomPolicy;
+use mcts_lib::policy::win_score::WinScore;
+use mcts_lib::rules::{GameResult, Rules};
+use sgf::sgf_export::SGF;
+
+#[derive(Debug, Clone)]
+pub struct SelfPlay {
+    pub size: usize,
+    // simulations per move, visits kept from the previous move count
+    pub playouts: usize,
+    // moves sampled proportionally to visits^(1/temperature), then the most visited one
+    pub temperature: f32,
+    pub temperature_moves: usize,
+    // resign when the best move wins less often than this (None: never resign)
+    pub resign_threshold: Option<f32>,
+    pub resign_min_moves: usize,
+    pub max_moves: usize,
+    pub features: Features,
+}
+
+impl Default for SelfPlay {
+    fn default() -> Self {
+        SelfPlay {
+            size: 9,
+            playouts: 200,
+            temperature: 1.,
+            temperature_moves: 10,
+            resign_threshold: Some(0.05),
+            resign_min_moves: 20,
+            max_moves: 2 * 9 * 9,
+            features: Features::new(4),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SelfPlayGame {
+    pub sgf: String,
+    pub samples: Vec<GoSample>,
+    // Stone::None for a draw
+    pub winner: Stone,
+    pub resigned: bool,
+    pub moves: usize,
+}
+
+impl SelfPlay {
+    pub fn new(size: usize, playouts: usize) -> Self {
+        SelfPlay {
+            size,
+            playouts,
+            max_moves: 2 * size * size,
+            ..SelfPlay::default()
+        }
+    }
+
+    pub fn play(&self, seed: u64) -> SelfPlayGame {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let policy = RandomPolicy::new(seed);
+        let score = WinScore::new();
+        let mut game = GoState::new(self.size);
+        let mut explorer = Explorer::new(1, game.clone());
+
+        let mut positions = vec![];
+        let mut resigned = None;
+        while game.result().is_none() && game.history.len() < self.max_moves {
+            let done = explorer.mcts().root().value.borrow().tries;
+            for _ in done..self.playouts {
+                explorer.explore(&policy, &score);
+            }
+            let visits = explorer.mcts().visits();
+            let best = SelfPlay::most_visited(&visits);
+            if self.should_resign(game.history.len(), explorer.mcts().win_rate(best)) {
+                resigned = Some(game.current_side);
+                break;
+            }
+
+            positions.push((game.current_side, GoSample::from_state(&self.features, &game, &visits, 0.)));
+            let action = match game.history.len() < self.temperature_moves {
+                true => self.sample(&visits, &mut rng).unwrap_or(best),
+                false => best
+            };
+            game.apply_action(action);
+            explorer.mcts_mut().advance(action);
+        }
+
+        game.update_score();
+        let winner = match resigned {
+            Some(loser) => loser.switch(),
+            None => match game.result().unwrap_or(GameResult::Draw) {
+                GameResult::Win => game.current_side,
+                GameResult::Lose => game.current_side.switch(),
+                _ => Stone::None
+            }
+        };
+        let samples = positions.into_iter()
+            .map(|(side, mut sample)| {
+                let value = match winner {
+                    Stone::None => 0.,
+                    w if w == side => 1.,
+                    _ => -1.
+                };
+                sample.value.insert(0, value);
+                sample
+            })
+            .collect();
+
+        let mut sgf = GoDisplay::sgf(&game);
+        sgf.add_root_prop("RE", &SGF::result(&game, winner, resigned.is_some()));
+        SelfPlayGame {
+            sgf: sgf.to_string(),
+            samples,
+            winner,
+            resigned: resigned.is_some(),
+            moves: game.history.len(),
+        }
+    }
+
+    fn should_resign(&self, moves: usize, win_rate: Option<f32>) -> bool {
+        match (self.resign_threshold, win_rate) {
+            (Some(threshold), Some(rate)) => moves >= self.resign_min_moves && rate < threshold,
+            _ => false
+        }
+    }
+
+    fn most_visited(visits: &[(GoAction, usize)]) -> GoAction {
+        visits.iter()
+            .max_by_key(|&&(_, n)| n)
+            .map(|&(a, _)| a)
+            .unwrap_or(GoAction::Pass)
+    }
+
+    fn sample(&self, visits: &[(GoAction, usize)], rng: &mut StdRng) -> Option<GoAction> {
+        let max = visits.iter().map(|&(_, n)| n).max().unwrap_or(0).max(1) as f32;
+        let weights = visits.iter()
+            .map(|&(_, n)| (n as f32 / max).powf(1. / self.temperature.max(1e-3)));
+        WeightedIndex::new(weights).ok()
+            .map(|dist| visits[dist.sample(rng)].0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use board::go_state::GoState;
+    use board::stones::stone::Stone;
+    use go_rules::go_action::GoAction;
+    use mcts::self_play::SelfPlay;
+    use mcts_lib::explorator::Explorer;
+    use mcts_lib::policy::random_policy::RandomPolicy;
+    use mcts_lib::policy::win_score::WinScore;
+    use mcts_lib::rules::Rules;
+    use sgf::sgf_export::SGF;
+
+    #[test]
+    fn self_play_game() {
+        let config = SelfPlay { resign_threshold: None, ..SelfPlay::new(5, 30) };
+        let game = config.play(7);
+        assert!(!game.resigned);
+        assert_eq!(game.samples.len(), game.moves);
+        for (i, sample) in game.samples.iter().enumerate() {
+            let sum = (0..26).map(|j| sample.policy.get(j)).sum::<f32>();
+            assert!((sum - 1.).abs() < 1e-4);
+            let side = if i % 2 == 0 { Stone::Black } else { Stone::White };
+            let expected = match game.winner {
+                Stone::None => 0.,
+                w if w == side => 1.,
+                _ => -1.
+            };
+            assert_eq!(sample.value.get(0), expected);
+        }
+        assert!(game.sgf.contains("RE["));
+        let state = SGF::read(&game.sgf).unwrap();
+        assert_eq!(state.history.len(), game.moves);
+    }
+
+    #[test]
+    fn temperature_sampling() {
+        let config = SelfPlay { temperature: 1e-4, ..SelfPlay::default() };
+        let visits = [(GoAction::Pass, 1), (GoAction::Cell(1, 1), 10)];
+        let mut rng = rand::SeedableRng::seed_from_u64(3);
+        for _ in 0..20 {
+            assert_eq!(config.sample(&visits, &mut rng), Some(GoAction::Cell(1, 1)));
+        }
+        assert_eq!(SelfPlay::most_visited(&visits), GoAction::Cell(1, 1));
+        assert!(config.should_resign(30, Some(0.01)));
+        assert!(!config.should_resign(3, Some(0.01)));
+    }
+
+    #[test]
+    fn won_position_win_rate() {
+        // black captured a stone and white passed: passing again wins the game for black
+        let mut game = GoState::new(5);
+        for action in [GoAction::Cell(1, 0), GoAction::Cell(0, 0), GoAction::Cell(0, 1), GoAction::Pass] {
+            game.apply_action(action);
+        }
+        assert_eq!(game.current_side, Stone::Black);
+        let policy = RandomPolicy::new(5);
+        let mut explorer = Explorer::new(1, game);
+        for _ in 0..200 {
+            explorer.explore(&policy, &WinScore::new());
+        }
+        let best = SelfPlay::most_visited(&explorer.mcts().visits());
+        assert_eq!(best, GoAction::Pass);
+        assert!(explorer.mcts().win_rate(best).unwrap() > 0.5);
+    }
+}
@@ -1 +1,2 @@
-pub mod sgf_export;
\ No newline at end of file
+pub mod sgf_export;
+pub mod sgf_import;
@@ -3,22 +3,28 @@ use std::fmt::{Display, Formatter};
 use std::fs::File;
 use std::io::Write;
 
+use board::go_state::GoState;
+use board::stats::full_stats::FullStats;
 use board::stones::stone::Stone;
 use go_rules::go_action::GoAction;
 
 pub struct Prop {
-    key: String,
-    value: String,
+    pub(crate) key: String,
+    pub(crate) values: Vec<String>,
 }
 
 impl Display for Prop {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}[{}]", self.key, self.value)
+        write!(f, "{}", self.key)?;
+        for value in self.values.iter() {
+            write!(f, "[{}]", value)?;
+        }
+        Ok(())
     }
 }
 
 pub struct Node {
-    props: Vec<Prop>
+    pub(crate) props: Vec<Prop>
 }
 
 impl Display for Node {
@@ -33,7 +39,7 @@ impl Display for Node {
 
 
 pub struct Sequence {
-    data: Vec<Node>
+    pub(crate) data: Vec<Node>
 }
 
 impl Display for Sequence {
@@ -46,6 +52,13 @@ impl Display for Sequence {
     }
 }
 
+impl Sequence {
+    // game info like RE (result) belongs to the root node
+    pub fn add_root_prop(&mut self, key: &str, value: &str) {
+        self.data[0].props.push(SGF::prop(key, value));
+    }
+}
+
 pub struct SGF {}
 
 impl SGF {
@@ -58,43 +71,96 @@ impl SGF {
     fn prop(key: &str, value: &str) -> Prop {
         Prop {
             key: String::from(key),
-            value: String::from(value),
+            values: vec![String::from(value)],
         }
     }
 
-    fn header(size: usize) -> Node {
+    fn header(width: usize, height: usize, stone: Stone) -> Node {
+        let size = match width == height {
+            true => width.to_string(),
+            false => format!("{}:{}", width, height)
+        };
         Node {
             props: vec![
                 SGF::prop("AP", "rust-mcts"),
                 SGF::prop("FF", "4"),
                 SGF::prop("GM", "1"),
-                SGF::prop("SZ", &size.to_string()),
-                SGF::prop("KM", "5.5"),
-                SGF::prop("PL", "B"),
+                SGF::prop("SZ", &size),
+                SGF::prop("KM", "0"),
+                SGF::prop("PL", &SGF::stone(stone)),
                 SGF::prop("RU", "Japanese"),
             ]
         }
     }
 
-    fn action(stone: Stone, a: GoAction) -> Node {
-        let stone_string = format!("{:?}", stone).chars().next().unwrap().to_string();
+    pub(crate) fn stone(stone: Stone) -> String {
+        format!("{:?}", stone).chars().next().unwrap().to_string()
+    }
+
+    // RE value of a scored game: 0 for a draw, B+R / W+R, or the winner and its margin
+    pub fn result(game: &GoState, winner: Stone, resigned: bool) -> String {
+        let score = |stone| game.stats.score(stone).score() as i64;
+        let margin = (score(Stone::Black) - score(Stone::White)).abs();
+        match (winner, resigned) {
+            (Stone::None, _) => String::from("0"),
+            (w, true) => format!("{}+R", SGF::stone(w)),
+            (w, false) => format!("{}+{}", SGF::stone(w), margin),
+        }
+    }
 
-        let a_string = match a {
+    fn point(a: GoAction) -> String {
+        match a {
             GoAction::Pass => String::from("tt"),
             _ => {
                 a.to_string().to_lowercase()
             }
-        };
+        }
+    }
 
+    fn action(stone: Stone, a: GoAction) -> Node {
         Node {
             props: vec![
-                SGF::prop(&stone_string, &a_string)
+                SGF::prop(&SGF::stone(stone), &SGF::point(a))
             ]
         }
     }
 
+    // the properties of a node apply at once: each point is written with its final state,
+    // the setup starting from an empty board
+    fn setup(header: &mut Node, setup: &[(GoAction, Stone)]) {
+        let mut points: Vec<(GoAction, Stone)> = vec![];
+        for &(a, stone) in setup {
+            match points.iter_mut().find(|(p, _)| *p == a) {
+                Some(point) => point.1 = stone,
+                None => points.push((a, stone)),
+            }
+        }
+        for &(key, stone) in [("AB", Stone::Black), ("AW", Stone::White)].iter() {
+            let values = points.iter()
+                .filter(|(_, s)| *s == stone)
+                .map(|&(a, _)| SGF::point(a))
+                .collect::<Vec<_>>();
+            if !values.is_empty() {
+                header.props.push(Prop {
+                    key: String::from(key),
+                    values,
+                });
+            }
+        }
+    }
+
     pub fn game(board_size: usize, stone: Stone, actions: &[GoAction]) -> Sequence {
-        let mut x = vec![SGF::header(board_size)];
+        SGF::rect_game(board_size, board_size, stone, actions)
+    }
+
+    pub fn rect_game(width: usize, height: usize, stone: Stone, actions: &[GoAction]) -> Sequence {
+        SGF::setup_game(width, height, &[], stone, actions)
+    }
+
+    pub fn setup_game(width: usize, height: usize, setup: &[(GoAction, Stone)], stone: Stone, actions: &[GoAction]) -> Sequence {
+        let mut header = SGF::header(width, height, stone);
+        SGF::setup(&mut header, setup);
+        let mut x = vec![header];
         let mut side = stone;
         for &a in actions {
             x.push(SGF::action(side, a));
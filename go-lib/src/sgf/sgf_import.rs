@@ -0,0 +1,354 @@
+use std::fs;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use board::go_state::GoState;
+use board::grid::Grid;
+use board::group_access::GroupAccess;
+use board::stones::stone::Stone;
+use go_rules::go_action::GoAction;
+use mcts_lib::rules::Rules;
+use sgf::sgf_export::{Node, Prop, Sequence, SGF};
+
+// reads the main line of the first game of a SGF collection (variations are skipped)
+pub struct SgfReader<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> SgfReader<'a> {
+    pub fn new(text: &'a str) -> Self {
+        SgfReader {
+            chars: text.chars().peekable(),
+        }
+    }
+
+    pub fn read(&mut self) -> Result<Sequence, String> {
+        let mut data = vec![];
+        self.game_tree(&mut data)?;
+        Ok(Sequence { data })
+    }
+
+    // every game of the collection
+    pub fn read_collection(&mut self) -> Result<Vec<Sequence>, String> {
+        let mut res = vec![];
+        while self.peek() == Some('(') {
+            res.push(self.read()?);
+        }
+        match self.peek() {
+            None => Ok(res),
+            Some(c) => Err(format!("expected '(', found '{}'", c)),
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        while let Some(c) = self.chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.chars.next();
+        }
+        self.chars.peek().cloned()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.chars.next();
+                Ok(())
+            }
+            Some(c) => Err(format!("expected '{}', found '{}'", expected, c)),
+            None => Err(format!("expected '{}', found end of file", expected)),
+        }
+    }
+
+    fn game_tree(&mut self, nodes: &mut Vec<Node>) -> Result<(), String> {
+        self.expect('(')?;
+        while self.peek() == Some(';') {
+            nodes.push(self.node()?);
+        }
+        let mut main_line = true;
+        while self.peek() == Some('(') {
+            if main_line {
+                self.game_tree(nodes)?;
+                main_line = false;
+            } else {
+                self.game_tree(&mut vec![])?;
+            }
+        }
+        self.expect(')')
+    }
+
+    fn node(&mut self) -> Result<Node, String> {
+        self.expect(';')?;
+        let mut props = vec![];
+        while let Some(c) = self.peek() {
+            if !c.is_ascii_alphabetic() {
+                break;
+            }
+            props.push(self.prop()?);
+        }
+        Ok(Node { props })
+    }
+
+    fn prop(&mut self) -> Result<Prop, String> {
+        let mut key = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_ascii_alphabetic() {
+                break;
+            }
+            // FF[3] allows lowercase letters in identifiers: they are ignored
+            if c.is_ascii_uppercase() {
+                key.push(c);
+            }
+            self.chars.next();
+        }
+        let mut values = vec![];
+        while self.peek() == Some('[') {
+            values.push(self.value()?);
+        }
+        if values.is_empty() {
+            return Err(format!("property {} has no value", key));
+        }
+        Ok(Prop { key, values })
+    }
+
+    fn value(&mut self) -> Result<String, String> {
+        self.expect('[')?;
+        let mut res = String::new();
+        loop {
+            match self.chars.next() {
+                None => return Err(String::from("unterminated property value")),
+                Some(']') => return Ok(res),
+                Some('\\') => {
+                    if let Some(c) = self.chars.next() {
+                        res.push(c);
+                    }
+                }
+                Some(c) => res.push(c),
+            }
+        }
+    }
+}
+
+impl Sequence {
+    pub fn nodes(&self) -> &[Node] {
+        &self.data
+    }
+}
+
+impl Node {
+    pub fn get(&self, key: &str) -> Option<&[String]> {
+        self.props.iter()
+            .find(|p| p.key == key)
+            .map(|p| p.values.as_slice())
+    }
+}
+
+impl SGF {
+    pub fn parse(text: &str) -> Result<Sequence, String> {
+        SgfReader::new(text).read()
+    }
+
+    pub fn load(path: &str) -> Result<GoState, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => SGF::read(&text),
+            Err(e) => Err(format!("{}: {}", path, e)),
+        }
+    }
+
+    pub fn parse_collection(text: &str) -> Result<Vec<Sequence>, String> {
+        SgfReader::new(text).read_collection()
+    }
+
+    pub fn read(text: &str) -> Result<GoState, String> {
+        SGF::state(&SGF::parse(text)?)
+    }
+
+    // winner of a RE property, Stone::None for a draw, an error for an unknown result
+    pub fn winner(game: &Sequence) -> Result<Stone, String> {
+        let result = game.nodes().first()
+            .and_then(|root| root.get("RE"))
+            .map(|re| re[0].trim().to_uppercase())
+            .ok_or("game without result")?;
+        match result.chars().next() {
+            Some('B') => Ok(Stone::Black),
+            Some('W') => Ok(Stone::White),
+            Some('0') | Some('D') => Ok(Stone::None),
+            _ => Err(format!("unknown result: {}", result)),
+        }
+    }
+
+    pub fn state(game: &Sequence) -> Result<GoState, String> {
+        let root = game.nodes().first().ok_or("empty game")?;
+        let goban = match root.get("SZ") {
+            None => Grid::new(19),
+            Some(size) => SGF::parse_size(&size[0])?,
+        };
+        let mut state = GoState::from_goban(goban);
+
+        for node in game.nodes() {
+            for &(key, stone) in [("AB", Stone::Black), ("AW", Stone::White), ("AE", Stone::None)].iter() {
+                for value in node.get(key).unwrap_or(&[]) {
+                    if !state.history.is_empty() {
+                        return Err(format!("{} after the first move is not supported", key));
+                    }
+                    for cell in SGF::parse_points(&state, value)? {
+                        state.setup_stone(cell, stone)?;
+                    }
+                }
+            }
+            if let Some(side) = node.get("PL") {
+                state.set_side(SGF::parse_stone(&side[0])?);
+            }
+            for &(key, stone) in [("B", Stone::Black), ("W", Stone::White)].iter() {
+                if let Some(value) = node.get(key) {
+                    let action = SGF::parse_action(&state, &value[0])?;
+                    match state.history.is_empty() {
+                        true => state.set_side(stone),
+                        false => state.current_side = stone
+                    }
+                    state.apply_action(action);
+                }
+            }
+        }
+        Ok(state)
+    }
+
+    fn parse_size(value: &str) -> Result<Grid, String> {
+        let parse = |x: &str| x.trim().parse::<usize>().map_err(|e| format!("SZ[{}]: {}", value, e));
+        match value.find(':') {
+            None => Ok(Grid::new(parse(value)?)),
+            Some(i) => Ok(Grid::rect(parse(&value[..i])?, parse(&value[i + 1..])?)),
+        }
+    }
+
+    fn parse_stone(value: &str) -> Result<Stone, String> {
+        match value.trim() {
+            "B" | "b" => Ok(Stone::Black),
+            "W" | "w" => Ok(Stone::White),
+            _ => Err(format!("invalid color: {}", value))
+        }
+    }
+
+    fn parse_xy(value: &str) -> Result<(usize, usize), String> {
+        let coord = |c: char| match c {
+            'a'..='z' => Ok(c as usize - 'a' as usize),
+            'A'..='Z' => Ok(c as usize - 'A' as usize + 26),
+            _ => Err(format!("invalid coordinate: {}", value))
+        };
+        let chars = value.trim().chars().collect::<Vec<_>>();
+        match chars.as_slice() {
+            &[x, y] => Ok((coord(x)?, coord(y)?)),
+            _ => Err(format!("invalid point: {}", value))
+        }
+    }
+
+    fn parse_action(state: &GoState, value: &str) -> Result<GoAction, String> {
+        let goban = state.gg.goban();
+        let value = value.trim();
+        if value.is_empty() || (value == "tt" && goban.width <= 19 && goban.height <= 19) {
+            return Ok(GoAction::Pass);
+        }
+        let (x, y) = SGF::parse_xy(value)?;
+        if x >= goban.width || y >= goban.height {
+            return Err(format!("point out of board: {}", value));
+        }
+        Ok(GoAction::Cell(x, y))
+    }
+
+    // a single point or a compressed rectangle "aa:cc"
+    fn parse_points(state: &GoState, value: &str) -> Result<Vec<usize>, String> {
+        let goban = state.gg.goban();
+        let (from, to) = match value.find(':') {
+            None => (value, value),
+            Some(i) => (&value[..i], &value[i + 1..]),
+        };
+        let (x1, y1) = SGF::parse_xy(from)?;
+        let (x2, y2) = SGF::parse_xy(to)?;
+        if x1.max(x2) >= goban.width || y1.max(y2) >= goban.height {
+            return Err(format!("point out of board: {}", value));
+        }
+        Ok(iproduct!(y1.min(y2)..=y1.max(y2), x1.min(x2)..=x1.max(x2))
+            .map(|(y, x)| goban.cell(x, y))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use board::go_state::GoState;
+    use board::group_access::GroupAccess;
+    use board::stones::stone::Stone;
+    use display::display::GoDisplay;
+    use display::goshow::GoShow;
+    use go_rules::go_action::GoAction;
+    use mcts_lib::rules::Rules;
+    use sgf::sgf_export::SGF;
+
+    #[test]
+    fn parse_variations() {
+        let game = SGF::parse("(;GM[1]SZ[9] ;B[cc](;W[dd];B[ee])(;W[ff]))").unwrap();
+        assert_eq!(game.nodes().len(), 4);
+        assert_eq!(game.nodes()[2].get("W").unwrap(), &[String::from("dd")]);
+        assert!(SGF::parse("(;B[cc]").is_err());
+    }
+
+    #[test]
+    fn collections() {
+        let games = SGF::parse_collection("(;SZ[9]RE[B+R];B[cc]) (;SZ[9]RE[W+2.5];B[cc];W[dd])\n(;SZ[9])").unwrap();
+        assert_eq!(games.len(), 3);
+        assert_eq!(SGF::winner(&games[0]), Ok(Stone::Black));
+        assert_eq!(SGF::winner(&games[1]), Ok(Stone::White));
+        assert!(SGF::winner(&games[2]).is_err());
+        assert_eq!(SGF::state(&games[1]).unwrap().history.len(), 2);
+        assert!(SGF::parse_collection("(;B[cc]) x").is_err());
+    }
+
+    #[test]
+    fn setup_round_trip() {
+        let mut state = GoState::new(9);
+        state.fixed_handicap(4);
+        state.setup_stone(state.gg.goban().cell(4, 4), Stone::White).unwrap();
+        state.apply_action(GoAction::Cell(3, 4));
+        state.apply_action(GoAction::Pass);
+
+        let text = GoDisplay::sgf(&state).to_string();
+        assert!(text.contains("AB[cg][gc][cc][gg]"));
+        assert!(text.contains("AW[ee]"));
+        assert!(text.contains("PL[W]"));
+
+        let loaded = SGF::read(&text).unwrap();
+        assert_eq!(loaded.setup, state.setup);
+        assert_eq!(loaded.history, state.history);
+        assert_eq!(loaded.start_side, Stone::White);
+        assert_eq!(loaded.current_side, state.current_side);
+        for &s in [Stone::Black, Stone::White, Stone::None].iter() {
+            loaded.stats.for_stone(s).assert_eq(state.stats.for_stone(s));
+        }
+    }
+
+    #[test]
+    fn edited_setup_export() {
+        let mut state = GoState::new(5);
+        let goban = state.gg.goban().clone();
+        state.setup_stone(goban.cell(0, 0), Stone::Black).unwrap();
+        state.setup_stone(goban.cell(1, 1), Stone::Black).unwrap();
+        state.setup_stone(goban.cell(0, 0), Stone::White).unwrap();
+        state.setup_stone(goban.cell(1, 1), Stone::None).unwrap();
+
+        let text = GoDisplay::sgf(&state).to_string();
+        assert!(text.contains("AW[aa]") && !text.contains("AB") && !text.contains("AE"), "{}", text);
+        let loaded = SGF::read(&text).unwrap();
+        assert_eq!(loaded.gg.stone_at(goban.cell(0, 0)), Stone::White);
+        assert_eq!(loaded.gg.stone_at(goban.cell(1, 1)), Stone::None);
+    }
+
+    #[test]
+    fn tsumego_setup() {
+        let state = SGF::read("(;SZ[7]AB[aa:ba][ab]AW[ca][cb][bb]AE[aa]PL[W])").unwrap();
+        assert_eq!(state.current_side, Stone::White);
+        assert_eq!(state.stats(Stone::Black).stones, 2);
+        assert_eq!(state.stats(Stone::White).stones, 3);
+        assert_eq!(state.gg.stone_at(state.gg.goban().cell(0, 0)), Stone::None);
+    }
+}
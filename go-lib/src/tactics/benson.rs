@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use bit_set::BitSet;
+use itertools::Itertools;
+
+use board::group_access::GroupAccess;
+use board::stones::stone::Stone;
+use go_rules::go::Go;
+use graph_lib::algo::flood::Flood;
+use graph_lib::graph::GFlood;
+use graph_lib::topology::Topology;
+
+struct Region {
+    border: Vec<usize>,
+    vital: Vec<usize>,
+}
+
+// Benson's algorithm: groups that can not be captured, even if their owner always passes
+pub struct Benson<'a, T: GroupAccess> {
+    state: &'a T
+}
+
+impl<'a, T: GroupAccess> Benson<'a, T> {
+    pub fn new(state: &'a T) -> Benson<'a, T> {
+        Benson { state }
+    }
+
+    pub fn alive_cells(&self, stone: Stone) -> BitSet {
+        assert_ne!(stone, Stone::None);
+        let blocks = self.state.groups_by_stone(stone)
+            .iter()
+            .map(|g| g.borrow().cells.clone())
+            .collect_vec();
+        let mut index = HashMap::new();
+        for (i, block) in blocks.iter().enumerate() {
+            for c in block.iter() {
+                index.insert(c, i);
+            }
+        }
+
+        let regions = self.regions(stone, &index);
+        let mut alive = vec![true; blocks.len()];
+        let mut valid = vec![true; regions.len()];
+        loop {
+            let mut changed = false;
+            for (b, a) in alive.iter_mut().enumerate() {
+                let vital = regions.iter()
+                    .zip(valid.iter())
+                    .filter(|&(r, &v)| v && r.vital.contains(&b))
+                    .count();
+                if *a && vital < 2 {
+                    *a = false;
+                    changed = true;
+                }
+            }
+            for (r, v) in regions.iter().zip(valid.iter_mut()) {
+                if *v && r.border.iter().any(|&b| !alive[b]) {
+                    *v = false;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let mut res = BitSet::new();
+        for (block, _) in blocks.iter().zip(alive.iter()).filter(|&(_, &a)| a) {
+            res.union_with(block);
+        }
+        res
+    }
+
+    // a region is vital to a block when all its empty cells are liberties of the block
+    fn regions(&self, stone: Stone, index: &HashMap<usize, usize>) -> Vec<Region> {
+        let goban = self.state.goban();
+        let go = Go::new(self.state);
+        let mut visited = BitSet::new();
+        let mut res = vec![];
+        for c in goban.vertices().iter() {
+            if visited.contains(c) || self.state.stone_at(c) == stone {
+                continue;
+            }
+            let cells = GFlood::new().flood(goban, c, &|x| self.state.stone_at(x) != stone);
+            visited.union_with(&cells);
+
+            let border = go.adjacent_cells(&cells).iter()
+                .map(|x| index[&x])
+                .unique()
+                .collect_vec();
+            let empties = cells.iter()
+                .filter(|&x| self.state.stone_at(x) == Stone::None)
+                .collect_vec();
+            let vital = border.iter()
+                .filter(|&&b| empties.iter().all(|&e| goban.edges(e).iter()
+                    .any(|n| index.get(&n) == Some(&b))))
+                .cloned()
+                .collect_vec();
+            res.push(Region { border, vital });
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use board::group_access::GroupAccess;
+    use board::stones::stone::Stone;
+    use sgf::sgf_export::SGF;
+    use tactics::benson::Benson;
+
+    #[test]
+    fn two_eyes() {
+        // black owns the top left corner with eyes at a1 and c1
+        let state = SGF::read("(;SZ[7]AB[ba][da][ab:db]AW[ea:eb][ac:ec])").unwrap();
+        let goban = state.gg.goban();
+        let alive = Benson::new(&state.gg).alive_cells(Stone::Black);
+        assert!(alive.contains(goban.cell(1, 0)));
+        assert!(alive.contains(goban.cell(3, 1)));
+        assert!(Benson::new(&state.gg).alive_cells(Stone::White).is_empty());
+
+        // a single eye is not enough
+        let state = SGF::read("(;SZ[7]AB[ab:db][da]AW[ea:eb][ac:ec])").unwrap();
+        assert!(Benson::new(&state.gg).alive_cells(Stone::Black).is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+use bit_set::BitSet;
+
+use board::go_state::GoState;
+use board::grid::GoCell;
+use board::group_access::GroupAccess;
+use board::stones::stone::Stone;
+use go_rules::go::Go;
+use go_rules::go_rules::GoRules;
+
+// reads ladders: the attacker only plays on the liberties of the target group, keeping it
+// in atari or at two liberties, the defender extends or captures adjacent groups in atari
+pub struct Ladder {
+    pub max_depth: usize,
+}
+
+impl Default for Ladder {
+    fn default() -> Self {
+        Ladder { max_depth: 80 }
+    }
+}
+
+impl Ladder {
+    pub fn new() -> Self {
+        Ladder::default()
+    }
+
+    pub fn liberties(state: &GoState, target: GoCell) -> BitSet {
+        let group = state.gg.group_at(target);
+        let mut res = Go::new(&state.gg).adjacent_cells(&group.borrow().cells);
+        res.intersect_with(&state.gg.empty_cells);
+        res
+    }
+
+    // attacker to move: the move capturing the target group, if any
+    pub fn capture_move(&self, state: &GoState, target: GoCell) -> Option<GoCell> {
+        assert_ne!(state.gg.stone_at(target), Stone::None);
+        self.attack(state, target, 0)
+    }
+
+    // defender to move
+    pub fn can_escape(&self, state: &GoState, target: GoCell) -> bool {
+        assert_ne!(state.gg.stone_at(target), Stone::None);
+        self.defend(state, target, 0)
+    }
+
+    fn attack(&self, state: &GoState, target: GoCell, depth: usize) -> Option<GoCell> {
+        let liberties = Ladder::liberties(state, target);
+        match liberties.len() {
+            1 => return liberties.iter().next(),
+            2 if depth < self.max_depth => {}
+            _ => return None,
+        }
+        let attacker = state.gg.stone_at(target).switch();
+        liberties.iter().find(|&cell| {
+            let mut child = state.clone();
+            child.play_at(cell, attacker);
+            // the attacking stone must not be captured right away
+            child.gg.stone_at(cell) == attacker
+                && Ladder::liberties(&child, cell).len() > 1
+                && !self.defend(&child, target, depth + 1)
+        })
+    }
+
+    fn defend(&self, state: &GoState, target: GoCell, depth: usize) -> bool {
+        let defender = state.gg.stone_at(target);
+        if defender == Stone::None {
+            return false;
+        }
+        let liberties = Ladder::liberties(state, target);
+        if liberties.len() > 2 || depth >= self.max_depth {
+            return true;
+        }
+
+        // extend, or capture an adjacent group in atari
+        let mut moves = liberties.clone();
+        for c in state.gg.group_at(target).borrow().cells.iter() {
+            for enemy in state.gg.adjacent_enemies_groups(c, defender) {
+                let enemy_cell = enemy.borrow().cells.iter().next().unwrap();
+                let enemy_liberties = Ladder::liberties(state, enemy_cell);
+                if enemy_liberties.len() == 1 {
+                    moves.union_with(&enemy_liberties);
+                }
+            }
+        }
+
+        moves.iter().any(|cell| {
+            let mut child = state.clone();
+            child.play_at(cell, defender);
+            child.gg.stone_at(target) == defender
+                && self.attack(&child, target, depth + 1).is_none()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use board::go_state::GoState;
+    use board::group_access::GroupAccess;
+    use board::stones::stone::Stone;
+    use sgf::sgf_export::SGF;
+    use tactics::ladder::Ladder;
+
+    // white c3 next to black b3 and c2: d2 forces the ladder toward the lower right corner
+    fn ladder(breaker: &str) -> GoState {
+        SGF::read(&format!("(;SZ[9]AB[bc][cb][db]AW[cc]{}PL[B])", breaker)).unwrap()
+    }
+
+    #[test]
+    fn working_ladder() {
+        let state = ladder("");
+        let goban = state.gg.goban();
+        let target = goban.cell(2, 2);
+        assert_eq!(Ladder::liberties(&state, target).len(), 2);
+
+        let capture = Ladder::new().capture_move(&state, target);
+        assert!(capture.is_some());
+        let mut state = state.clone();
+        state.setup_stone(capture.unwrap(), Stone::Black).unwrap();
+        assert!(!Ladder::new().can_escape(&state, target));
+    }
+
+    #[test]
+    fn broken_ladder() {
+        let state = ladder("[gg]");
+        let target = state.gg.goban().cell(2, 2);
+        assert_eq!(Ladder::new().capture_move(&state, target), None);
+    }
+
+    #[test]
+    fn escape_by_capture() {
+        // white c1 in atari: extending to d1 is not enough
+        let state = SGF::read("(;SZ[9]AB[ba][cb][db]AW[ca])").unwrap();
+        let target = state.gg.goban().cell(2, 0);
+        assert_eq!(Ladder::liberties(&state, target).len(), 1);
+        assert!(!Ladder::new().can_escape(&state, target));
+
+        // with white b2 and b3, black b1 is in atari too and white escapes by capturing it
+        let state = SGF::read("(;SZ[9]AB[ba][cb][db]AW[ca][bb][bc])").unwrap();
+        assert!(Ladder::new().can_escape(&state, target));
+    }
+}
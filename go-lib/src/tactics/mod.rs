@@ -0,0 +1,4 @@
+pub mod benson;
+pub mod ladder;
+pub mod semeai;
+pub mod tsumego;
@@ -0,0 +1,172 @@
+use bit_set::BitSet;
+use itertools::Itertools;
+
+use board::go_state::GoState;
+use board::grid::GoCell;
+use board::group_access::GroupAccess;
+use board::stones::stone::Stone;
+use go_rules::go::Go;
+use graph_lib::algo::flood::Flood;
+use graph_lib::graph::GFlood;
+use tactics::ladder::Ladder;
+
+// liberties needed to fill an eye of a given size (nakade counting)
+const EYE_LIBERTIES: [usize; 7] = [0, 1, 2, 3, 5, 8, 12];
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RaceCount {
+    pub stone: Stone,
+    pub liberties: usize,
+    pub eye: bool,
+}
+
+// capture race between two adjacent groups, evaluated by liberty counting
+#[derive(Debug)]
+pub struct Semeai {
+    pub first: RaceCount,
+    pub second: RaceCount,
+    pub shared: usize,
+}
+
+impl Semeai {
+    pub fn new(state: &GoState, a: GoCell, b: GoCell) -> Self {
+        let stone = state.gg.stone_at(a);
+        assert_ne!(stone, Stone::None);
+        assert_eq!(state.gg.stone_at(b), stone.switch(), "semeai needs groups of opposite colors");
+        let libs_a = Ladder::liberties(state, a);
+        let libs_b = Ladder::liberties(state, b);
+        let mut shared = libs_a.clone();
+        shared.intersect_with(&libs_b);
+
+        let other = state.gg.group_at(b);
+        let adjacent = state.gg.group_at(a).borrow().cells.iter()
+            .any(|c| state.gg.adjacent_enemies_groups(c, stone).contains(other));
+        assert!(adjacent || !shared.is_empty(), "semeai needs adjacent groups");
+
+        Semeai {
+            first: Semeai::count(state, a, &libs_a, &shared),
+            second: Semeai::count(state, b, &libs_b, &shared),
+            shared: shared.len(),
+        }
+    }
+
+    fn count(state: &GoState, cell: GoCell, liberties: &BitSet, shared: &BitSet) -> RaceCount {
+        let goban = state.gg.goban();
+        let group = state.gg.group_at(cell).borrow().cells.clone();
+        let go = Go::new(&state.gg);
+
+        let mut outside = liberties.clone();
+        outside.difference_with(shared);
+        let mut eye = false;
+        let mut eye_liberties = 0;
+        let mut visited = BitSet::new();
+        for c in liberties.iter().filter(|&c| !shared.contains(c)) {
+            if visited.contains(c) {
+                continue;
+            }
+            let area = GFlood::new().flood(goban, c, &|x| state.gg.stone_at(x) == Stone::None);
+            visited.union_with(&area);
+            // an eye is an empty area only surrounded by the group
+            if area.len() < EYE_LIBERTIES.len() && go.adjacent_cells(&area).is_subset(&group) {
+                eye = true;
+                eye_liberties += EYE_LIBERTIES[area.len()];
+                outside.difference_with(&area);
+            }
+        }
+        RaceCount {
+            stone: state.gg.stone_at(cell),
+            liberties: outside.len() + eye_liberties,
+            eye,
+        }
+    }
+
+    // None when the race ends in seki
+    pub fn winner(&self, to_move: Stone) -> Option<Stone> {
+        let (mover, other) = match to_move == self.first.stone {
+            true => (&self.first, &self.second),
+            false => (&self.second, &self.first)
+        };
+        // liberties the mover may keep on top of the other group ones (and conversely):
+        // shared liberties belong to the only group having an eye, and have to be
+        // filled by the capturer when both groups have one
+        let (a, b, margin) = match (mover.eye, other.eye) {
+            (true, false) => (mover.liberties + self.shared, other.liberties, 0),
+            (false, true) => (mover.liberties, other.liberties + self.shared, 0),
+            (true, true) => (mover.liberties, other.liberties, self.shared),
+            (false, false) => (mover.liberties, other.liberties, self.shared.max(1) - 1),
+        };
+        if a >= b + margin {
+            Some(mover.stone)
+        } else if b > a + margin {
+            Some(other.stone)
+        } else {
+            None
+        }
+    }
+
+    pub fn counts(&self) -> Vec<RaceCount> {
+        [self.first, self.second].iter().cloned().collect_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use board::group_access::GroupAccess;
+    use board::stones::stone::Stone;
+    use sgf::sgf_export::SGF;
+    use tactics::semeai::{RaceCount, Semeai};
+
+    // black b1-b3 against white c1-c3, each one with 3 outside liberties
+    const RACE: &str = "(;SZ[9]AB[ba:bc][ea:ec][cd:dd]AW[ca:cc][ad:bd]{})";
+
+    #[test]
+    fn outside_liberties() {
+        let state = SGF::read(&RACE.replace("{}", "")).unwrap();
+        let goban = state.gg.goban();
+        let semeai = Semeai::new(&state, goban.cell(1, 0), goban.cell(2, 0));
+        assert_eq!(semeai.first.liberties, 3);
+        assert_eq!(semeai.second.liberties, 3);
+        assert_eq!(semeai.shared, 0);
+        assert!(!semeai.first.eye && !semeai.second.eye);
+        assert_eq!(semeai.winner(Stone::Black), Some(Stone::Black));
+        assert_eq!(semeai.winner(Stone::White), Some(Stone::White));
+
+        let state = SGF::read(&RACE.replace("{}", "[ac]")).unwrap();
+        let semeai = Semeai::new(&state, goban.cell(1, 0), goban.cell(2, 0));
+        assert_eq!(semeai.first.liberties, 2);
+        assert_eq!(semeai.winner(Stone::Black), Some(Stone::White));
+    }
+
+    #[test]
+    fn shared_liberties_seki() {
+        let state = SGF::read("(;SZ[9]AB[aa:ab][bc:cc][da:db]AW[ca:cb][ac])").unwrap();
+        let goban = state.gg.goban();
+        let semeai = Semeai::new(&state, goban.cell(0, 0), goban.cell(2, 0));
+        assert_eq!(semeai.shared, 2);
+        assert_eq!(semeai.first.liberties + semeai.second.liberties, 0);
+        assert_eq!(semeai.winner(Stone::Black), None);
+        assert_eq!(semeai.winner(Stone::White), None);
+    }
+
+    #[test]
+    fn eye_liberties() {
+        // black has an eye at a1, white has 3 liberties
+        let state = SGF::read("(;SZ[9]AB[ba][ab:bb]AW[ca:cb])").unwrap();
+        let goban = state.gg.goban();
+        let semeai = Semeai::new(&state, goban.cell(0, 1), goban.cell(2, 0));
+        assert!(semeai.first.eye);
+        assert_eq!(semeai.first.liberties, 3);
+        assert_eq!(semeai.second.liberties, 3);
+
+        // shared liberties only count for the group having an eye
+        let semeai = Semeai {
+            first: RaceCount { stone: Stone::Black, liberties: 1, eye: true },
+            second: RaceCount { stone: Stone::White, liberties: 3, eye: false },
+            shared: 2,
+        };
+        assert_eq!(semeai.winner(Stone::Black), Some(Stone::Black));
+        assert_eq!(semeai.winner(Stone::White), Some(Stone::White));
+        let semeai = Semeai { first: RaceCount { eye: false, ..semeai.first }, ..semeai };
+        assert_eq!(semeai.winner(Stone::Black), Some(Stone::White));
+    }
+}
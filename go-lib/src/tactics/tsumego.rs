@@ -0,0 +1,355 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fmt;
+use std::fmt::Formatter;
+use std::hash::{Hash, Hasher};
+
+use bit_set::BitSet;
+use itertools::Itertools;
+
+use board::go_state::GoState;
+use board::grid::GoCell;
+use board::group_access::GroupAccess;
+use board::stones::stone::Stone;
+use go_rules::go_action::GoAction;
+use graph_lib::topology::Topology;
+use mcts_lib::rules::Rules;
+use tactics::benson::Benson;
+
+const INF: u64 = u64::MAX / 4;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Status {
+    Alive,
+    Dead,
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProofTree {
+    pub stone: Stone,
+    pub action: GoAction,
+    pub children: Vec<ProofTree>,
+}
+
+impl ProofTree {
+    fn write(&self, f: &mut Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(f, "{}{:?} {}", "  ".repeat(depth), self.stone, self.action)?;
+        for child in self.children.iter() {
+            child.write(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ProofTree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+#[derive(Debug)]
+pub struct Solution {
+    pub status: Status,
+    // winning move of the side to move (none if it can not win, or has already won)
+    pub key_move: Option<GoAction>,
+    // answers of the winner to every move of the loser
+    pub tree: Vec<ProofTree>,
+    pub nodes: usize,
+}
+
+// life and death problem: the attacker wants to capture the target group,
+// the defender wants it unconditionally alive (or the attacker to run out of moves).
+// Both players only play inside the region, the defender may also pass.
+pub struct Tsumego {
+    pub state: GoState,
+    pub region: BitSet,
+    pub target: GoCell,
+    pub max_nodes: usize,
+}
+
+struct PnNode {
+    action: GoAction,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    side: Stone,
+    key: u64,
+    pn: u64,
+    dn: u64,
+    expanded: bool,
+}
+
+impl Tsumego {
+    pub fn new(state: GoState, region: BitSet, target: GoCell) -> Self {
+        assert_ne!(state.gg.stone_at(target), Stone::None, "the target must be a stone");
+        Tsumego {
+            state,
+            region,
+            target,
+            max_nodes: 100_000,
+        }
+    }
+
+    pub fn defender(&self) -> Stone {
+        self.state.gg.stone_at(self.target)
+    }
+
+    pub fn attacker(&self) -> Stone {
+        self.defender().switch()
+    }
+
+    pub fn solve(&self) -> Solution {
+        let mut search = PnSearch {
+            problem: self,
+            root_side: self.state.current_side,
+            nodes: vec![],
+        };
+        search.add_node(GoAction::Pass, None, &self.state);
+        while !search.is_solved(0) && search.nodes.len() < self.max_nodes {
+            let leaf = search.most_proving();
+            search.expand(leaf);
+            search.update(leaf);
+        }
+        search.solution()
+    }
+
+    fn winner(&self, state: &GoState) -> Option<Stone> {
+        if state.gg.stone_at(self.target) != self.defender() {
+            Some(self.attacker())
+        } else if Benson::new(&state.gg).alive_cells(self.defender()).contains(self.target) {
+            Some(self.defender())
+        } else {
+            None
+        }
+    }
+
+    fn position_key(state: &GoState) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for c in state.gg.goban().vertices().iter() {
+            state.gg.stone_at(c).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+struct PnSearch<'a> {
+    problem: &'a Tsumego,
+    root_side: Stone,
+    nodes: Vec<PnNode>,
+}
+
+impl<'a> PnSearch<'a> {
+    fn add_node(&mut self, action: GoAction, parent: Option<usize>, state: &GoState) -> usize {
+        let mut node = PnNode {
+            action,
+            parent,
+            children: vec![],
+            side: state.current_side,
+            key: Tsumego::position_key(state),
+            pn: 1,
+            dn: 1,
+            expanded: false,
+        };
+        if let Some(winner) = self.problem.winner(state) {
+            let (pn, dn) = self.proof_numbers(winner);
+            node.pn = pn;
+            node.dn = dn;
+            node.expanded = true;
+        }
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    fn proof_numbers(&self, winner: Stone) -> (u64, u64) {
+        if winner == self.root_side { (0, INF) } else { (INF, 0) }
+    }
+
+    fn is_solved(&self, n: usize) -> bool {
+        self.nodes[n].pn == 0 || self.nodes[n].dn == 0
+    }
+
+    fn most_proving(&self) -> usize {
+        let mut n = 0;
+        while self.nodes[n].expanded {
+            let node = &self.nodes[n];
+            n = if node.side == self.root_side {
+                *node.children.iter().min_by_key(|&&c| self.nodes[c].pn).unwrap()
+            } else {
+                *node.children.iter().min_by_key(|&&c| self.nodes[c].dn).unwrap()
+            };
+        }
+        n
+    }
+
+    fn path(&self, mut n: usize) -> Vec<usize> {
+        let mut res = vec![n];
+        while let Some(p) = self.nodes[n].parent {
+            res.push(p);
+            n = p;
+        }
+        res.reverse();
+        res
+    }
+
+    fn expand(&mut self, n: usize) {
+        let path = self.path(n);
+        let keys = path.iter().map(|&p| self.nodes[p].key).collect::<HashSet<_>>();
+        let mut state = self.problem.state.clone();
+        for &p in path.iter().skip(1) {
+            state.apply_action(self.nodes[p].action);
+        }
+
+        let side = state.current_side;
+        let mut moves = self.problem.region.clone();
+        moves.intersect_with(&state.gg.empty_cells);
+        for cell in moves.iter() {
+            let (x, y) = state.gg.goban().xy(cell);
+            let mut child = state.clone();
+            child.apply_action(GoAction::Cell(x, y));
+            let suicide = child.gg.stone_at(cell) == Stone::None;
+            // no positional repetition (this also forbids ko recaptures)
+            if suicide || keys.contains(&Tsumego::position_key(&child)) {
+                continue;
+            }
+            let c = self.add_node(GoAction::Cell(x, y), Some(n), &child);
+            self.nodes[n].children.push(c);
+        }
+        if side == self.problem.defender() {
+            let mut child = state.clone();
+            child.apply_action(GoAction::Pass);
+            let c = self.add_node(GoAction::Pass, Some(n), &child);
+            self.nodes[n].children.push(c);
+        }
+
+        self.nodes[n].expanded = true;
+        if self.nodes[n].children.is_empty() {
+            // the attacker has nothing left to try
+            let (pn, dn) = self.proof_numbers(self.problem.defender());
+            self.nodes[n].pn = pn;
+            self.nodes[n].dn = dn;
+        }
+    }
+
+    fn update(&mut self, mut n: usize) {
+        loop {
+            if !self.nodes[n].children.is_empty() {
+                let children = self.nodes[n].children.iter().map(|&c| &self.nodes[c]).collect_vec();
+                let min_pn = children.iter().map(|c| c.pn).min().unwrap();
+                let min_dn = children.iter().map(|c| c.dn).min().unwrap();
+                let sum_pn = children.iter().map(|c| c.pn).fold(0, |a, b| (a + b).min(INF));
+                let sum_dn = children.iter().map(|c| c.dn).fold(0, |a, b| (a + b).min(INF));
+                let node = &mut self.nodes[n];
+                if node.side == self.root_side {
+                    node.pn = min_pn;
+                    node.dn = sum_dn;
+                } else {
+                    node.pn = sum_pn;
+                    node.dn = min_dn;
+                }
+            }
+            match self.nodes[n].parent {
+                Some(p) => n = p,
+                None => break
+            }
+        }
+    }
+
+    fn winner(&self, n: usize) -> Stone {
+        if self.nodes[n].pn == 0 { self.root_side } else { self.root_side.switch() }
+    }
+
+    fn proof(&self, n: usize, winner: Stone) -> Vec<ProofTree> {
+        let node = &self.nodes[n];
+        let children = node.children.iter()
+            .filter(|&&c| node.side != winner || self.is_solved(c) && self.winner(c) == winner)
+            .take(if node.side == winner { 1 } else { node.children.len() });
+        children
+            .map(|&c| ProofTree {
+                stone: node.side,
+                action: self.nodes[c].action,
+                children: self.proof(c, winner),
+            })
+            .collect()
+    }
+
+    fn solution(&self) -> Solution {
+        let nodes = self.nodes.len();
+        if !self.is_solved(0) {
+            return Solution { status: Status::Unknown, key_move: None, tree: vec![], nodes };
+        }
+        let winner = self.winner(0);
+        let tree = self.proof(0, winner);
+        let key_move = match winner == self.root_side {
+            true => tree.first().map(|t| t.action),
+            false => None
+        };
+        let status = match winner == self.problem.attacker() {
+            true => Status::Dead,
+            false => Status::Alive
+        };
+        Solution { status, key_move, tree, nodes }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bit_set::BitSet;
+
+    use board::group_access::GroupAccess;
+    use board::stones::stone::Stone;
+    use go_rules::go_action::GoAction;
+    use sgf::sgf_export::SGF;
+    use tactics::tsumego::{Status, Tsumego};
+
+    // black group in the corner with a straight three eye space (a1 b1 c1)
+    const STRAIGHT_THREE: &str = "(;SZ[7]AB[ab:db][da]AW[ea:eb][ac:ec]PL[{}])";
+
+    fn straight_three(side: &str) -> Tsumego {
+        let state = SGF::read(&STRAIGHT_THREE.replace("{}", side)).unwrap();
+        let goban = state.gg.goban().clone();
+        let region = (0..3).map(|x| goban.cell(x, 0)).collect::<BitSet>();
+        Tsumego::new(state, region, goban.cell(0, 1))
+    }
+
+    #[test]
+    fn kill_straight_three() {
+        let solution = straight_three("W").solve();
+        assert_eq!(solution.status, Status::Dead);
+        assert_eq!(solution.key_move, Some(GoAction::Cell(1, 0)));
+        assert_eq!(solution.tree.len(), 1);
+    }
+
+    #[test]
+    fn live_straight_three() {
+        let solution = straight_three("B").solve();
+        assert_eq!(solution.status, Status::Alive);
+        assert_eq!(solution.key_move, Some(GoAction::Cell(1, 0)));
+    }
+
+    #[test]
+    fn refute_every_attack() {
+        // black already has two eyes: white can not even play inside
+        let state = SGF::read("(;SZ[7]AB[ba][da][ab:db]AW[ea:eb][ac:ec]PL[W])").unwrap();
+        let goban = state.gg.goban().clone();
+        let region = (0..3).map(|x| goban.cell(x, 0)).collect::<BitSet>();
+        let solution = Tsumego::new(state, region, goban.cell(1, 0)).solve();
+        assert_eq!(solution.status, Status::Alive);
+        assert_eq!(solution.key_move, None);
+
+        // straight two: the group can only make one eye whatever black does
+        let state = SGF::read("(;SZ[7]AB[ab:cb][ca]AW[da:db][ac:dc]PL[B])").unwrap();
+        let region = (0..2).map(|x| goban.cell(x, 0)).collect::<BitSet>();
+        let solution = Tsumego::new(state, region, goban.cell(0, 1)).solve();
+        assert_eq!(solution.status, Status::Dead);
+        assert_eq!(solution.key_move, None);
+        assert_eq!(solution.tree.len(), 3);
+        // every black move, pass included, gets a white answer
+        let moves = solution.tree.iter().map(|t| (t.stone, t.action)).collect::<Vec<_>>();
+        let black = |a| (Stone::Black, a);
+        assert_eq!(moves, vec![black(GoAction::Cell(0, 0)), black(GoAction::Cell(1, 0)), black(GoAction::Pass)]);
+        for t in solution.tree.iter() {
+            assert_eq!(t.children.len(), 1);
+            assert_eq!(t.children[0].stone, Stone::White);
+        }
+    }
+}
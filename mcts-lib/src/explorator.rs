@@ -6,6 +6,7 @@ use ordered_float::OrderedFloat;
 
 use graph_lib::algo::trees::Trees;
 use mymcts::MyMcts;
+use policy::evaluator::Evaluator;
 use policy::policy::Policy;
 use policy::score::Score;
 use policy::win_score::ExploreScore;
@@ -45,24 +46,65 @@ impl<A: Action, S: Rules<A>> Explorer<A, S> {
         sim_policy: &Sim,
         select_policy: &Select)
         -> MctsNode<A>
+    {
+        self.explore_with(sim_policy, select_policy, &mut |_| {})
+    }
+
+    // on_terminal is called with the final position of every simulation
+    pub fn explore_with<Sim: Policy<A, S>, Select: Score, F: FnMut(&S)>(
+        &mut self,
+        sim_policy: &Sim,
+        select_policy: &Select,
+        on_terminal: &mut F)
+        -> MctsNode<A>
     {
         // log::debug!("* Exploration:");
         let selected = self.mcts.selection(select_policy);
-        let (_action, expansion) = self.mcts.expansion(&selected, sim_policy);
-        let res = self.simulation(sim_policy);
+        let expansion = self.expand(selected, sim_policy);
+        let res = self.simulation(sim_policy, on_terminal);
+        self.mcts.backpropagation(&expansion, res);
+
+        expansion
+    }
+
+    // the expanded position is scored by an evaluator instead of a simulation
+    pub fn explore_eval<P: Policy<A, S>, Select: Score, E: Evaluator<A, S>>(
+        &mut self,
+        expansion_policy: &P,
+        select_policy: &Select,
+        evaluator: &E)
+        -> MctsNode<A>
+    {
+        let selected = self.mcts.selection(select_policy);
+        let expansion = self.expand(selected, expansion_policy);
+        let res = evaluator.evaluate(self.mcts.state());
         self.mcts.backpropagation(&expansion, res);
 
         expansion
     }
 
-    fn simulation<Sim: Policy<A, S>>(&mut self, policy: &Sim) -> SimResult {
+    // a finished game has no child: its node is scored again as it is
+    fn expand<P: Policy<A, S>>(&mut self, selected: MctsNode<A>, policy: &P) -> MctsNode<A> {
+        match self.mcts.state().result() {
+            Some(_) => selected,
+            None => self.mcts.expansion(&selected, policy).1
+        }
+    }
+
+    fn simulation<Sim: Policy<A, S>, F: FnMut(&S)>(&mut self, policy: &Sim, on_terminal: &mut F) -> SimResult {
         let res = match self.simulation_factor {
-            1 => self.mcts.state_mut().simulation(policy),
+            1 => {
+                let res = self.mcts.state_mut().simulation(policy);
+                on_terminal(self.mcts.state());
+                res
+            }
             _ => {
                 let mut result = SimResult::new();
                 let mut state = self.mcts.state().fork();
                 for _i in 0..self.simulation_factor {
-                    result.merge(&state.fork().simulation(policy));
+                    let mut sim = state.fork();
+                    result.merge(&sim.simulation(policy));
+                    on_terminal(&sim);
                 }
                 result
             }
@@ -71,4 +113,3 @@ impl<A: Action, S: Rules<A>> Explorer<A, S> {
         res
     }
 }
-
@@ -13,7 +13,7 @@ use graph_lib::tree::TheTree;
 use mcts::Mcts;
 use policy::policy::Policy;
 use policy::score::Score;
-use policy::win_score::ExploreScore;
+use policy::win_score::{ExploreScore, WinScore};
 use rules::{Action, Rules};
 use sim_result::SimResult;
 
@@ -21,12 +21,15 @@ use crate::mcts::MctsNode;
 
 pub struct MyMcts<A: Action, S: Rules<A>> {
     the_state: S,
+    // position of the root node
+    root_state: S,
     root: MctsNode<A>,
 }
 
 impl<A: Action, S: Rules<A>> MyMcts<A, S> {
     pub fn new(state: S) -> MyMcts<A, S> {
         MyMcts {
+            root_state: state.clone(),
             the_state: state,
             root: SimResult::node(),
         }
@@ -35,17 +38,58 @@ impl<A: Action, S: Rules<A>> MyMcts<A, S> {
     pub fn fork(&self, node: &MctsNode<A>) -> MyMcts<A, S> {
         MyMcts {
             the_state: self.state().clone(),
+            root_state: self.root_state.clone(),
             root: node.clone(),
         }
     }
 
+    // visit counts of the root children
+    pub fn visits(&self) -> Vec<(A, usize)> {
+        self.root.children.borrow().iter()
+            .map(|(&action, node)| (action, node.value.borrow().tries))
+            .collect()
+    }
+
+    // win ratio of a root child, for the side to move at the root
+    pub fn win_rate(&self, action: A) -> Option<f32> {
+        self.root.get_child(action)
+            .map(|node| WinScore::new().score(node.value.borrow().deref()))
+    }
+
+    // plays an action at the root and keeps its subtree for the next searches
+    pub fn advance(&mut self, action: A) {
+        self.root_state.apply_action(action);
+        let child = self.root.get_child(action).unwrap_or_else(SimResult::node);
+        child.parent.replace(None);
+        self.root = child;
+        self.reset();
+    }
+
+    // prior statistics of root moves, seen as earlier simulations: the root is expanded
+    // and wins count for the side to move at the root, like the ones of win_rate
+    pub fn seed_root(&mut self, stats: Vec<(A, SimResult)>) {
+        self.reset();
+        if self.root.children.borrow().is_empty() {
+            for a in self.state().actions() {
+                self.root.set_child(a, &SimResult::node());
+            }
+        }
+        for (action, res) in stats {
+            if let Some(child) = self.root.get_child(action) {
+                child.value.borrow_mut().merge(&res);
+                self.root.value.borrow_mut().merge(&res);
+            }
+        }
+    }
+
     fn is_leaf(node: MctsNode<A>) -> bool {
         node.value.borrow().is_leaf()
     }
 
     pub(crate) fn reset(&mut self) {
         let current = self.root.clone();
-        self.the_state.reset();
+        // in place: the state keeps its buffers between iterations
+        self.the_state.clone_from(&self.root_state);
         for (action, _) in current.parents().iter().rev() {
             self.state_mut().apply_action(action.clone())
         }
@@ -92,11 +136,13 @@ impl<A: Action, S: Rules<A>> Mcts<A, S> for MyMcts<A, S> {
     }
 
     fn expansion<P: Policy<A, S>>(&mut self, selected: &MctsNode<A>, policy: &P) -> (A, MctsNode<A>) {
+        // children are the moves available at the selected node, before playing one of them
+        let actions = self.state().actions();
         let action = policy.select(self.state());
         self.state_mut().apply_action(action);
 
         let mut next_node = selected.clone();
-        for a in self.state().actions() {
+        for a in actions {
             let new_node = SimResult::node();
             selected.set_child(a, &new_node);
             if a == action {
@@ -108,13 +154,15 @@ impl<A: Action, S: Rules<A>> Mcts<A, S> for MyMcts<A, S> {
     }
 
 
+    // res is seen by the side to move at the cursor while a node counts the wins of the
+    // side that played into it: res is swapped before each node up to the root
     fn backpropagation(&mut self, cursor: &MctsNode<A>, mut res: SimResult) {
         // log::debug!("Backpropagation: ({} parents)", cursor.parents().len());
+        res.swap();
         cursor.value.borrow_mut().merge(&res);
-        let parents = cursor.parents();
-        for (_key, value) in parents {
-            value.value.borrow_mut().merge(&res);
+        for (_key, parent) in cursor.parents() {
             res.swap();
+            parent.value.borrow_mut().merge(&res);
         }
     }
 
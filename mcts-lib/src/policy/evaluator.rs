@@ -0,0 +1,8 @@
+use rules::{Action, Rules};
+use sim_result::SimResult;
+
+// estimates the outcome of a position instead of playing it out, seen by the side
+// to move like Rules::result; finished games are evaluated too
+pub trait Evaluator<A: Action, S: Rules<A>> {
+    fn evaluate(&self, state: &S) -> SimResult;
+}
@@ -1,4 +1,5 @@
+pub mod evaluator;
 pub mod policy;
 pub mod random_policy;
 pub mod score;
-pub mod win_score;
+pub mod win_score;
\ No newline at end of file
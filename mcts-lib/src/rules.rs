@@ -28,12 +28,17 @@ pub trait Rules<A: Action> where Self: Clone {
     fn result(&self) -> Option<GameResult>;
     fn actions(&self) -> Vec<A>;
     fn apply_action(&mut self, action: A);
+    // the result is seen by the side to move when the playout starts: players alternate,
+    // so the final result is switched after an odd number of moves
     fn simulation<P: Policy<A, Self>>(&mut self, policy: &P) -> SimResult {
+        let mut moves = 0;
         while !self.result().is_some() {
             let action = policy.select(self);
             self.apply_action(action);
+            moves += 1;
         }
-        SimResult::from_game(self.result().unwrap())
+        let result = self.result().unwrap();
+        SimResult::from_game(if moves % 2 == 0 { result } else { result.switch() })
     }
 }
 
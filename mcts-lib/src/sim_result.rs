@@ -41,6 +41,17 @@ impl SimResult {
         res
     }
 
+    // a value in [-1, 1] spread over a number of tries, draws are rounded away
+    pub fn from_value(value: f32, tries: usize) -> SimResult {
+        let wins = (tries as f32 * (1. + value.clamp(-1., 1.)) / 2.).round() as usize;
+        SimResult {
+            tries,
+            wins,
+            draws: 0,
+            loses: tries - wins,
+        }
+    }
+
     pub fn update(&mut self, result: GameResult) {
         match result {
             GameResult::Win => self.wins += 1,
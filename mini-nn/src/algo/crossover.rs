@@ -1,24 +1,27 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+
 use crate::algo::population::population::Population;
 
 pub trait CrossOver<Mod> {
-    fn cross(&self, m1: &mut Mod, m2: &mut Mod);
-    fn cross_pop(&self, population: &mut Population<Mod>) {
-        let size = population.population.len();
-        for i in 1..(size / 2) {
-            let (m1, m2) = &mut population.population.split_at_mut(i);
-            self.cross(
-                &mut m1[i-1].adn,
-                &mut m2[m2.len() - 1 - i].adn,
-            );
+    fn cross<R: Rng>(&self, m1: &mut Mod, m2: &mut Mod, rng: &mut R);
+    // crosses random pairs, every individual is in at most one pair
+    fn cross_pop<R: Rng>(&self, population: &mut Population<Mod>, rng: &mut R) {
+        let mut order: Vec<usize> = (0..population.len()).collect();
+        order.shuffle(rng);
+        for pair in order.chunks_exact(2) {
+            let (i, j) = (pair[0].min(pair[1]), pair[0].max(pair[1]));
+            let (m1, m2) = population.population.split_at_mut(j);
+            self.cross(&mut m1[i].adn, &mut m2[0].adn, rng);
         }
     }
 }
 
 
 impl<Mod, Cr: CrossOver<Mod>> CrossOver<Mod> for Population<Cr> {
-    fn cross(&self, m1: &mut Mod, m2: &mut Mod) {
+    fn cross<R: Rng>(&self, m1: &mut Mod, m2: &mut Mod, rng: &mut R) {
         for mutator in self.population.iter() {
-            mutator.adn.cross(m1, m2);
+            mutator.adn.cross(m1, m2, rng);
         }
     }
 }
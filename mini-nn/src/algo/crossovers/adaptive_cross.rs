@@ -0,0 +1,24 @@
+use rand::Rng;
+
+use crate::algo::crossover::CrossOver;
+use crate::algo::mutations::adaptive_mut::SelfAdaptive;
+
+// crosses the models, both children get the geometric mean of the step sizes
+pub struct AdaptiveCross<Cr> {
+    pub cross: Cr,
+}
+
+impl<Cr> AdaptiveCross<Cr> {
+    pub fn new(cross: Cr) -> Self {
+        AdaptiveCross { cross }
+    }
+}
+
+impl<Mod, Cr: CrossOver<Mod>> CrossOver<SelfAdaptive<Mod>> for AdaptiveCross<Cr> {
+    fn cross<R: Rng>(&self, m1: &mut SelfAdaptive<Mod>, m2: &mut SelfAdaptive<Mod>, rng: &mut R) {
+        self.cross.cross(&mut m1.model, &mut m2.model, rng);
+        let sigma = (m1.sigma * m2.sigma).sqrt();
+        m1.sigma = sigma;
+        m2.sigma = sigma;
+    }
+}
@@ -1,28 +1,35 @@
-use rand_distr::{Distribution, Normal, Uniform};
-
-use tensor_lib::tensor::Tensor;
-use tensor_lib::traits::view::View;
+use rand::Rng;
+use rand_distr::{Distribution, Uniform};
 
 use crate::algo::crossover::CrossOver;
+use crate::framework::parameters::Parameters;
 
+// blends the parameters of the models between two random cuts
 pub struct AverageCross {
-    power: f32
+    pub power: f32
+}
+
+impl AverageCross {
+    pub fn new(power: f32) -> Self {
+        AverageCross { power }
+    }
 }
 
 
-impl CrossOver<Tensor> for AverageCross {
-    fn cross(&self, m1: &mut Tensor, m2: &mut Tensor) {
-        let mut rng = rand::thread_rng();
+impl<P: Parameters> CrossOver<P> for AverageCross {
+    fn cross<R: Rng>(&self, m1: &mut P, m2: &mut P, rng: &mut R) {
+        let (mut v1, mut v2) = (m1.flat_values(), m2.flat_values());
 
-        let size = m1.shape().len();
-        let a = Uniform::new(0, size / 2).sample(&mut rng);
-        let b = Uniform::new(a, size).sample(&mut rng);
+        let size = v1.len();
+        let a = Uniform::new(0, size / 2 + 1).sample(rng);
+        let b = Uniform::new_inclusive(a, size).sample(rng);
 
-        for i in 0..size {
-            if a < i || i < b {
-                m1.insert(i, m1.get(i) + self.power * m2.get(i));
-                m2.insert(i, self.power * m1.get(i) + m2.get(i));
-            }
+        for i in a..b {
+            let (x1, x2) = (v1[i], v2[i]);
+            v1[i] = x1 + self.power * x2;
+            v2[i] = self.power * x1 + x2;
         }
+        m1.set_flat_values(&v1);
+        m2.set_flat_values(&v2);
     }
 }
\ No newline at end of file
@@ -1,3 +1,5 @@
+use rand::Rng;
+
 use crate::conv2::Conv2;
 use tensor_lib::tensor::Tensor;
 use crate::algo::crossover::CrossOver;
@@ -18,16 +20,16 @@ impl<Cr: CrossOver<Tensor>> ConvCross<Cr> {
 
 
 impl<Cr: CrossOver<Tensor>> CrossOver<Conv2> for ConvCross<Cr> {
-    fn cross(&self, m1: &mut Conv2, m2: &mut Conv2) {
+    fn cross<R: Rng>(&self, m1: &mut Conv2, m2: &mut Conv2, rng: &mut R) {
         match &self.bias {
             Some(mutation) => {
-                mutation.cross(&mut m1.bias, &mut m2.bias);
+                mutation.cross(&mut m1.bias, &mut m2.bias, rng);
             }
             _ => {}
         }
         match &self.filter {
             Some(mutation) => {
-                mutation.cross(&mut m1.filter, &mut m2.filter);
+                mutation.cross(&mut m1.filter, &mut m2.filter, rng);
             }
             _ => {}
         }
@@ -2,5 +2,44 @@
 pub mod mix_cross;
 pub mod conv_mut;
 pub mod average_cross;
+pub mod adaptive_cross;
 
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
 
+    use tensor_lib::tensor::Tensor;
+
+    use crate::algo::crossover::CrossOver;
+    use crate::algo::crossovers::average_cross::AverageCross;
+    use crate::algo::crossovers::mix_cross::MixCross;
+    use crate::framework::parameters::Parameters;
+
+    // indices changed by a crossover of a tensor of ones and a tensor of twos
+    fn crossed<C: CrossOver<Tensor>>(cross: &C, seed: u64) -> (Vec<usize>, Vec<usize>) {
+        let (mut m1, mut m2) = (Tensor::full(&[10], 1.), Tensor::full(&[10], 2.));
+        cross.cross(&mut m1, &mut m2, &mut StdRng::seed_from_u64(seed));
+        let changed = |m: &Tensor, x: f32| m.flat_values().iter()
+            .enumerate().filter(|&(_, &v)| v != x).map(|(i, _)| i).collect();
+        (changed(&m1, 1.), changed(&m2, 2.))
+    }
+
+    #[test]
+    fn crossovers_change_one_range() {
+        let mut partial = 0;
+        for seed in 0..20 {
+            for (c1, c2) in [crossed(&MixCross {}, seed), crossed(&AverageCross::new(0.5), seed)].iter() {
+                assert_eq!(c1, c2);
+                if let (Some(first), Some(last)) = (c1.first(), c1.last()) {
+                    assert_eq!(c1.len(), last - first + 1);
+                }
+                if c1.len() < 10 {
+                    partial += 1;
+                }
+            }
+        }
+        // the values outside of the cuts are kept
+        assert!(partial > 20);
+    }
+}
@@ -0,0 +1,347 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+
+use tensor_lib::tensor::Tensor;
+
+use crate::algo::genetic_trainer::dataset_score;
+use crate::framework::model::Model;
+use crate::framework::parameters::Parameters;
+use crate::framework::trainer::{EpochMetrics, Trainer, TrainReport};
+
+// covariance matrix adaptation evolution strategy (Hansen's tutorial, no restarts):
+// the model parameters are the initial mean, the best candidate seen is kept
+pub struct CmaEs {
+    // initial step size
+    pub sigma: f32,
+    pub generations: usize,
+    // candidates per generation, 4 + 3 ln(n) when None
+    pub lambda: Option<usize>,
+    // above this many parameters only the diagonal of the covariance is adapted (sep-CMA):
+    // the full matrix costs n^2 memory and an O(n^3) decomposition
+    pub full_covariance_limit: usize,
+    pub seed: u64,
+}
+
+impl CmaEs {
+    pub fn new(sigma: f32, generations: usize) -> Self {
+        CmaEs {
+            sigma,
+            generations,
+            lambda: None,
+            full_covariance_limit: 200,
+            seed: 0,
+        }
+    }
+}
+
+impl<X, Mod> Trainer<X, Tensor, Mod> for CmaEs
+    where Mod: Model<X, Tensor> + Parameters
+{
+    fn fit(&self, model: &mut Mod, x: &Vec<X>, y: &Vec<Tensor>) -> TrainReport {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut pred = y.clone();
+        let mut best = model.flat_values();
+        let mut best_score = dataset_score(model, x, y, &mut pred);
+        let diagonal = best.len() > self.full_covariance_limit;
+        let mut cma = CmaState::new(best.iter().map(|&v| v as f64).collect(), self.sigma as f64, self.lambda, diagonal);
+
+        let mut report = TrainReport::default();
+        for generation in 0..self.generations {
+            let mut candidates: Vec<(f32, Vec<f64>)> = (0..cma.lambda)
+                .map(|_| {
+                    let step = cma.sample(&mut rng);
+                    let values: Vec<f32> = cma.mean.iter().zip(step.iter())
+                        .map(|(m, s)| (m + cma.sigma * s) as f32)
+                        .collect();
+                    model.set_flat_values(&values);
+                    let score = dataset_score(model, x, y, &mut pred);
+                    if score < best_score {
+                        best_score = score;
+                        best = values;
+                    }
+                    (score, step)
+                })
+                .collect();
+            candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let steps: Vec<Vec<f64>> = candidates.into_iter().map(|(_, step)| step).collect();
+            cma.update(&steps);
+
+            report.epochs.push(EpochMetrics {
+                epoch: generation,
+                learning_rate: cma.sigma as f32,
+                train_loss: best_score,
+                validation_loss: None,
+            });
+        }
+        model.set_flat_values(&best);
+        report
+    }
+}
+
+// matrices are n x n, row major; a diagonal covariance only keeps its n diagonal values
+struct CmaState {
+    n: usize,
+    diagonal: bool,
+    lambda: usize,
+    // recombination weights of the mu best candidates
+    weights: Vec<f64>,
+    mueff: f64,
+    cc: f64,
+    cs: f64,
+    c1: f64,
+    cmu: f64,
+    damps: f64,
+    // expected norm of a N(0, I) vector
+    chi_n: f64,
+    mean: Vec<f64>,
+    sigma: f64,
+    cov: Vec<f64>,
+    // eigen vectors of cov in columns (empty when diagonal), and square roots of its eigen values
+    basis: Vec<f64>,
+    scales: Vec<f64>,
+    pc: Vec<f64>,
+    ps: Vec<f64>,
+    generation: usize,
+    // generations between two decompositions, the decomposition lags behind cov in between
+    decomposition_gap: usize,
+    decomposed_at: usize,
+}
+
+impl CmaState {
+    fn new(mean: Vec<f64>, sigma: f64, lambda: Option<usize>, diagonal: bool) -> Self {
+        let n = mean.len();
+        assert!(n > 0, "nothing to optimize");
+        let nf = n as f64;
+        let lambda = lambda.unwrap_or(4 + (3. * nf.ln()).floor() as usize).max(2);
+        let mu = lambda / 2;
+        let raw: Vec<f64> = (1..=mu).map(|i| (mu as f64 + 0.5).ln() - (i as f64).ln()).collect();
+        let total: f64 = raw.iter().sum();
+        let weights: Vec<f64> = raw.iter().map(|w| w / total).collect();
+        let mueff = 1. / weights.iter().map(|w| w * w).sum::<f64>();
+        let cs = (mueff + 2.) / (nf + mueff + 5.);
+        let c1 = 2. / ((nf + 1.3).powi(2) + mueff);
+        let cmu = (1. - c1).min(2. * (mueff - 2. + 1. / mueff) / ((nf + 2.).powi(2) + mueff));
+        // sep-CMA learns the n variances faster than the n^2 covariances
+        let (c1, cmu) = match diagonal {
+            true => {
+                let c1 = (c1 * (nf + 2.) / 3.).min(1.);
+                (c1, (cmu * (nf + 2.) / 3.).min(1. - c1))
+            }
+            false => (c1, cmu)
+        };
+        CmaState {
+            n,
+            diagonal,
+            lambda,
+            weights,
+            mueff,
+            cc: (4. + mueff / nf) / (nf + 4. + 2. * mueff / nf),
+            cs,
+            c1,
+            cmu,
+            damps: 1. + 2. * (((mueff - 1.) / (nf + 1.)).sqrt() - 1.).max(0.) + cs,
+            chi_n: nf.sqrt() * (1. - 1. / (4. * nf) + 1. / (21. * nf * nf)),
+            mean,
+            sigma,
+            cov: if diagonal { vec![1.; n] } else { identity(n) },
+            basis: if diagonal { vec![] } else { identity(n) },
+            scales: vec![1.; n],
+            pc: vec![0.; n],
+            ps: vec![0.; n],
+            generation: 0,
+            decomposition_gap: match diagonal {
+                true => 1,
+                false => (1. / (10. * nf * (c1 + cmu))).floor() as usize + 1
+            },
+            decomposed_at: 0,
+        }
+    }
+
+    // a step following N(0, cov), the candidate is mean + sigma * step
+    fn sample<R: Rng>(&self, rng: &mut R) -> Vec<f64> {
+        let z: Vec<f64> = self.scales.iter()
+            .map(|d| {
+                let r: f64 = StandardNormal.sample(rng);
+                d * r
+            })
+            .collect();
+        match self.diagonal {
+            true => z,
+            false => mat_vec(&self.basis, &z)
+        }
+    }
+
+    // steps sorted from the best candidate
+    fn update(&mut self, steps: &[Vec<f64>]) {
+        let n = self.n;
+        let nf = n as f64;
+        let yw: Vec<f64> = (0..n)
+            .map(|i| self.weights.iter().zip(steps).map(|(w, s)| w * s[i]).sum())
+            .collect();
+        for (m, y) in self.mean.iter_mut().zip(yw.iter()) {
+            *m += self.sigma * y;
+        }
+
+        // cov^-1/2 yw = B D^-1 B^T yw
+        let whitened = match self.diagonal {
+            true => yw.iter().zip(self.scales.iter()).map(|(y, d)| y / d).collect(),
+            false => {
+                let projected: Vec<f64> = (0..n)
+                    .map(|j| (0..n).map(|i| self.basis[i * n + j] * yw[i]).sum::<f64>() / self.scales[j])
+                    .collect();
+                mat_vec(&self.basis, &projected)
+            }
+        };
+        let cs_norm = (self.cs * (2. - self.cs) * self.mueff).sqrt();
+        for (p, w) in self.ps.iter_mut().zip(whitened.iter()) {
+            *p = (1. - self.cs) * *p + cs_norm * w;
+        }
+        let ps_norm = self.ps.iter().map(|p| p * p).sum::<f64>().sqrt();
+
+        self.generation += 1;
+        let ps_ratio = ps_norm / (1. - (1. - self.cs).powi(2 * self.generation as i32)).sqrt() / self.chi_n;
+        let hsig = if ps_ratio < 1.4 + 2. / (nf + 1.) { 1. } else { 0. };
+        let cc_norm = (self.cc * (2. - self.cc) * self.mueff).sqrt();
+        for (p, y) in self.pc.iter_mut().zip(yw.iter()) {
+            *p = (1. - self.cc) * *p + hsig * cc_norm * y;
+        }
+
+        let keep = 1. - self.c1 - self.cmu + (1. - hsig) * self.c1 * self.cc * (2. - self.cc);
+        for i in 0..n {
+            let columns = if self.diagonal { i..i + 1 } else { 0..n };
+            for j in columns {
+                let rank_mu: f64 = self.weights.iter().zip(steps).map(|(w, s)| w * s[i] * s[j]).sum();
+                let k = if self.diagonal { i } else { i * n + j };
+                self.cov[k] = keep * self.cov[k]
+                    + self.c1 * self.pc[i] * self.pc[j]
+                    + self.cmu * rank_mu;
+            }
+        }
+        self.sigma *= ((self.cs / self.damps) * (ps_norm / self.chi_n - 1.)).exp();
+
+        if self.diagonal {
+            self.scales = self.cov.iter().map(|v| v.max(1e-20).sqrt()).collect();
+        } else if self.generation - self.decomposed_at >= self.decomposition_gap {
+            let (values, vectors) = jacobi(&self.cov, n);
+            self.scales = values.iter().map(|v| v.max(1e-20).sqrt()).collect();
+            self.basis = vectors;
+            self.decomposed_at = self.generation;
+        }
+    }
+}
+
+fn identity(n: usize) -> Vec<f64> {
+    (0..n * n).map(|i| if i % (n + 1) == 0 { 1. } else { 0. }).collect()
+}
+
+fn mat_vec(matrix: &[f64], v: &[f64]) -> Vec<f64> {
+    matrix.chunks(v.len())
+        .map(|row| row.iter().zip(v).map(|(a, b)| a * b).sum())
+        .collect()
+}
+
+// eigen decomposition of a symmetric matrix by cyclic Jacobi rotations:
+// (eigen values, eigen vectors in columns)
+fn jacobi(matrix: &[f64], n: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut a = matrix.to_vec();
+    let mut v = identity(n);
+    for _sweep in 0..50 {
+        let diagonal: f64 = (0..n).map(|i| a[i * n + i].powi(2)).sum();
+        let off: f64 = (0..n * n).filter(|i| i % (n + 1) != 0).map(|i| a[i].powi(2)).sum();
+        if off <= 1e-24 * diagonal {
+            break;
+        }
+        for p in 0..n {
+            for q in (p + 1)..n {
+                let apq = a[p * n + q];
+                if apq == 0. {
+                    continue;
+                }
+                let theta = (a[q * n + q] - a[p * n + p]) / (2. * apq);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.).sqrt());
+                let c = 1. / (t * t + 1.).sqrt();
+                let s = t * c;
+                let rotate = |m: &mut Vec<f64>, x: usize, y: usize| {
+                    let (mx, my) = (m[x], m[y]);
+                    m[x] = c * mx - s * my;
+                    m[y] = s * mx + c * my;
+                };
+                for k in 0..n {
+                    rotate(&mut a, k * n + p, k * n + q);
+                }
+                for k in 0..n {
+                    rotate(&mut a, p * n + k, q * n + k);
+                }
+                for k in 0..n {
+                    rotate(&mut v, k * n + p, k * n + q);
+                }
+            }
+        }
+    }
+    ((0..n).map(|i| a[i * n + i]).collect(), v)
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::structs::shape4::Shape4;
+    use tensor_lib::tensor::Tensor;
+    use tensor_lib::traits::view::View;
+
+    use crate::algo::es::cma_es::{CmaEs, CmaState, jacobi, mat_vec};
+    use crate::algo::genetic_trainer::dataset_score;
+    use crate::conv2::Conv2;
+    use crate::framework::model::Model;
+    use crate::framework::trainer::Trainer;
+
+    #[test]
+    fn eigen_decomposition() {
+        let m = vec![4., 1., 2., 1., 3., 0., 2., 0., 5.];
+        let (values, vectors) = jacobi(&m, 3);
+        for k in 0..3 {
+            let column: Vec<f64> = (0..3).map(|i| vectors[i * 3 + k]).collect();
+            let image = mat_vec(&m, &column);
+            for i in 0..3 {
+                assert!((image[i] - values[k] * column[i]).abs() < 1e-9);
+            }
+        }
+        assert!((values.iter().sum::<f64>() - 12.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn decomposition_gap() {
+        assert_eq!(CmaState::new(vec![0.; 10], 1., None, false).decomposition_gap, 1);
+        assert!(CmaState::new(vec![0.; 150], 1., None, false).decomposition_gap > 1);
+        assert!(CmaState::new(vec![0.; 150], 1., None, true).basis.is_empty());
+    }
+
+    fn fit_a_conv(trainer: CmaEs) {
+        let target = Conv2::new(3, 1, 1);
+        let xx = vec![Tensor::normal(Shape4::vec3(6, 6, 1), 0.0, 1.0)];
+        let mut yy = vec![target.output_tensor(xx[0].shape())];
+        target.predict(&xx[0], &mut yy[0]);
+
+        let mut model = Conv2::new(3, 1, 1);
+        let report = trainer.fit(&mut model, &xx, &yy);
+        let first = report.epochs[0].train_loss;
+        let last = report.last().unwrap().train_loss;
+        assert!(last < 1e-3 * first, "{} -> {}", first, last);
+
+        // the model keeps the best candidate
+        let mut pred = yy.clone();
+        assert_eq!(dataset_score(&model, &xx, &yy, &mut pred), last);
+    }
+
+    #[test]
+    fn cma_es_fits_a_conv() {
+        fit_a_conv(CmaEs::new(1., 150));
+    }
+
+    #[test]
+    fn sep_cma_es_fits_a_conv() {
+        // the diagonal adapts slower to the correlated pixels
+        let mut trainer = CmaEs::new(1., 400);
+        trainer.full_covariance_limit = 0;
+        fit_a_conv(trainer);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod cma_es;
+pub mod one_fifth;
\ No newline at end of file
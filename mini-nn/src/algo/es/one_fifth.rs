@@ -0,0 +1,156 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Distribution, StandardNormal};
+
+use tensor_lib::tensor::Tensor;
+
+use crate::algo::genetic_trainer::dataset_score;
+use crate::algo::mutations::adaptive_mut::SelfAdaptive;
+use crate::framework::model::Model;
+use crate::framework::parameters::Parameters;
+use crate::framework::trainer::{EpochMetrics, Trainer, TrainReport};
+
+// Rechenberg's rule: more than 1 success out of 5 mutations means the steps are too small
+#[derive(Debug, Clone)]
+pub struct OneFifthRule {
+    // mutations counted before each adjustment
+    pub window: usize,
+    // step size multiplier when too few mutations succeed
+    pub factor: f32,
+    successes: usize,
+    trials: usize,
+}
+
+impl OneFifthRule {
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0);
+        OneFifthRule {
+            window,
+            factor: 0.82,
+            successes: 0,
+            trials: 0,
+        }
+    }
+
+    // new step size, changed once per window
+    pub fn update(&mut self, sigma: f32, success: bool) -> f32 {
+        self.trials += 1;
+        if success {
+            self.successes += 1;
+        }
+        if self.trials < self.window {
+            return sigma;
+        }
+        let rate = self.successes as f32 / self.trials as f32;
+        self.successes = 0;
+        self.trials = 0;
+        match rate {
+            r if r > 0.2 => sigma / self.factor,
+            r if r < 0.2 => sigma * self.factor,
+            _ => sigma
+        }
+    }
+}
+
+// (1+1) evolution strategy: a single child replaces its parent when not worse,
+// the step size of the genome follows the 1/5th success rule
+pub struct OnePlusOne {
+    pub iterations: usize,
+    pub window: usize,
+    pub seed: u64,
+}
+
+impl OnePlusOne {
+    pub fn new(iterations: usize) -> Self {
+        OnePlusOne {
+            iterations,
+            window: 10,
+            seed: 0,
+        }
+    }
+}
+
+impl<X, Mod> Trainer<X, Tensor, SelfAdaptive<Mod>> for OnePlusOne
+    where Mod: Model<X, Tensor> + Parameters
+{
+    fn fit(&self, model: &mut SelfAdaptive<Mod>, x: &Vec<X>, y: &Vec<Tensor>) -> TrainReport {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut rule = OneFifthRule::new(self.window);
+        let mut pred = y.clone();
+        let mut parent = model.model.flat_values();
+        let mut score = dataset_score(&model.model, x, y, &mut pred);
+
+        let mut report = TrainReport::default();
+        for i in 0..self.iterations {
+            let child: Vec<f32> = parent.iter()
+                .map(|&v| {
+                    let r: f32 = StandardNormal.sample(&mut rng);
+                    v + model.sigma * r
+                })
+                .collect();
+            model.model.set_flat_values(&child);
+            let child_score = dataset_score(&model.model, x, y, &mut pred);
+            let success = child_score <= score;
+            if success {
+                parent = child;
+                score = child_score;
+            }
+            model.sigma = rule.update(model.sigma, success);
+            if (i + 1) % self.window == 0 || i + 1 == self.iterations {
+                report.epochs.push(EpochMetrics {
+                    epoch: i,
+                    learning_rate: model.sigma,
+                    train_loss: score,
+                    validation_loss: None,
+                });
+            }
+        }
+        model.model.set_flat_values(&parent);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::structs::shape4::Shape4;
+    use tensor_lib::tensor::Tensor;
+    use tensor_lib::traits::view::View;
+
+    use crate::algo::es::one_fifth::{OneFifthRule, OnePlusOne};
+    use crate::algo::mutations::adaptive_mut::SelfAdaptive;
+    use crate::conv2::Conv2;
+    use crate::framework::model::Model;
+    use crate::framework::trainer::Trainer;
+
+    #[test]
+    fn step_size_follows_success_rate() {
+        let mut rule = OneFifthRule::new(5);
+        let mut sigma = 1.;
+        for i in 0..4 {
+            sigma = rule.update(sigma, i == 0);
+        }
+        assert_eq!(sigma, 1.);
+        // 1 success out of 5: unchanged
+        assert_eq!(rule.update(sigma, false), 1.);
+        let grown = (0..5).fold(1., |s, _| rule.update(s, true));
+        assert!(grown > 1.);
+        let shrunk = (0..5).fold(1., |s, _| rule.update(s, false));
+        assert!(shrunk < 1.);
+    }
+
+    #[test]
+    fn one_plus_one_fits_a_conv() {
+        let target = Conv2::new(3, 1, 1);
+        let xx = vec![Tensor::normal(Shape4::vec3(6, 6, 1), 0.0, 1.0)];
+        let mut yy = vec![target.output_tensor(xx[0].shape())];
+        target.predict(&xx[0], &mut yy[0]);
+
+        let mut model = SelfAdaptive::new(Conv2::new(3, 1, 1), 1.);
+        let report = OnePlusOne::new(500).fit(&mut model, &xx, &yy);
+        let losses: Vec<f32> = report.epochs.iter().map(|e| e.train_loss).collect();
+        assert!(losses.windows(2).all(|w| w[1] <= w[0]));
+        assert!(losses[losses.len() - 1] < 0.1 * losses[0]);
+        // the step size shrinks close to the optimum
+        assert!(model.sigma < 1.);
+    }
+}
@@ -1,14 +1,18 @@
 use crate::algo::population::adn::Adn;
+use crate::algo::population::diversity::Diversity;
 use crate::algo::population::population::Population;
 use crate::framework::model::Model;
 
 pub struct GeneticModel<Mod> {
     pub population: Population<Mod>,
     pub best: Adn<Mod>,
+    pub generation: usize,
+    // measured on the last scored generation
+    pub diversity: Diversity,
 }
 
 impl<Mod> GeneticModel<Mod> {
-    pub fn new<F: Fn() -> Mod>(init: F, size: usize) -> Self {
+    pub fn new<F: FnMut() -> Mod>(mut init: F, size: usize) -> Self {
         let mut items = Vec::with_capacity(size);
         for _i in 0..size {
             items.push(init())
@@ -16,8 +20,16 @@ impl<Mod> GeneticModel<Mod> {
         GeneticModel {
             population: Population::new(items),
             best: Adn::new(init()),
+            generation: 0,
+            diversity: Diversity::default(),
         }
     }
+
+    // cached scores only hold for the dataset they were measured on
+    pub fn forget_scores(&mut self) {
+        self.population.population.iter_mut().for_each(|x| x.scored = false);
+        self.best.scored = false;
+    }
 }
 
 impl<X, Y, Mod: Model<X,Y>> Model<X, Y> for GeneticModel<Mod> {
@@ -1,21 +1,63 @@
+use std::iter::once;
+use std::thread;
+
 use ordered_float::OrderedFloat;
+use rand::Rng;
+use rand::seq::index;
 
+use rust_tools::parallel::parallel_map;
 use tensor_lib::tensor::Tensor;
 
+use crate::algo::crossover::CrossOver;
 use crate::algo::genetic_model::GeneticModel;
-use crate::algo::mutations::conv_mut::ConvMut;
 use crate::algo::mutation::Mutation;
 use crate::algo::population::adn::Adn;
+use crate::algo::population::diversity::Diversity;
 use crate::algo::population::population::Population;
+use crate::algo::selection::Selection;
+use crate::algo::selections::tournament::TournamentSelection;
 use crate::framework::metric::Metric;
 use crate::framework::metrics::mse::MSE;
 use crate::framework::model::Model;
-use crate::framework::trainer::Trainer;
-use crate::algo::crossover::CrossOver;
+use crate::framework::trainer::{EpochMetrics, Trainer, TrainReport};
+
+// mean squared error of a model over a dataset, pred is a buffer shaped like y
+pub fn dataset_score<X, Mod: Model<X, Tensor>>(model: &Mod, x: &[X], y: &[Tensor], pred: &mut [Tensor]) -> f32 {
+    x.iter().zip(pred.iter_mut()).for_each(|(x, p)| model.predict(x, p));
+    MSE::score_vec(&MSE::score_zip(pred, y))
+}
+
+// mean squared error of a model over the samples at the given indices
+pub fn batch_score<X, Mod: Model<X, Tensor>>(model: &Mod, x: &[X], y: &[Tensor], batch: &[usize]) -> f32 {
+    let scores: Vec<f32> = batch.iter()
+        .map(|&i| {
+            let mut pred = y[i].clone();
+            model.predict(&x[i], &mut pred);
+            MSE::score(&pred, &y[i])
+        })
+        .collect();
+    MSE::score_vec(&scores)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Replacement {
+    // the whole population but the elites is replaced every generation
+    Generational,
+    // only the given number of worst individuals is replaced
+    SteadyState(usize),
+}
 
-pub struct GeneticTrainer<Mut, Cr> {
+pub struct GeneticTrainer<Mut, Cr, Sel = TournamentSelection> {
     pub mutations: Population<Mut>,
-    pub crossovers: Population<Cr>
+    pub crossovers: Population<Cr>,
+    pub selection: Sel,
+    // best individuals copied unchanged to the next generation
+    pub elitism: usize,
+    pub replacement: Replacement,
+    // individuals are scored on a random sample of that size, None for the whole dataset
+    pub batch_size: Option<usize>,
+    // worker threads of fit_parallel
+    pub threads: usize,
 }
 
 impl<Mut, Cr> GeneticTrainer<Mut, Cr> {
@@ -23,32 +65,87 @@ impl<Mut, Cr> GeneticTrainer<Mut, Cr> {
         GeneticTrainer {
             mutations: Population::new(mutations),
             crossovers: Population::new(crossovers),
+            selection: TournamentSelection::new(3),
+            elitism: 1,
+            replacement: Replacement::Generational,
+            batch_size: None,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
         }
     }
 }
 
+impl<Mut, Cr, Sel> GeneticTrainer<Mut, Cr, Sel> {
+    pub fn with_selection<S: Selection>(self, selection: S) -> GeneticTrainer<Mut, Cr, S> {
+        GeneticTrainer {
+            mutations: self.mutations,
+            crossovers: self.crossovers,
+            selection,
+            elitism: self.elitism,
+            replacement: self.replacement,
+            batch_size: self.batch_size,
+            threads: self.threads,
+        }
+    }
 
-impl<X, Mod, Mut, Cr> Trainer<X, Tensor, GeneticModel<Mod>> for GeneticTrainer<Mut, Cr>
-    where
-        Mod: Model<X, Tensor> + Clone,
-        Mut: Mutation<Mod>,
-        Cr: CrossOver<Mod>,
+    fn is_full(&self, len: usize) -> bool {
+        !matches!(self.batch_size, Some(n) if n < len)
+    }
 
-{
-    fn fit(&self, model: &mut GeneticModel<Mod>, x: &Vec<X>, y: &Vec<Tensor>) {
-        self.mutations.mutate_pop(&mut model.population);
-        self.crossovers.cross_pop(&mut model.population);
-
-        //update scores
-        let mut pred = y.clone();
-        model.best.adn.predict_map(x, &mut pred);
-        let scores = MSE::score_zip(&pred, y);
-        model.best.score = MSE::score_vec(&scores);
-        // println!("{}", model.best.score);
-        for m in model.population.population.iter_mut() {
-            m.adn.predict_map(x, &mut pred);
-            let scores = MSE::score_zip(&pred, y);
-            m.score = MSE::score_vec(&scores);
+    // samples scored this generation
+    fn batch(&self, len: usize) -> Vec<usize> {
+        match self.is_full(len) {
+            true => (0..len).collect(),
+            false => index::sample(&mut rand::thread_rng(), len, self.batch_size.unwrap()).into_vec(),
+        }
+    }
+
+    // individuals to score, the best one having the index population.len():
+    // on the whole dataset unchanged individuals keep their score,
+    // on a mini-batch everyone is scored again to be compared on the same samples
+    fn pending<Mod>(&self, model: &GeneticModel<Mod>, len: usize) -> Vec<usize> {
+        let full = self.is_full(len);
+        model.population.population.iter().chain(once(&model.best)).enumerate()
+            .filter(|(_, x)| !(full && x.scored))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn individual<Mod>(model: &mut GeneticModel<Mod>, i: usize) -> &mut Adn<Mod> {
+        match model.population.population.get_mut(i) {
+            Some(x) => x,
+            None => &mut model.best,
+        }
+    }
+
+    // scores the models over the worker threads
+    pub fn parallel_scores<X: Sync, Mod: Model<X, Tensor> + Sync>(&self, models: &[&Mod], x: &[X], y: &[Tensor], batch: &[usize]) -> Vec<f32> {
+        parallel_map(models, self.threads, |_, m| batch_score(*m, x, y, batch))
+    }
+
+    // same generation as fit, individuals being scored in parallel:
+    // the workers share the models, so models holding Rc buffers (Sequential) have to use fit
+    pub fn fit_parallel<X: Sync, Mod>(&self, model: &mut GeneticModel<Mod>, x: &[X], y: &[Tensor]) -> TrainReport
+        where Mod: Model<X, Tensor> + Clone + Sync, Mut: Mutation<Mod>, Cr: CrossOver<Mod>, Sel: Selection
+    {
+        let batch = self.batch(x.len());
+        let pending = self.pending(model, x.len());
+        let scores = {
+            let models: Vec<&Mod> = pending.iter()
+                .map(|&i| &model.population.population.get(i).unwrap_or(&model.best).adn)
+                .collect();
+            self.parallel_scores(&models, x, y, &batch)
+        };
+        self.generation(model, &pending, &scores, &mut rand::thread_rng())
+    }
+
+    // stores the new scores, updates the best individual then breeds
+    fn generation<Mod: Clone, R: Rng>(&self, model: &mut GeneticModel<Mod>, pending: &[usize], scores: &[f32], rng: &mut R) -> TrainReport
+        where Mut: Mutation<Mod>, Cr: CrossOver<Mod>, Sel: Selection
+    {
+        for (&i, &score) in pending.iter().zip(scores.iter()) {
+            let x = GeneticTrainer::<Mut, Cr, Sel>::individual(model, i);
+            x.score = score;
+            x.scored = true;
         }
 
         //update best
@@ -56,15 +153,70 @@ impl<X, Mod, Mut, Cr> Trainer<X, Tensor, GeneticModel<Mod>> for GeneticTrainer<M
         if new_best.score < model.best.score {
             model.best = new_best;
         }
+        model.diversity = Diversity::of_scores(&model.population);
+
+        self.next_generation(&mut model.population, rng);
+        model.generation += 1;
 
-        // update population to the fittests
-        let old_ppop = model.population.len();
-        let population_limit = model.population.len() -1;
-        model.population.population.sort_by_key(|x| OrderedFloat(x.score));
-        model.population.population.drain(..population_limit);
+        // one generation
+        TrainReport {
+            epochs: vec![EpochMetrics {
+                epoch: model.generation - 1,
+                learning_rate: 0.,
+                train_loss: model.best.score,
+                validation_loss: None,
+            }],
+            stopped_early: false,
+        }
+    }
 
-        while model.population.len() < old_ppop {
-            model.population.population.push(Adn::new(model.best.adn.clone()));
+    // number of children bred for a population of the given size
+    pub fn offspring(&self, size: usize) -> usize {
+        let free = size - self.elitism.min(size);
+        match self.replacement {
+            Replacement::Generational => free,
+            Replacement::SteadyState(n) => n.min(free),
         }
     }
-}
\ No newline at end of file
+
+    // replaces the worst scored individuals with mutated children of selected parents
+    pub fn next_generation<Mod: Clone, R: Rng>(&self, population: &mut Population<Mod>, rng: &mut R)
+        where Mut: Mutation<Mod>, Cr: CrossOver<Mod>, Sel: Selection
+    {
+        let size = population.len();
+        let count = self.offspring(size);
+        population.population.sort_by_key(|x| OrderedFloat(x.score));
+
+        let mut children = Vec::with_capacity(count + 1);
+        while children.len() < count {
+            let (i, j) = self.selection.select_pair(&population.population, rng);
+            let mut a = population.population[i].adn.clone();
+            let mut b = population.population[j].adn.clone();
+            self.crossovers.cross(&mut a, &mut b, rng);
+            self.mutations.mutate(&mut a, rng);
+            self.mutations.mutate(&mut b, rng);
+            children.push(Adn::new(a));
+            children.push(Adn::new(b));
+        }
+        children.truncate(count);
+        population.population.truncate(size - count);
+        population.population.extend(children);
+    }
+}
+
+impl<X, Mod, Mut, Cr, Sel> Trainer<X, Tensor, GeneticModel<Mod>> for GeneticTrainer<Mut, Cr, Sel>
+    where
+        Mod: Model<X, Tensor> + Clone,
+        Mut: Mutation<Mod>,
+        Cr: CrossOver<Mod>,
+        Sel: Selection,
+{
+    fn fit(&self, model: &mut GeneticModel<Mod>, x: &Vec<X>, y: &Vec<Tensor>) -> TrainReport {
+        let batch = self.batch(x.len());
+        let pending = self.pending(model, x.len());
+        let scores: Vec<f32> = pending.iter()
+            .map(|&i| batch_score(&model.population.population.get(i).unwrap_or(&model.best).adn, x, y, &batch))
+            .collect();
+        self.generation(model, &pending, &scores, &mut rand::thread_rng())
+    }
+}
@@ -5,6 +5,9 @@ pub mod genetic_trainer;
 pub mod mutation;
 pub mod crossover;
 pub mod crossovers;
+pub mod selection;
+pub mod selections;
+pub mod es;
 
 #[cfg(test)]
 mod test {
@@ -12,24 +15,32 @@ mod test {
     use std::iter::FromIterator;
 
     use log::LevelFilter;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
     use rand_distr::NormalError;
 
     use rust_tools::bench::Bench;
     use rust_tools::loggers::init_logs;
     use tensor_lib::structs::shape4::Shape4;
     use tensor_lib::tensor::Tensor;
+    use tensor_lib::traits::view::View;
 
     use crate::algo::crossovers::conv_mut::ConvCross;
     use crate::algo::crossovers::mix_cross::MixCross;
+    use crate::algo::es::one_fifth::OneFifthRule;
     use crate::algo::genetic_model::GeneticModel;
-    use crate::algo::genetic_trainer::GeneticTrainer;
+    use crate::algo::genetic_trainer::{batch_score, GeneticTrainer, Replacement};
     use crate::algo::mutations::add_mut::AddMut;
     use crate::algo::mutations::conv_mut::ConvMut;
+    use crate::algo::selections::rank::RankSelection;
     use crate::conv2::Conv2;
     use crate::framework::model::Model;
+    use crate::framework::parameters::Parameters;
     use crate::framework::trainer::Trainer;
+    use crate::layers::sequential::Sequential;
 
     #[test]
+    #[ignore]
     fn test_trainer() -> Result<(), NormalError> {
         init_logs(LevelFilter::Trace);
         // CONFIG
@@ -76,8 +87,8 @@ mod test {
         let mut pred = yy[0].clone();
         let mut bench = Bench::new("Genetic algorithm");
         let mut last_best: f32 = 1_000_000.0;
-        let mut stable_period = 0;
-        let mut treshold = 1.0;
+        // the mutation powers follow the 1/5th success rule over the rounds
+        let mut rule = OneFifthRule::new(5);
         while bench.for_iterations(100_000) {
             let mut round = Bench::new(&format!("Round-{}", bench.loops));
             while round.for_iterations(100) {
@@ -85,26 +96,13 @@ mod test {
             }
             let delta = (model.best.score - last_best).abs();
             log::info!("loop: {} best: {} delta: {}", bench.loops, model.best, delta);
-            if delta < treshold {
-                if stable_period >= 2 {
-                    for muta in trainer.mutations.population.iter_mut() {
-                        let filter = match &muta.adn.filter {
-                            None => { 0.0 }
-                            Some(x) => x.power
-                        };
-                        let bias = match &muta.adn.bias {
-                            None => { 0.0 }
-                            Some(x) => x.power
-                        };
-                        muta.adn.filter.iter_mut().for_each(|x| x.power *= 0.5);
-                        muta.adn.bias.iter_mut().for_each(|x| x.power *= 0.5);
-                        log::info!("Reduce learning rate: bias={} filter={}", bias, filter);
-                    }
-                    treshold *= 0.5;
-                    stable_period = 0;
-                    log::info!("Reduce treshold: {}", treshold);
+            let scale = rule.update(1.0, model.best.score < last_best);
+            if scale != 1.0 {
+                for muta in trainer.mutations.population.iter_mut() {
+                    muta.adn.filter.iter_mut().for_each(|x| x.power *= scale);
+                    muta.adn.bias.iter_mut().for_each(|x| x.power *= scale);
                 }
-                stable_period += 1;
+                log::info!("Scale mutation powers: {}", scale);
             }
 
             // model.predict(&xx[0], &mut pred);
@@ -117,4 +115,106 @@ mod test {
 
         Ok(())
     }
-}
+
+    #[test]
+    fn elitism_keeps_the_best() {
+        let x_shape = Shape4::vec3(6, 6, 1);
+        let target = Conv2::new(3, 1, 1);
+        let xx = vec![Tensor::normal(x_shape, 0.0, 1.0)];
+        let mut yy = vec![target.output_tensor(xx[0].shape())];
+        target.predict(&xx[0], &mut yy[0]);
+
+        let mut model = GeneticModel::new(|| Conv2::new(3, 1, 1), 20);
+        let mut trainer = GeneticTrainer::new(
+            vec![ConvMut::filter(AddMut::new(0.5)), ConvMut::bias(AddMut::new(0.5))],
+            vec![ConvCross::filter(MixCross {})],
+        ).with_selection(RankSelection::new(1.8));
+        trainer.elitism = 2;
+
+        let mut last_best = f32::INFINITY;
+        let mut last_elite = f32::INFINITY;
+        for generation in 0..20 {
+            if generation == 10 {
+                trainer.replacement = Replacement::SteadyState(5);
+            }
+            let report = trainer.fit(&mut model, &xx, &yy);
+            let best = report.last().unwrap().train_loss;
+            assert!(best <= last_best);
+            // the elites come first, still scored
+            let elite = model.population.population[0].score;
+            assert!(elite <= last_elite && elite >= model.best.score);
+            assert_eq!(model.population.len(), 20);
+            assert!(model.diversity.score_std > 0. && model.diversity.distinct_scores > 0.);
+            last_best = best;
+            last_elite = elite;
+        }
+        assert_eq!(model.generation, 20);
+        assert_eq!(trainer.offspring(20), 5);
+    }
+
+    #[test]
+    fn seeded_breeding() {
+        let trainer = GeneticTrainer::new(
+            vec![ConvMut::filter(AddMut::new(0.5)), ConvMut::bias(AddMut::new(0.5))],
+            vec![ConvCross::filter(MixCross {})],
+        );
+        let bred = |seed: u64| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut model = GeneticModel::new(|| Conv2::random(3, 1, 1, &mut rng), 6);
+            for (i, x) in model.population.population.iter_mut().enumerate() {
+                x.score = i as f32;
+            }
+            let mut rng = StdRng::seed_from_u64(seed);
+            trainer.next_generation(&mut model.population, &mut rng);
+            model.population.population.iter().map(|x| x.adn.flat_values()).collect::<Vec<_>>()
+        };
+        assert_eq!(bred(1), bred(1));
+        assert_ne!(bred(1), bred(2));
+    }
+
+    #[test]
+    fn evolve_a_sequential() {
+        // y = 2 x0 - x1
+        let xx: Vec<Tensor> = (0..8).map(|i| Tensor::from_vec(vec![i as f32 / 8., (i % 3) as f32 / 3.], &[2])).collect();
+        let yy: Vec<Tensor> = xx.iter().map(|x| Tensor::from_vec(vec![2. * x.get(0) - x.get(1)], &[1])).collect();
+
+        let mut model = GeneticModel::new(|| Sequential::new(&[2]).dense(1), 30);
+        let trainer: GeneticTrainer<AddMut, MixCross> = GeneticTrainer::new(vec![AddMut::new(0.3)], vec![MixCross {}]);
+        let first = trainer.fit(&mut model, &xx, &yy).last().unwrap().train_loss;
+        let mut last = first;
+        for _ in 0..100 {
+            last = trainer.fit(&mut model, &xx, &yy).last().unwrap().train_loss;
+        }
+        assert!(last < 0.1 * first, "{} -> {}", first, last);
+    }
+
+    #[test]
+    fn cached_and_parallel_scores() {
+        let xx: Vec<Tensor> = (0..6).map(|_| Tensor::normal(Shape4::vec3(4, 4, 1), 0., 1.)).collect();
+        let yy: Vec<Tensor> = (0..6).map(|_| Tensor::normal(Shape4::vec3(2, 2, 1), 0., 1.)).collect();
+        let mut trainer: GeneticTrainer<AddMut, MixCross> = GeneticTrainer::new(vec![AddMut::new(0.3)], vec![MixCross {}]);
+        trainer.elitism = 4;
+
+        // an unchanged elite keeps its score on the whole dataset
+        let mut model = GeneticModel::new(|| Conv2::new(3, 1, 1), 8);
+        trainer.fit_parallel(&mut model, &xx, &yy);
+        assert!(model.population.population.iter().take(4).all(|x| x.scored));
+        assert!(model.population.population.iter().skip(4).all(|x| !x.scored));
+        model.population.population[0].score = -1.;
+        trainer.fit_parallel(&mut model, &xx, &yy);
+        assert_eq!(model.best.score, -1.);
+
+        // mini-batches score everyone again
+        trainer.batch_size = Some(3);
+        model.forget_scores();
+        model.population.population[0].score = -1.;
+        trainer.fit(&mut model, &xx, &yy);
+        assert!(model.best.score >= 0.);
+
+        let models: Vec<&Conv2> = model.population.population.iter().map(|x| &x.adn).collect();
+        let batch = [0, 2, 5];
+        let expected: Vec<f32> = models.iter().map(|m| batch_score(*m, &xx, &yy, &batch)).collect();
+        trainer.threads = 3;
+        assert_eq!(trainer.parallel_scores(&models, &xx, &yy, &batch), expected);
+    }
+}
\ No newline at end of file
@@ -1,18 +1,20 @@
+use rand::Rng;
+
 use crate::algo::population::population::Population;
 
 pub trait Mutation<Mod> {
-    fn mutate(&self, adn: &mut Mod);
-    fn mutate_pop(&self, population: &mut Population<Mod>) {
+    fn mutate<R: Rng>(&self, adn: &mut Mod, rng: &mut R);
+    fn mutate_pop<R: Rng>(&self, population: &mut Population<Mod>, rng: &mut R) {
         for x in population.population.iter_mut() {
-            self.mutate(&mut x.adn);
+            self.mutate(&mut x.adn, rng);
         }
     }
 }
 
 impl<Mod, Mut: Mutation<Mod>> Mutation<Mod> for Population<Mut> {
-    fn mutate(&self, adn: &mut Mod) {
+    fn mutate<R: Rng>(&self, adn: &mut Mod, rng: &mut R) {
         for mutator in self.population.iter() {
-            mutator.adn.mutate(adn);
+            mutator.adn.mutate(adn, rng);
         }
     }
 }
@@ -0,0 +1,82 @@
+use std::fmt::{Display, Formatter};
+use std::fmt;
+
+use rand::Rng;
+use rand_distr::{Distribution, StandardNormal};
+
+use tensor_lib::tensor::Tensor;
+
+use crate::algo::mutation::Mutation;
+use crate::framework::model::Model;
+use crate::framework::parameters::Parameters;
+
+// a genome carrying its own mutation step size, evolved along with it
+#[derive(Debug, Clone)]
+pub struct SelfAdaptive<Mod> {
+    pub model: Mod,
+    pub sigma: f32,
+}
+
+impl<Mod> SelfAdaptive<Mod> {
+    pub fn new(model: Mod, sigma: f32) -> Self {
+        SelfAdaptive { model, sigma }
+    }
+}
+
+impl<X, Y, Mod: Model<X, Y>> Model<X, Y> for SelfAdaptive<Mod> {
+    fn predict(&self, x: &X, y: &mut Y) {
+        self.model.predict(x, y);
+    }
+}
+
+impl<Mod: Parameters> Parameters for SelfAdaptive<Mod> {
+    fn named_parameters(&self) -> Vec<(String, Tensor)> {
+        self.model.named_parameters()
+    }
+
+    fn set_parameters(&mut self, values: Vec<Tensor>) {
+        self.model.set_parameters(values)
+    }
+
+    fn flat_update<F: FnOnce(f32) -> f32>(&mut self, index: usize, f: F) {
+        self.model.flat_update(index, f)
+    }
+}
+
+impl<Mod: Display> Display for SelfAdaptive<Mod> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "sigma: {}\n{}", self.sigma, self.model)
+    }
+}
+
+// log-normal self-adaptation: the step size mutates first, then every parameter
+// moves by a gaussian of the new step size
+pub struct AdaptiveMut {
+    // learning rate of the step size, 1 / sqrt(n) is usual
+    pub tau: f32,
+    pub min_sigma: f32,
+}
+
+impl AdaptiveMut {
+    pub fn new(tau: f32) -> Self {
+        AdaptiveMut { tau, min_sigma: 1e-6 }
+    }
+
+    pub fn for_parameters(count: usize) -> Self {
+        AdaptiveMut::new(1. / (count.max(1) as f32).sqrt())
+    }
+}
+
+impl<Mod: Parameters> Mutation<SelfAdaptive<Mod>> for AdaptiveMut {
+    fn mutate<R: Rng>(&self, adn: &mut SelfAdaptive<Mod>, rng: &mut R) {
+        let r: f32 = StandardNormal.sample(rng);
+        adn.sigma = (adn.sigma * (self.tau * r).exp()).max(self.min_sigma);
+        let values: Vec<f32> = adn.model.flat_values().into_iter()
+            .map(|x| {
+                let r: f32 = StandardNormal.sample(rng);
+                x + adn.sigma * r
+            })
+            .collect();
+        adn.model.set_flat_values(&values);
+    }
+}
@@ -1,10 +1,9 @@
+use rand::Rng;
 use rand_distr::Distribution;
 use rand_distr::Normal;
 
-use tensor_lib::tensor::Tensor;
-use tensor_lib::traits::view::View;
-
 use crate::algo::mutation::Mutation;
+use crate::framework::parameters::Parameters;
 use rand::distributions::Uniform;
 
 pub struct AddMut {
@@ -17,19 +16,18 @@ impl AddMut {
     }
 }
 
-impl Mutation<Tensor> for AddMut {
-    fn mutate(&self, m: &mut Tensor) {
-        let mut rng = rand::thread_rng();
+// moves one random parameter of the model
+impl<P: Parameters> Mutation<P> for AddMut {
+    fn mutate<R: Rng>(&self, m: &mut P, rng: &mut R) {
         let normal = Normal::new(0.0, 1.0).unwrap();
-        let offset = Uniform::new(0, m.shape().len()).sample(&mut rng);
-        let r = normal.sample(&mut rng);
-        let x = m.get(offset)  + r * self.power;
-        m.insert(offset, x);
+        let offset = Uniform::new(0, m.parameter_count()).sample(rng);
+        let r = normal.sample(rng);
+        m.flat_update(offset, |x| x + r * self.power);
 
 
         // let normal = Normal::new(0.0, 1.0).unwrap();
         // for i in 0..adn.shape().len() {
-        //     let r = normal.sample(&mut rng);
+        //     let r = normal.sample(rng);
         //     if r > 0.0 {
         //         let x = adn.get(i) + self.power;
         //         adn.insert(i, x);
@@ -1,3 +1,5 @@
+use rand::Rng;
+
 use tensor_lib::tensor::Tensor;
 
 use crate::algo::mutation::Mutation;
@@ -19,16 +21,16 @@ impl<Mut: Mutation<Tensor>> ConvMut<Mut> {
 
 
 impl<Mut: Mutation<Tensor>> Mutation<Conv2> for ConvMut<Mut> {
-    fn mutate(&self, m: &mut Conv2) {
+    fn mutate<R: Rng>(&self, m: &mut Conv2, rng: &mut R) {
         match &self.bias {
             Some(mutation) => {
-                mutation.mutate(&mut m.bias);
+                mutation.mutate(&mut m.bias, rng);
             }
             _ => {}
         }
         match &self.filter {
             Some(mutation) => {
-                mutation.mutate(&mut m.filter);
+                mutation.mutate(&mut m.filter, rng);
             }
             _ => {}
         }
@@ -1,6 +1,7 @@
 pub mod add_mut;
 pub mod mult_mut;
 pub mod conv_mut;
+pub mod adaptive_mut;
 
 #[cfg(test)]
 mod tests {
@@ -8,8 +9,12 @@ mod tests {
     use tensor_lib::tensor::Tensor;
 
     use crate::algo::mutation::Mutation;
+    use crate::algo::mutations::adaptive_mut::{AdaptiveMut, SelfAdaptive};
     use crate::algo::mutations::add_mut::AddMut;
+    use crate::algo::mutations::mult_mut::MulMut;
     use crate::algo::population::population::Population;
+    use crate::framework::parameters::Parameters;
+    use crate::layers::sequential::Sequential;
 
     #[test]
     fn test_mutate() {
@@ -24,8 +29,33 @@ mod tests {
 
         log::info!("{}", population);
         for i in 0..5 {
-            mutations.mutate_pop(&mut population);
+            mutations.mutate_pop(&mut population, &mut rand::thread_rng());
             log::info!("{}", population);
         }
     }
+
+    #[test]
+    fn test_adaptive_mutate() {
+        let mut adn = SelfAdaptive::new(Tensor::new(Shape4::vec1(100), 0_f32), 1.);
+        let mutation = AdaptiveMut::for_parameters(adn.model.parameter_count());
+        assert_eq!(mutation.tau, 0.1);
+        mutation.mutate(&mut adn, &mut rand::thread_rng());
+        assert_ne!(adn.sigma, 1.);
+        let values = adn.model.flat_values();
+        let std = (values.iter().map(|v| v * v).sum::<f32>() / 100.).sqrt();
+        assert!(std > 0.5 * adn.sigma && std < 1.5 * adn.sigma);
+    }
+
+    #[test]
+    fn single_value_mutations() {
+        let mut rng = rand::thread_rng();
+        let mut net = Sequential::new(&[4]).dense(3).relu().dense(2);
+        let copy = net.clone();
+        let before = net.flat_values();
+        AddMut::new(1.).mutate(&mut net, &mut rng);
+        MulMut::new(1.).mutate(&mut net, &mut rng);
+        let changed = net.flat_values().iter().zip(before.iter()).filter(|(a, b)| a != b).count();
+        assert!(changed == 1 || changed == 2);
+        assert_eq!(copy.flat_values(), before);
+    }
 }
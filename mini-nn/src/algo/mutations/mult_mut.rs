@@ -1,9 +1,9 @@
+use rand::Rng;
 use rand::distributions::Uniform;
 use rand_distr::{Distribution, Normal};
 
 use crate::algo::mutation::Mutation;
-use tensor_lib::tensor::Tensor;
-use tensor_lib::traits::view::View;
+use crate::framework::parameters::Parameters;
 
 pub struct MulMut {
     power: f32
@@ -15,14 +15,13 @@ impl MulMut {
     }
 }
 
-impl Mutation<Tensor> for MulMut {
-    fn mutate(&self, m: &mut Tensor) {
-        let mut rng = rand::thread_rng();
+// scales one random parameter of the model
+impl<P: Parameters> Mutation<P> for MulMut {
+    fn mutate<R: Rng>(&self, m: &mut P, rng: &mut R) {
 
         let normal = Normal::new(0.0, 1.0).unwrap();
-        let offset = Uniform::new(0, m.shape().len()).sample(&mut rng);
-        let r = normal.sample(&mut rng);
-        let x = m.get(offset) * r * self.power;
-        m.insert(offset, x);
+        let offset = Uniform::new(0, m.parameter_count()).sample(rng);
+        let r = normal.sample(rng);
+        m.flat_update(offset, |x| x * r * self.power);
     }
 }
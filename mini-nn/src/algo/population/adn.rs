@@ -5,11 +5,13 @@ use std::fmt;
 pub struct Adn<T> {
     pub adn: T,
     pub score: f32,
+    // the score is kept while the individual is unchanged
+    pub scored: bool,
 }
 
 impl<T> Adn<T> {
     pub fn new(adn: T) -> Self {
-        Adn { adn, score: 0_f32 }
+        Adn { adn, score: 0_f32, scored: false }
     }
 }
 
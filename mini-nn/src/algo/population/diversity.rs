@@ -0,0 +1,62 @@
+use std::fmt::{Display, Formatter};
+use std::fmt;
+
+use ordered_float::OrderedFloat;
+
+use crate::algo::population::population::Population;
+
+// spread of a population: a collapsing diversity means a premature convergence
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diversity {
+    pub score_mean: f32,
+    pub score_std: f32,
+    // fraction of different scores, 1 when every individual differs
+    pub distinct_scores: f32,
+    // mean distance between genomes, when a distance is given
+    pub mean_distance: Option<f32>,
+}
+
+impl Diversity {
+    pub fn of_scores<T>(population: &Population<T>) -> Self {
+        let n = population.len().max(1) as f32;
+        let scores: Vec<f32> = population.population.iter().map(|x| x.score).collect();
+        let mean = scores.iter().sum::<f32>() / n;
+        let variance = scores.iter().map(|s| (s - mean).powi(2)).sum::<f32>() / n;
+        let mut distinct: Vec<_> = scores.iter().map(|&s| OrderedFloat(s)).collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+        Diversity {
+            score_mean: mean,
+            score_std: variance.sqrt(),
+            distinct_scores: distinct.len() as f32 / n,
+            mean_distance: None,
+        }
+    }
+
+    // averages the distance over all the pairs of individuals
+    pub fn with_distance<T, F: Fn(&T, &T) -> f32>(population: &Population<T>, distance: F) -> Self {
+        let adns = &population.population;
+        let mut total = 0.;
+        let mut pairs = 0;
+        for i in 0..adns.len() {
+            for j in (i + 1)..adns.len() {
+                total += distance(&adns[i].adn, &adns[j].adn);
+                pairs += 1;
+            }
+        }
+        Diversity {
+            mean_distance: Some(total / pairs.max(1) as f32),
+            ..Diversity::of_scores(population)
+        }
+    }
+}
+
+impl Display for Diversity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "score {:.3} ± {:.3}, {:.0}% distinct", self.score_mean, self.score_std, 100. * self.distinct_scores)?;
+        match self.mean_distance {
+            Some(d) => write!(f, ", distance {:.3}", d),
+            None => Ok(())
+        }
+    }
+}
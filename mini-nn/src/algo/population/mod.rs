@@ -1,2 +1,3 @@
 pub mod population;
 pub mod adn;
+pub mod diversity;
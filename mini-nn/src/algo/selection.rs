@@ -0,0 +1,13 @@
+use rand::Rng;
+
+use crate::algo::population::adn::Adn;
+
+// picks parents for the next generation, scores are minimized
+pub trait Selection {
+    // index of the selected individual
+    fn select<T, R: Rng>(&self, population: &[Adn<T>], rng: &mut R) -> usize;
+
+    fn select_pair<T, R: Rng>(&self, population: &[Adn<T>], rng: &mut R) -> (usize, usize) {
+        (self.select(population, rng), self.select(population, rng))
+    }
+}
@@ -0,0 +1,42 @@
+pub mod rank;
+pub mod roulette;
+pub mod tournament;
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::algo::population::population::Population;
+    use crate::algo::selection::Selection;
+    use crate::algo::selections::rank::RankSelection;
+    use crate::algo::selections::roulette::RouletteSelection;
+    use crate::algo::selections::tournament::TournamentSelection;
+
+    // selection counts of each individual, scored by their index
+    fn counts<S: Selection>(selection: &S) -> Vec<usize> {
+        let mut population = Population::new(vec![(); 5]);
+        population.population.iter_mut().enumerate().for_each(|(i, x)| x.score = i as f32);
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut res = vec![0; 5];
+        for _ in 0..5000 {
+            res[selection.select(&population.population, &mut rng)] += 1;
+        }
+        res
+    }
+
+    #[test]
+    fn lower_scores_are_preferred() {
+        for res in [
+            counts(&TournamentSelection::new(3)),
+            counts(&RouletteSelection::new()),
+            counts(&RankSelection::new(1.5)),
+        ].iter() {
+            assert!(res.windows(2).all(|w| w[0] > w[1]), "{:?}", res);
+        }
+        // the worst of 5 wins a tournament of 3 only when drawn 3 times
+        assert!(counts(&TournamentSelection::new(3))[4] < 100);
+        assert_eq!(counts(&TournamentSelection::new(1)).iter().filter(|&&n| n > 800).count(), 5);
+        assert_eq!(counts(&RankSelection::new(2.))[4], 0);
+    }
+}
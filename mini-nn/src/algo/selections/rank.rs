@@ -0,0 +1,37 @@
+use ordered_float::OrderedFloat;
+use rand::distributions::WeightedIndex;
+use rand::Rng;
+use rand_distr::Distribution;
+
+use crate::algo::population::adn::Adn;
+use crate::algo::selection::Selection;
+
+// linear ranking: the best individual is `pressure` times more likely than average,
+// the worst 2 - pressure times, whatever the scale of the scores
+#[derive(Debug, Clone)]
+pub struct RankSelection {
+    pub pressure: f32,
+}
+
+impl RankSelection {
+    pub fn new(pressure: f32) -> Self {
+        assert!((1. ..=2.).contains(&pressure), "pressure {} is out of [1, 2]", pressure);
+        RankSelection { pressure }
+    }
+}
+
+impl Selection for RankSelection {
+    fn select<T, R: Rng>(&self, population: &[Adn<T>], rng: &mut R) -> usize {
+        let n = population.len();
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by_key(|&i| OrderedFloat(population[i].score));
+        let step = 2. * (self.pressure - 1.) / (n.max(2) - 1) as f32;
+        let weights = (0..n).map(|rank| self.pressure - step * rank as f32);
+        // the worst individual keeps a null weight with a pressure of 2
+        let rank = match WeightedIndex::new(weights) {
+            Ok(dist) => dist.sample(rng),
+            Err(_) => 0
+        };
+        order[rank]
+    }
+}
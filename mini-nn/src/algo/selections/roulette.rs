@@ -0,0 +1,24 @@
+use rand::distributions::WeightedIndex;
+use rand::Rng;
+use rand_distr::Distribution;
+
+use crate::algo::population::adn::Adn;
+use crate::algo::selection::Selection;
+
+// fitness proportionate: an individual weighs 1 / (1 + score - best score)
+#[derive(Debug, Clone, Default)]
+pub struct RouletteSelection {}
+
+impl RouletteSelection {
+    pub fn new() -> Self {
+        RouletteSelection {}
+    }
+}
+
+impl Selection for RouletteSelection {
+    fn select<T, R: Rng>(&self, population: &[Adn<T>], rng: &mut R) -> usize {
+        let best = population.iter().map(|x| x.score).fold(f32::INFINITY, f32::min);
+        let weights = population.iter().map(|x| 1. / (1. + x.score - best));
+        WeightedIndex::new(weights).unwrap().sample(rng)
+    }
+}
@@ -0,0 +1,27 @@
+use ordered_float::OrderedFloat;
+use rand::Rng;
+
+use crate::algo::population::adn::Adn;
+use crate::algo::selection::Selection;
+
+// best of `size` individuals drawn with replacement, larger sizes select harder
+#[derive(Debug, Clone)]
+pub struct TournamentSelection {
+    pub size: usize,
+}
+
+impl TournamentSelection {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0);
+        TournamentSelection { size }
+    }
+}
+
+impl Selection for TournamentSelection {
+    fn select<T, R: Rng>(&self, population: &[Adn<T>], rng: &mut R) -> usize {
+        (0..self.size)
+            .map(|_| rng.gen_range(0..population.len()))
+            .min_by_key(|&i| OrderedFloat(population[i].score))
+            .unwrap()
+    }
+}
@@ -1,6 +1,9 @@
 use std::fmt::{Display, Formatter};
 use std::fmt;
+use std::io;
+use std::io::{Read, Write};
 
+use rand::Rng;
 use rand_distr::Normal;
 
 use tensor_lib::structs::offset4::Offset4;
@@ -17,10 +20,15 @@ pub struct Conv2 {
 
 impl Conv2 {
     pub fn new(kernel_size: usize, in_features: usize, out_features: usize) -> Self {
+        Conv2::random(kernel_size, in_features, out_features, &mut rand::thread_rng())
+    }
+
+    pub fn random<R: Rng>(kernel_size: usize, in_features: usize, out_features: usize, rng: &mut R) -> Self {
         let shape = Shape4::vec4(kernel_size, kernel_size, in_features, out_features);
+        let normal = Normal::new(0.0, 1.0).unwrap();
         Conv2 {
-            filter: Tensor::normal(shape.clone(), 0.0, 1.0),
-            bias: Tensor::normal(shape.clone(), 0.0, 1.0),
+            filter: Tensor::from_distrib_rng(shape.clone(), normal, rng),
+            bias: Tensor::from_distrib_rng(shape.clone(), normal, rng),
         }
     }
 
@@ -34,32 +42,25 @@ impl Conv2 {
         Tensor::new(output_shape, 0_f32)
     }
 
+    pub fn write_to<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        self.filter.write_to(out)?;
+        self.bias.write_to(out)
+    }
 
-    fn compute_at(&self, out_x: usize, out_y: usize, feat_out: usize, input: &Tensor) -> f32 {
-        let kw = self.shape().x().unwrap();
-        let kh = self.shape().y().unwrap();
-        let kd = self.shape().z().unwrap();
-
-        assert!(out_x < input.shape().x().unwrap() - kw + 1);
-        assert!(out_y < input.shape().y().unwrap() - kh + 1);
-        assert!(feat_out < self.shape().t().unwrap());
-        let mut res = 0_f32;
-        for feat_in in 0..kd {
-            for kx in 0..kw {
-                for ky in 0..kh {
-                    let offset = Offset4(kx, ky, feat_in, 0);
-                    let kernel_offset = self.filter.shape().index(&offset);
-                    let a = self.filter.get(kernel_offset);
-                    let b = self.bias.get(kernel_offset);
-
-                    let offset2 = Offset4(out_x + kx, out_y + ky, feat_in, 0);
-                    let input_offset = input.shape().index(&offset2);
-                    let k = input.get(input_offset);
-                    res += a * k + b
-                }
-            }
+    pub fn read_from<R: Read>(input: &mut R) -> io::Result<Self> {
+        let filter = Tensor::read_from(input)?;
+        let bias = Tensor::read_from(input)?;
+        if filter.ndim() != 4 || filter.dims() != bias.dims() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                      format!("conv2 filter {:?} and bias {:?}", filter.dims(), bias.dims())));
         }
-        res
+        Ok(Conv2 { filter, bias })
+    }
+
+    // the bias is added once per filter weight
+    fn bias_sum(&self) -> Tensor {
+        let features = self.shape().t().unwrap();
+        self.bias.sum_to(&[1, 1, 1, features]).reshape(&[1, 1, features])
     }
 }
 
@@ -80,16 +81,8 @@ impl Model<Tensor, Tensor> for Conv2 {
         assert_eq!(output.shape().x().unwrap(), (input.shape().x() - self.shape().x()).unwrap() + 1);
         assert_eq!(output.shape().y().unwrap(), (input.shape().y() - self.shape().y()).unwrap() + 1);
 
-        for feat_o in 0..output.shape().z().unwrap() {
-            for x in 0..output.shape().x().unwrap() {
-                for y in 0..output.shape().y().unwrap() {
-                    let res = self.compute_at(x, y, feat_o, input);
-                    let offset = Offset4(x, y, feat_o, 0);
-                    let offset = output.shape().index(&offset);
-                    output.insert(offset, res);
-                }
-            }
-        }
+        let res = &input.conv2d(&self.filter, 0, 1) + &self.bias_sum();
+        output.copy_from(&res);
     }
 }
 
@@ -110,6 +103,9 @@ impl Clone for Conv2 {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use tensor_lib::structs::offset4::Offset4;
     use tensor_lib::structs::shape4::Shape4;
     use tensor_lib::tensor::Tensor;
     use tensor_lib::traits::view::View;
@@ -118,6 +114,42 @@ mod tests {
     use crate::framework::model::Model;
     use rust_tools::bench::Bench;
 
+    // the scalar loops predict used before im2col (now reading the filter of feat_out), kept as the reference
+    fn compute_at(conv: &Conv2, out_x: usize, out_y: usize, feat_out: usize, input: &Tensor) -> f32 {
+        let kw = conv.shape().x().unwrap();
+        let kh = conv.shape().y().unwrap();
+        let kd = conv.shape().z().unwrap();
+        let mut res = 0_f32;
+        for feat_in in 0..kd {
+            for kx in 0..kw {
+                for ky in 0..kh {
+                    let offset = Offset4(kx, ky, feat_in, feat_out);
+                    let kernel_offset = conv.filter.shape().index(&offset);
+                    let a = conv.filter.get(kernel_offset);
+                    let b = conv.bias.get(kernel_offset);
+
+                    let offset2 = Offset4(out_x + kx, out_y + ky, feat_in, 0);
+                    let input_offset = input.shape().index(&offset2);
+                    let k = input.get(input_offset);
+                    res += a * k + b
+                }
+            }
+        }
+        res
+    }
+
+    fn predict_direct(conv: &Conv2, input: &Tensor, output: &mut Tensor) {
+        for feat_o in 0..output.shape().z().unwrap() {
+            for x in 0..output.shape().x().unwrap() {
+                for y in 0..output.shape().y().unwrap() {
+                    let res = compute_at(conv, x, y, feat_o, input);
+                    let offset = output.shape().index(&Offset4(x, y, feat_o, 0));
+                    output.insert(offset, res);
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_conv2() {
         let input = Tensor::normal(Shape4::vec3(128, 128, 1), 0.0, 1.0);
@@ -130,4 +162,46 @@ mod tests {
         }
         // println!("{:?}", output)
     }
+
+    #[test]
+    #[ignore]
+    fn bench_against_direct_loop() {
+        let input = Tensor::normal(Shape4::vec3(128, 128, 8), 0.0, 1.0);
+        let conv = Conv2::new(3, 8, 16);
+        let mut output = conv.output_tensor(input.shape());
+        let mut bench = Bench::new("Conv2 im2col 128x128x8 -> 16");
+        while bench.for_duration(Duration::from_secs(1)) {
+            conv.predict(&input, &mut output);
+        }
+        let mut bench = Bench::new("Conv2 direct loop 128x128x8 -> 16");
+        while bench.for_duration(Duration::from_secs(1)) {
+            predict_direct(&conv, &input, &mut output);
+        }
+    }
+
+    #[test]
+    fn save_and_load() {
+        let conv = Conv2::new(3, 2, 4);
+        let mut bytes = vec![];
+        conv.write_to(&mut bytes).unwrap();
+        let loaded = Conv2::read_from(&mut bytes.as_slice()).unwrap();
+        assert_eq!(loaded.shape(), conv.shape());
+        assert_eq!(loaded.filter, conv.filter);
+        assert_eq!(loaded.bias, conv.bias);
+    }
+
+    #[test]
+    fn predict_matches_direct_loop() {
+        let input = Tensor::normal(Shape4::vec3(6, 5, 2), 0.0, 1.0);
+        let conv = Conv2::new(3, 2, 2);
+        let mut output = conv.output_tensor(input.shape());
+        conv.predict(&input, &mut output);
+        let mut expected = conv.output_tensor(input.shape());
+        predict_direct(&conv, &input, &mut expected);
+
+        for (x, y, o) in iproduct!(0..4, 0..3, 0..2) {
+            let offset = Offset4(x, y, o, 0);
+            assert!((output.get_at(offset.clone()) - expected.get_at(offset)).abs() < 1e-4);
+        }
+    }
 }
@@ -0,0 +1,213 @@
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use tensor_lib::autograd::variable::Variable;
+use tensor_lib::tensor::Tensor;
+
+use crate::framework::layer::Layer;
+use crate::framework::loss::Loss;
+use crate::framework::model::Model;
+use crate::framework::optimizer::Optimizer;
+use crate::framework::optimizers::schedule::Schedule;
+use crate::framework::trainer::{EpochMetrics, Trainer, TrainReport};
+
+// mini-batch gradient descent, samples are stacked along a last batch dimension
+pub struct GradientTrainer<O, L> {
+    pub optimizer: O,
+    pub loss: L,
+    pub schedule: Schedule,
+    pub epochs: usize,
+    pub batch_size: usize,
+    // fraction of the samples kept aside to measure the validation loss
+    pub validation_split: f32,
+    // epochs without validation improvement before stopping; the state of the best epoch
+    // (weights and batch norm statistics) is restored at the end of the training
+    pub patience: Option<usize>,
+    pub seed: u64,
+}
+
+impl<O: Optimizer + Clone, L: Loss> GradientTrainer<O, L> {
+    pub fn new(optimizer: O, loss: L) -> Self {
+        GradientTrainer {
+            optimizer,
+            loss,
+            schedule: Schedule::Constant,
+            epochs: 10,
+            batch_size: 32,
+            validation_split: 0.,
+            patience: None,
+            seed: 0,
+        }
+    }
+
+    // shuffled (train, validation) indices
+    pub fn split(&self, len: usize) -> (Vec<usize>, Vec<usize>) {
+        let mut indices: Vec<usize> = (0..len).collect();
+        indices.shuffle(&mut StdRng::seed_from_u64(self.seed));
+        let validation = (len as f32 * self.validation_split).round() as usize;
+        let train = indices.split_off(validation.min(len));
+        (train, indices)
+    }
+
+    fn batch(samples: &[Tensor], indices: &[usize]) -> Variable {
+        let batch: Vec<Tensor> = indices.iter().map(|&i| samples[i].clone()).collect();
+        Variable::constant(Tensor::stack(&batch))
+    }
+
+    pub fn evaluate<M: Layer>(&self, model: &mut M, x: &[Tensor], y: &[Tensor], indices: &[usize]) -> f32 {
+        model.set_training(false);
+        let mut total = 0.;
+        for chunk in indices.chunks(self.batch_size.max(1)) {
+            let prediction = model.forward(&GradientTrainer::<O, L>::batch(x, chunk)).detach();
+            let loss = self.loss.loss(&prediction, &GradientTrainer::<O, L>::batch(y, chunk));
+            total += loss.value().get(0) * chunk.len() as f32;
+        }
+        total / indices.len().max(1) as f32
+    }
+}
+
+impl<O, L, M> Trainer<Tensor, Tensor, M> for GradientTrainer<O, L>
+    where
+        O: Optimizer + Clone,
+        L: Loss,
+        M: Layer + Model<Tensor, Tensor>,
+{
+    fn fit(&self, model: &mut M, x: &Vec<Tensor>, y: &Vec<Tensor>) -> TrainReport {
+        assert_eq!(x.len(), y.len());
+        let mut optimizer = self.optimizer.clone();
+        let base_rate = optimizer.learning_rate();
+        let parameters = model.parameters();
+        let (mut train, validation) = self.split(x.len());
+        let mut rng = StdRng::seed_from_u64(self.seed + 1);
+        let mut report = TrainReport::default();
+        let mut best: Option<(f32, usize, Vec<Tensor>)> = None;
+
+        for epoch in 0..self.epochs {
+            optimizer.set_learning_rate(self.schedule.rate(base_rate, epoch));
+            train.shuffle(&mut rng);
+            model.set_training(true);
+            let mut train_loss = 0.;
+            for chunk in train.chunks(self.batch_size.max(1)) {
+                optimizer.zero_grad(&parameters);
+                let prediction = model.forward(&GradientTrainer::<O, L>::batch(x, chunk));
+                let loss = self.loss.loss(&prediction, &GradientTrainer::<O, L>::batch(y, chunk));
+                loss.backward();
+                optimizer.step(&parameters);
+                train_loss += loss.value().get(0) * chunk.len() as f32;
+            }
+            let metrics = EpochMetrics {
+                epoch,
+                learning_rate: optimizer.learning_rate(),
+                train_loss: train_loss / train.len().max(1) as f32,
+                validation_loss: match validation.is_empty() {
+                    true => None,
+                    false => Some(self.evaluate(model, x, y, &validation))
+                },
+            };
+            log::info!("{}", metrics);
+            let validation_loss = metrics.validation_loss;
+            report.epochs.push(metrics);
+
+            if let (Some(patience), Some(loss)) = (self.patience, validation_loss) {
+                match &best {
+                    Some((best_loss, _, _)) if *best_loss <= loss => {}
+                    _ => best = Some((loss, epoch, model.state().iter().map(|t| t.deep_clone()).collect()))
+                }
+                let (_, best_epoch, _) = best.as_ref().unwrap();
+                if epoch - best_epoch >= patience {
+                    report.stopped_early = true;
+                    break;
+                }
+            }
+        }
+        if let Some((_, _, state)) = best {
+            model.load_state(&mut state.into_iter()).unwrap();
+        }
+        model.set_training(false);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::tensor::Tensor;
+
+    use crate::framework::metrics::mse::MSE;
+    use crate::framework::model::Model;
+    use crate::framework::optimizers::adam::Adam;
+    use crate::framework::optimizers::schedule::Schedule;
+    use crate::framework::optimizers::sgd::Sgd;
+    use crate::framework::trainer::Trainer;
+    use crate::framework::gradient_trainer::GradientTrainer;
+    use crate::layers::sequential::Sequential;
+
+    // y = 2 x0 - x1 + 0.5
+    fn linear_dataset(len: usize) -> (Vec<Tensor>, Vec<Tensor>) {
+        let x: Vec<Tensor> = (0..len)
+            .map(|i| Tensor::from_vec(vec![(i % 7) as f32 / 7. - 0.5, (i % 5) as f32 / 5. - 0.5], &[2]))
+            .collect();
+        let y = x.iter().map(|x| Tensor::from_vec(vec![2. * x.get(0) - x.get(1) + 0.5], &[1])).collect();
+        (x, y)
+    }
+
+    #[test]
+    fn fit_linear() {
+        let (x, y) = linear_dataset(100);
+        let mut model = Sequential::new(&[2]).dense(1);
+        let trainer = GradientTrainer {
+            epochs: 60,
+            batch_size: 10,
+            validation_split: 0.2,
+            schedule: Schedule::Step { every: 20, gamma: 0.5 },
+            ..GradientTrainer::new(Sgd::with_momentum(0.1, 0.9), MSE {})
+        };
+        assert_eq!(trainer.split(100).1.len(), 20);
+        let report = trainer.fit(&mut model, &x, &y);
+        assert_eq!(report.epochs.len(), 60);
+        assert_eq!(report.last().unwrap().learning_rate, 0.025);
+        assert!(report.best_validation_loss().unwrap() < 1e-4);
+
+        let mut prediction = Tensor::zeros(&[1]);
+        model.predict(&Tensor::from_vec(vec![0.25, -0.25], &[2]), &mut prediction);
+        assert!((prediction.get(0) - 1.25).abs() < 0.01);
+    }
+
+    #[test]
+    fn early_stopping() {
+        // targets are noise: the validation loss stops improving quickly
+        let (x, _) = linear_dataset(60);
+        let y = (0..60).map(|i| Tensor::full(&[1], ((i * 37) % 11) as f32 - 5.)).collect();
+        let mut model = Sequential::new(&[2]).dense(16).relu().dense(1);
+        let trainer = GradientTrainer {
+            epochs: 500,
+            batch_size: 8,
+            validation_split: 0.3,
+            patience: Some(5),
+            ..GradientTrainer::new(Adam::new(0.05), MSE {})
+        };
+        let report = trainer.fit(&mut model, &x, &y);
+        assert!(report.stopped_early);
+        assert!(report.epochs.len() < 500);
+    }
+
+    #[test]
+    fn restore_best_state() {
+        // the best epoch is restored even when the patience is never exhausted
+        let (x, _) = linear_dataset(60);
+        let y = (0..60).map(|i| Tensor::full(&[1], ((i * 37) % 11) as f32 - 5.)).collect::<Vec<_>>();
+        let mut model = Sequential::new(&[2]).dense(16).batch_norm().relu().dense(1);
+        let trainer = GradientTrainer {
+            epochs: 30,
+            batch_size: 8,
+            validation_split: 0.3,
+            patience: Some(1000),
+            ..GradientTrainer::new(Adam::new(0.05), MSE {})
+        };
+        let report = trainer.fit(&mut model, &x, &y);
+        assert!(!report.stopped_early);
+        let (_, validation) = trainer.split(x.len());
+        let loss = trainer.evaluate(&mut model, &x, &y, &validation);
+        assert!((loss - report.best_validation_loss().unwrap()).abs() < 1e-4);
+    }
+}
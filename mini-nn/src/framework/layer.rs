@@ -0,0 +1,47 @@
+use tensor_lib::autograd::variable::Variable;
+use tensor_lib::tensor::Tensor;
+
+use crate::layers::spec::LayerSpec;
+
+// differentiable block working on batches, the batch being the last dimension
+pub trait Layer {
+    // dimensions of one sample
+    fn output_dims(&self, input: &[usize]) -> Vec<usize>;
+
+    fn forward(&self, x: &Variable) -> Variable;
+
+    fn parameters(&self) -> Vec<Variable> {
+        vec![]
+    }
+
+    // one name per parameter, unique in the layer
+    fn parameter_names(&self) -> Vec<String> {
+        (0..self.parameters().len()).map(|i| i.to_string()).collect()
+    }
+
+    // batch norm uses batch statistics while training, running ones otherwise
+    fn set_training(&mut self, _training: bool) {}
+
+    // architecture, enough to rebuild the layer without its weights
+    fn spec(&self) -> LayerSpec;
+
+    // parameters then buffers, in the order expected by load_state
+    fn state(&self) -> Vec<Tensor> {
+        self.parameters().iter().map(|p| p.value()).collect()
+    }
+
+    fn load_state(&self, state: &mut dyn Iterator<Item=Tensor>) -> Result<(), String> {
+        for p in self.parameters() {
+            p.set_value(next_state(state, &p.dims())?);
+        }
+        Ok(())
+    }
+}
+
+pub fn next_state(state: &mut dyn Iterator<Item=Tensor>, dims: &[usize]) -> Result<Tensor, String> {
+    match state.next() {
+        None => Err(String::from("missing tensor")),
+        Some(t) if t.dims() != dims => Err(format!("expected a {:?} tensor, got {:?}", dims, t.dims())),
+        Some(t) => Ok(t)
+    }
+}
@@ -0,0 +1,20 @@
+use tensor_lib::autograd::variable::Variable;
+use tensor_lib::tensor::Tensor;
+
+// differentiable training objective, averaged over the batch
+pub trait Loss {
+    fn loss(&self, prediction: &Variable, target: &Variable) -> Variable;
+
+    fn value(&self, prediction: &Tensor, target: &Tensor) -> f32 {
+        self.loss(&Variable::constant(prediction.clone()), &Variable::constant(target.clone()))
+            .value()
+            .get(0)
+    }
+
+    // d loss / d prediction
+    fn gradient(&self, prediction: &Tensor, target: &Tensor) -> Tensor {
+        let prediction = Variable::new(prediction.clone());
+        self.loss(&prediction, &Variable::constant(target.clone())).backward();
+        prediction.grad().unwrap()
+    }
+}
@@ -5,13 +5,10 @@ use itertools::zip;
 pub trait Metric<T> {
     fn score(lhs: &T, rhs: &T) -> f32;
 
+    // mean score of lhs against every rhs, 0 when there is none
     fn score_map(lhs: &T, rhs: &[T]) -> f32 {
-        let mut res = 0_f32;
-        for i in 0..rhs.len() {
-            let delta = Self::score(lhs, &rhs[i]);
-            res += delta * delta;
-        }
-        res.sqrt()
+        let sum: f32 = rhs.iter().map(|r| Self::score(lhs, r)).sum();
+        sum / rhs.len().max(1) as f32
     }
 
     fn score_zip(lhs: &[T], rhs: &[T]) -> Vec<f32> {
@@ -0,0 +1,57 @@
+use tensor_lib::tensor::Tensor;
+
+use crate::framework::metric::Metric;
+
+// fraction of samples whose target class (argmax of the first dimension) is among the k best predictions
+pub struct TopKAccuracy<const K: usize> {}
+
+pub type Accuracy = TopKAccuracy<1>;
+
+fn argmax(row: &[f32]) -> usize {
+    (0..row.len()).fold(0, |best, i| if row[i] > row[best] { i } else { best })
+}
+
+impl<const K: usize> Metric<Tensor> for TopKAccuracy<K> {
+    fn score(lhs: &Tensor, rhs: &Tensor) -> f32 {
+        assert_eq!(lhs.dims(), rhs.dims());
+        let classes = lhs.dims()[0];
+        let (prediction, target) = (lhs.to_vec(), rhs.to_vec());
+        let rows = prediction.chunks(classes).zip(target.chunks(classes));
+        let hits = rows.clone()
+            .filter(|(p, t)| {
+                let expected = p[argmax(t)];
+                // ties count against the target
+                p.iter().filter(|&&x| x >= expected).count() <= K
+            })
+            .count();
+        hits as f32 / rows.count().max(1) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::tensor::Tensor;
+
+    use crate::framework::metric::Metric;
+    use crate::framework::metrics::accuracy::{Accuracy, TopKAccuracy};
+
+    #[test]
+    fn top_k() {
+        // predictions of 4 samples over 3 classes
+        let p = Tensor::from_vec(vec![
+            0.7, 0.2, 0.1,
+            0.1, 0.3, 0.6,
+            0.5, 0.4, 0.1,
+            0.2, 0.3, 0.5,
+        ], &[3, 4]);
+        let t = Tensor::from_vec(vec![
+            1., 0., 0.,
+            0., 0., 1.,
+            0., 1., 0.,
+            1., 0., 0.,
+        ], &[3, 4]);
+        assert_eq!(Accuracy::score(&p, &t), 0.5);
+        assert_eq!(TopKAccuracy::<2>::score(&p, &t), 0.75);
+        assert_eq!(TopKAccuracy::<3>::score(&p, &t), 1.);
+    }
+}
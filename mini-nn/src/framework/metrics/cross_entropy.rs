@@ -0,0 +1,76 @@
+use tensor_lib::autograd::variable::Variable;
+use tensor_lib::tensor::Tensor;
+
+use crate::framework::loss::Loss;
+use crate::framework::metric::Metric;
+
+// keeps log away from 0 probabilities
+const EPSILON: f32 = 1e-7;
+
+// predictions are probabilities over the first dimension (a softmax output), targets may be soft;
+// summed over the classes and averaged over the batch
+pub struct CrossEntropy {}
+
+impl Metric<Tensor> for CrossEntropy {
+    fn score(lhs: &Tensor, rhs: &Tensor) -> f32 {
+        CrossEntropy {}.value(lhs, rhs)
+    }
+}
+
+impl Loss for CrossEntropy {
+    fn loss(&self, prediction: &Variable, target: &Variable) -> Variable {
+        let dims = prediction.dims();
+        let batch = dims.iter().skip(1).product::<usize>() as f32;
+        let epsilon = Variable::constant(Tensor::full(&[1], EPSILON));
+        (target * &(prediction + &epsilon).log()).sum().scale(-1. / batch)
+    }
+}
+
+// predictions and targets in [0, 1], averaged over all the elements
+pub struct BinaryCrossEntropy {}
+
+impl Metric<Tensor> for BinaryCrossEntropy {
+    fn score(lhs: &Tensor, rhs: &Tensor) -> f32 {
+        BinaryCrossEntropy {}.value(lhs, rhs)
+    }
+}
+
+impl Loss for BinaryCrossEntropy {
+    fn loss(&self, prediction: &Variable, target: &Variable) -> Variable {
+        let one = Variable::constant(Tensor::full(&[1], 1.));
+        let epsilon = Variable::constant(Tensor::full(&[1], EPSILON));
+        let positive = target * &(prediction + &epsilon).log();
+        let negative = &(&one - target) * &(&(&one - prediction) + &epsilon).log();
+        (&positive + &negative).mean().scale(-1.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::tensor::Tensor;
+
+    use crate::framework::loss::Loss;
+    use crate::framework::metric::Metric;
+    use crate::framework::metrics::cross_entropy::{BinaryCrossEntropy, CrossEntropy};
+
+    #[test]
+    fn soft_targets() {
+        // two samples of three classes
+        let p = Tensor::from_vec(vec![0.5, 0.25, 0.25, 0.1, 0.1, 0.8], &[3, 2]);
+        let t = Tensor::from_vec(vec![1., 0., 0., 0., 0.5, 0.5], &[3, 2]);
+        let expected = -(0.5_f32.ln() + 0.5 * 0.1_f32.ln() + 0.5 * 0.8_f32.ln()) / 2.;
+        assert!((CrossEntropy::score(&p, &t) - expected).abs() < 1e-5);
+        let grad = CrossEntropy {}.gradient(&p, &t);
+        assert!((grad.get(0) + 1.).abs() < 1e-4 && grad.get(1) == 0. && (grad.get(5) + 0.3125).abs() < 1e-4);
+    }
+
+    #[test]
+    fn binary() {
+        let p = Tensor::from_vec(vec![0.9, 0.2], &[2]);
+        let t = Tensor::from_vec(vec![1., 0.], &[2]);
+        let expected = -(0.9_f32.ln() + 0.8_f32.ln()) / 2.;
+        assert!((BinaryCrossEntropy::score(&p, &t) - expected).abs() < 1e-5);
+        let grad = BinaryCrossEntropy {}.gradient(&p, &t);
+        assert!((grad.get(0) + 1. / 1.8).abs() < 1e-4 && (grad.get(1) - 1. / 1.6).abs() < 1e-4);
+    }
+}
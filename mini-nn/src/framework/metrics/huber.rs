@@ -0,0 +1,58 @@
+use tensor_lib::autograd::variable::Variable;
+use tensor_lib::tensor::Tensor;
+
+use crate::framework::loss::Loss;
+use crate::framework::metric::Metric;
+
+// quadratic below delta, linear above
+pub struct Huber {
+    pub delta: f32,
+}
+
+impl Default for Huber {
+    fn default() -> Self {
+        Huber { delta: 1. }
+    }
+}
+
+impl Metric<Tensor> for Huber {
+    fn score(lhs: &Tensor, rhs: &Tensor) -> f32 {
+        Huber::default().value(lhs, rhs)
+    }
+}
+
+impl Loss for Huber {
+    fn loss(&self, prediction: &Variable, target: &Variable) -> Variable {
+        let delta = (prediction - target).value();
+        let (n, k) = (delta.len() as f32, self.delta);
+        let value = delta.map(|d| match d.abs() <= k {
+            true => 0.5 * d * d,
+            false => k * (d.abs() - 0.5 * k)
+        }).mean();
+        Variable::from_op(Tensor::full(&[1], value), vec![prediction.clone(), target.clone()], Box::new(move |g| {
+            let scale = g.get(0) / n;
+            let grad = delta.map(|d| scale * d.clamp(-k, k));
+            vec![grad.clone(), grad.map(|x| -x)]
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::tensor::Tensor;
+
+    use crate::framework::loss::Loss;
+    use crate::framework::metric::Metric;
+    use crate::framework::metrics::huber::Huber;
+
+    #[test]
+    fn quadratic_then_linear() {
+        let p = Tensor::from_vec(vec![0.5, 3., -2.], &[3]);
+        let t = Tensor::zeros(&[3]);
+        // 0.125, 2.5 and 1.5
+        assert!((Huber::score(&p, &t) - 4.125 / 3.).abs() < 1e-6);
+        let grad = Huber::default().gradient(&p, &t).to_vec();
+        let expected = [0.5 / 3., 1. / 3., -1. / 3.];
+        assert!(grad.iter().zip(expected.iter()).all(|(g, e)| (g - e).abs() < 1e-6));
+    }
+}
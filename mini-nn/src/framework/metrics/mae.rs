@@ -0,0 +1,53 @@
+use tensor_lib::autograd::variable::Variable;
+use tensor_lib::tensor::Tensor;
+
+use crate::framework::loss::Loss;
+use crate::framework::metric::Metric;
+
+// mean absolute error, the gradient at 0 is 0
+pub struct MAE {}
+
+impl Metric<Tensor> for MAE {
+    fn score(lhs: &Tensor, rhs: &Tensor) -> f32 {
+        lhs.zip_with(rhs, |p, t| (p - t).abs()).mean()
+    }
+}
+
+impl Loss for MAE {
+    fn loss(&self, prediction: &Variable, target: &Variable) -> Variable {
+        let delta = (prediction - target).value();
+        let n = delta.len() as f32;
+        let value = Tensor::full(&[1], delta.map(f32::abs).sum() / n);
+        Variable::from_op(value, vec![prediction.clone(), target.clone()], Box::new(move |g| {
+            let k = g.get(0) / n;
+            let grad = delta.map(|d| k * sign(d));
+            vec![grad.clone(), grad.map(|x| -x)]
+        }))
+    }
+}
+
+fn sign(x: f32) -> f32 {
+    match x {
+        x if x > 0. => 1.,
+        x if x < 0. => -1.,
+        _ => 0.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::tensor::Tensor;
+
+    use crate::framework::loss::Loss;
+    use crate::framework::metric::Metric;
+    use crate::framework::metrics::mae::MAE;
+
+    #[test]
+    fn absolute_error() {
+        let p = Tensor::from_vec(vec![1., 2., 3., 4.], &[4]);
+        let t = Tensor::from_vec(vec![0., 2., 5., 4.], &[4]);
+        assert_eq!(MAE::score(&p, &t), 0.75);
+        assert_eq!(MAE {}.value(&p, &t), 0.75);
+        assert_eq!(MAE {}.gradient(&p, &t).to_vec(), vec![0.25, 0., -0.25, 0.]);
+    }
+}
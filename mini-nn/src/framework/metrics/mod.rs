@@ -1 +1,5 @@
 pub mod mse;
+pub mod mae;
+pub mod huber;
+pub mod cross_entropy;
+pub mod accuracy;
\ No newline at end of file
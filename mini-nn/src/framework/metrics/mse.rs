@@ -1,28 +1,28 @@
+use tensor_lib::autograd::variable::Variable;
 use tensor_lib::tensor::Tensor;
-use tensor_lib::traits::view::View;
 
+use crate::framework::loss::Loss;
 use crate::framework::metric::Metric;
 
 pub struct MSE {}
 
 impl MSE {
-    pub fn score_vec(vec: &Vec<f32>) -> f32 {
-        let mut score = 0_f32;
-        for &x in vec.iter() {
-            score += x * x;
-        }
-        score.sqrt()
+    // mean of per sample scores
+    pub fn score_vec(vec: &[f32]) -> f32 {
+        vec.iter().sum::<f32>() / vec.len().max(1) as f32
     }
 }
 
 impl Metric<Tensor> for MSE {
     fn score(lhs: &Tensor, rhs: &Tensor) -> f32 {
-        let mut score = 0_f32;
-        for i in 0..lhs.shape().len() {
-            let x = lhs.get(i) - rhs.get(i);
-            score += x * x;
-        }
-        score.sqrt()
+        MSE {}.value(lhs, rhs)
+    }
+}
+
+impl Loss for MSE {
+    fn loss(&self, prediction: &Variable, target: &Variable) -> Variable {
+        let delta = prediction - target;
+        (&delta * &delta).mean()
     }
 }
 
@@ -32,6 +32,7 @@ mod tests {
     use tensor_lib::structs::shape4::Shape4;
     use tensor_lib::tensor::Tensor;
 
+    use crate::framework::loss::Loss;
     use crate::framework::metric::Metric;
     use crate::framework::metrics::mse::MSE;
 
@@ -50,4 +51,16 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn mean_of_squares() {
+        let p = Tensor::from_vec(vec![1., 2., 3., 4.], &[4]);
+        let t = Tensor::from_vec(vec![0., 2., 5., 4.], &[4]);
+        assert_eq!(MSE::score(&p, &t), 1.25);
+        assert_eq!(MSE {}.gradient(&p, &t).to_vec(), vec![0.5, 0., -1., 0.]);
+        assert_eq!(MSE::score_vec(&[1., 2., 6.]), 3.);
+        // 1.25 against t, 0 against p itself
+        assert_eq!(MSE::score_map(&p, &[t, p.clone()]), 0.625);
+        assert_eq!(MSE::score_map(&p, &[]), 0.);
+    }
 }
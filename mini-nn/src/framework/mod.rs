@@ -2,3 +2,9 @@ pub mod metrics;
 pub mod model;
 pub mod metric;
 pub mod trainer;
+pub mod layer;
+pub mod loss;
+pub mod optimizer;
+pub mod optimizers;
+pub mod gradient_trainer;
+pub mod parameters;
\ No newline at end of file
@@ -0,0 +1,16 @@
+use tensor_lib::autograd::variable::Variable;
+
+// updates parameters from their accumulated gradients, parameters keep the same order between steps
+pub trait Optimizer {
+    fn step(&mut self, parameters: &[Variable]);
+
+    fn learning_rate(&self) -> f32;
+
+    fn set_learning_rate(&mut self, learning_rate: f32);
+
+    fn zero_grad(&self, parameters: &[Variable]) {
+        for p in parameters {
+            p.zero_grad();
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use tensor_lib::autograd::variable::Variable;
+use tensor_lib::tensor::Tensor;
+
+use crate::framework::optimizer::Optimizer;
+
+// weight decay is decoupled from the moments (AdamW)
+#[derive(Clone)]
+pub struct Adam {
+    pub learning_rate: f32,
+    pub beta1: f32,
+    pub beta2: f32,
+    pub epsilon: f32,
+    pub weight_decay: f32,
+    steps: i32,
+    moments: Vec<Option<(Tensor, Tensor)>>,
+}
+
+impl Adam {
+    pub fn new(learning_rate: f32) -> Self {
+        Adam {
+            learning_rate,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+            weight_decay: 0.,
+            steps: 0,
+            moments: vec![],
+        }
+    }
+}
+
+impl Optimizer for Adam {
+    fn step(&mut self, parameters: &[Variable]) {
+        self.moments.resize(parameters.len(), None);
+        self.steps += 1;
+        let (b1, b2, eps) = (self.beta1, self.beta2, self.epsilon);
+        let correction1 = 1. - b1.powi(self.steps);
+        let correction2 = 1. - b2.powi(self.steps);
+        for (p, moments) in parameters.iter().zip(self.moments.iter_mut()) {
+            let grad = match p.grad() {
+                None => continue,
+                Some(grad) => grad
+            };
+            let (m, v) = match moments.take() {
+                None => (Tensor::zeros(grad.dims()), Tensor::zeros(grad.dims())),
+                Some(moments) => moments
+            };
+            let m = m.zip_with(&grad, |m, g| b1 * m + (1. - b1) * g);
+            let v = v.zip_with(&grad, |v, g| b2 * v + (1. - b2) * g * g);
+            let step = m.zip_with(&v, |m, v| (m / correction1) / ((v / correction2).sqrt() + eps));
+            let (lr, decay) = (self.learning_rate, self.weight_decay);
+            p.set_value(p.value().zip_with(&step, |w, s| w - lr * (s + decay * w)));
+            *moments = Some((m, v));
+        }
+    }
+
+    fn learning_rate(&self) -> f32 {
+        self.learning_rate
+    }
+
+    fn set_learning_rate(&mut self, learning_rate: f32) {
+        self.learning_rate = learning_rate;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::autograd::variable::Variable;
+    use tensor_lib::tensor::Tensor;
+
+    use crate::framework::optimizer::Optimizer;
+    use crate::framework::optimizers::adam::Adam;
+
+    #[test]
+    fn minimise_quadratic() {
+        let w = Variable::new(Tensor::from_vec(vec![3., -1.], &[2]));
+        let mut adam = Adam::new(0.1);
+        // the first step moves each weight by the learning rate
+        (&w * &w).sum().backward();
+        adam.step(&[w.clone()]);
+        assert!((w.value().get(0) - 2.9).abs() < 1e-5 && (w.value().get(1) + 0.9).abs() < 1e-5);
+
+        for _ in 0..300 {
+            adam.zero_grad(&[w.clone()]);
+            (&w * &w).sum().backward();
+            adam.step(&[w.clone()]);
+        }
+        assert!(w.value().iter().all(|x| x.abs() < 0.05));
+    }
+}
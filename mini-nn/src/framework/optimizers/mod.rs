@@ -0,0 +1,3 @@
+pub mod sgd;
+pub mod adam;
+pub mod schedule;
\ No newline at end of file
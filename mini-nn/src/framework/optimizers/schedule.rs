@@ -0,0 +1,41 @@
+use std::f32::consts::PI;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    Constant,
+    // multiplied by gamma every n epochs
+    Step { every: usize, gamma: f32 },
+    Exponential { gamma: f32 },
+    // from the base rate down to min over epochs
+    Cosine { epochs: usize, min: f32 },
+}
+
+impl Schedule {
+    pub fn rate(&self, base: f32, epoch: usize) -> f32 {
+        match *self {
+            Schedule::Constant => base,
+            Schedule::Step { every, gamma } => base * gamma.powi((epoch / every.max(1)) as i32),
+            Schedule::Exponential { gamma } => base * gamma.powi(epoch as i32),
+            Schedule::Cosine { epochs, min } => {
+                let progress = (epoch as f32 / epochs.max(1) as f32).min(1.);
+                min + (base - min) * (1. + (PI * progress).cos()) / 2.
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::framework::optimizers::schedule::Schedule;
+
+    #[test]
+    fn rates() {
+        assert_eq!(Schedule::Constant.rate(0.1, 7), 0.1);
+        assert_eq!(Schedule::Step { every: 2, gamma: 0.5 }.rate(1., 5), 0.25);
+        assert_eq!(Schedule::Exponential { gamma: 0.5 }.rate(1., 3), 0.125);
+        let cosine = Schedule::Cosine { epochs: 10, min: 0.1 };
+        assert_eq!(cosine.rate(1., 0), 1.);
+        assert!((cosine.rate(1., 5) - 0.55).abs() < 1e-6);
+        assert!((cosine.rate(1., 20) - 0.1).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,88 @@
+use tensor_lib::autograd::variable::Variable;
+use tensor_lib::tensor::Tensor;
+
+use crate::framework::optimizer::Optimizer;
+
+// weight decay is added to the gradient (L2 penalty)
+#[derive(Clone)]
+pub struct Sgd {
+    pub learning_rate: f32,
+    pub momentum: f32,
+    pub weight_decay: f32,
+    velocities: Vec<Option<Tensor>>,
+}
+
+impl Sgd {
+    pub fn new(learning_rate: f32) -> Self {
+        Sgd {
+            learning_rate,
+            momentum: 0.,
+            weight_decay: 0.,
+            velocities: vec![],
+        }
+    }
+
+    pub fn with_momentum(learning_rate: f32, momentum: f32) -> Self {
+        Sgd { momentum, ..Sgd::new(learning_rate) }
+    }
+}
+
+impl Optimizer for Sgd {
+    fn step(&mut self, parameters: &[Variable]) {
+        self.velocities.resize(parameters.len(), None);
+        for (p, velocity) in parameters.iter().zip(self.velocities.iter_mut()) {
+            let grad = match p.grad() {
+                None => continue,
+                Some(grad) => grad
+            };
+            let value = p.value();
+            let decay = self.weight_decay;
+            let grad = grad.zip_with(&value, |g, w| g + decay * w);
+            let update = match velocity.take() {
+                None => grad,
+                Some(v) => {
+                    let momentum = self.momentum;
+                    v.zip_with(&grad, |v, g| momentum * v + g)
+                }
+            };
+            let lr = self.learning_rate;
+            p.set_value(value.zip_with(&update, |w, u| w - lr * u));
+            if self.momentum > 0. {
+                *velocity = Some(update);
+            }
+        }
+    }
+
+    fn learning_rate(&self) -> f32 {
+        self.learning_rate
+    }
+
+    fn set_learning_rate(&mut self, learning_rate: f32) {
+        self.learning_rate = learning_rate;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::autograd::variable::Variable;
+    use tensor_lib::tensor::Tensor;
+
+    use crate::framework::optimizer::Optimizer;
+    use crate::framework::optimizers::sgd::Sgd;
+
+    #[test]
+    fn momentum_steps() {
+        let w = Variable::new(Tensor::from_vec(vec![1., -2.], &[2]));
+        let mut sgd = Sgd { weight_decay: 0.5, ..Sgd::with_momentum(0.1, 0.9) };
+        for _ in 0..2 {
+            sgd.zero_grad(&[w.clone()]);
+            w.sum().backward();
+            sgd.step(&[w.clone()]);
+        }
+        // g1 = 1 + 0.5 w0 = [1.5, 0], w1 = [0.85, -2]
+        // g2 = [1.425, 0], v2 = 0.9 g1 + g2 = [2.775, 0]
+        let value = w.value().to_vec();
+        assert!((value[0] - (0.85 - 0.2775)).abs() < 1e-6);
+        assert_eq!(value[1], -2.);
+    }
+}
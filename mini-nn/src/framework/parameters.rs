@@ -0,0 +1,198 @@
+use tensor_lib::tensor::Tensor;
+
+use crate::conv2::Conv2;
+use crate::framework::layer::Layer;
+
+// trainable tensors of a model, by name: the tensors share the model buffers and copy
+// them on the first write, set_parameters stores the changes back
+pub trait Parameters {
+    // names are unique in the model, the order never changes
+    fn named_parameters(&self) -> Vec<(String, Tensor)>;
+
+    // values in the order of named_parameters, with the same dimensions
+    fn set_parameters(&mut self, values: Vec<Tensor>);
+
+    fn parameter_count(&self) -> usize {
+        self.named_parameters().iter().map(|(_, t)| t.len()).sum()
+    }
+
+    fn parameter(&self, name: &str) -> Option<Tensor> {
+        self.named_parameters().into_iter().find(|(n, _)| n == name).map(|(_, t)| t)
+    }
+
+    // all the values one after the other, in logical order
+    fn flat_values(&self) -> Vec<f32> {
+        self.named_parameters().iter().flat_map(|(_, t)| t.to_vec()).collect()
+    }
+
+    fn set_flat_values(&mut self, values: &[f32]) {
+        let mut rest = values;
+        let tensors = self.named_parameters().into_iter()
+            .map(|(_, t)| {
+                let (head, tail) = rest.split_at(t.len());
+                rest = tail;
+                Tensor::from_vec(head.to_vec(), t.dims())
+            })
+            .collect();
+        assert!(rest.is_empty(), "{} values for {} parameters", values.len(), values.len() - rest.len());
+        self.set_parameters(tensors);
+    }
+
+    // element of the flattened parameters
+    fn flat_get(&self, index: usize) -> f32 {
+        let (tensors, i, offset) = locate(self.named_parameters(), index);
+        tensors[i].get(offset)
+    }
+
+    fn flat_set(&mut self, index: usize, value: f32) {
+        self.flat_update(index, |_| value);
+    }
+
+    // changes one element, in place where the model gives access to its tensors
+    fn flat_update<F: FnOnce(f32) -> f32>(&mut self, index: usize, f: F) {
+        let (mut tensors, i, offset) = locate(self.named_parameters(), index);
+        let x = tensors[i].get(offset);
+        tensors[i].insert(offset, f(x));
+        self.set_parameters(tensors);
+    }
+}
+
+// (tensors, index of the tensor holding the element, offset in that tensor)
+fn locate(parameters: Vec<(String, Tensor)>, index: usize) -> (Vec<Tensor>, usize, usize) {
+    let tensors: Vec<Tensor> = parameters.into_iter().map(|(_, t)| t).collect();
+    let mut offset = index;
+    for (i, t) in tensors.iter().enumerate() {
+        if offset < t.len() {
+            return (tensors, i, offset);
+        }
+        offset -= t.len();
+    }
+    panic!("parameter {} is out of {} values", index, index - offset)
+}
+
+impl Parameters for Tensor {
+    fn named_parameters(&self) -> Vec<(String, Tensor)> {
+        vec![(String::from("tensor"), self.clone())]
+    }
+
+    fn set_parameters(&mut self, values: Vec<Tensor>) {
+        assert_eq!(values.len(), 1);
+        self.copy_from(&values[0]);
+    }
+
+    fn flat_update<F: FnOnce(f32) -> f32>(&mut self, index: usize, f: F) {
+        let x = self.get(index);
+        self.insert(index, f(x));
+    }
+}
+
+impl Parameters for Conv2 {
+    fn named_parameters(&self) -> Vec<(String, Tensor)> {
+        vec![
+            (String::from("filter"), self.filter.clone()),
+            (String::from("bias"), self.bias.clone()),
+        ]
+    }
+
+    fn set_parameters(&mut self, values: Vec<Tensor>) {
+        assert_eq!(values.len(), 2);
+        self.filter.copy_from(&values[0]);
+        self.bias.copy_from(&values[1]);
+    }
+
+    fn flat_update<F: FnOnce(f32) -> f32>(&mut self, index: usize, f: F) {
+        let (tensor, offset) = match index < self.filter.len() {
+            true => (&mut self.filter, index),
+            false => (&mut self.bias, index - self.filter.len())
+        };
+        let x = tensor.get(offset);
+        tensor.insert(offset, f(x));
+    }
+}
+
+impl<L: Layer> Parameters for L {
+    fn named_parameters(&self) -> Vec<(String, Tensor)> {
+        Layer::parameter_names(self).into_iter()
+            .zip(self.parameters().iter().map(|p| p.value()))
+            .collect()
+    }
+
+    fn set_parameters(&mut self, values: Vec<Tensor>) {
+        let parameters = Layer::parameters(self);
+        assert_eq!(values.len(), parameters.len());
+        for (p, value) in parameters.iter().zip(values) {
+            assert_eq!(p.dims(), value.dims(), "parameter dimensions");
+            p.set_value(value);
+        }
+    }
+
+    fn flat_update<F: FnOnce(f32) -> f32>(&mut self, index: usize, f: F) {
+        let mut offset = index;
+        for p in Layer::parameters(self) {
+            let len = p.dims().iter().product::<usize>();
+            if offset < len {
+                return p.update_value(|t| {
+                    let x = t.get(offset);
+                    t.insert(offset, f(x));
+                });
+            }
+            offset -= len;
+        }
+        panic!("parameter {} is out of {} values", index, index - offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::tensor::Tensor;
+
+    use crate::conv2::Conv2;
+    use crate::framework::parameters::Parameters;
+    use crate::layers::policy_value::PolicyValueNet;
+    use crate::layers::sequential::Sequential;
+
+    #[test]
+    fn named_views() {
+        let net = Sequential::new(&[3, 3, 2])
+            .conv(4, 3)
+            .batch_norm()
+            .residual_block(3)
+            .flatten()
+            .dense(2);
+        let names: Vec<String> = net.named_parameters().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(&names[..4], &["0.filter", "0.bias", "1.gamma", "1.beta"]);
+        assert_eq!(names[4], "2.body.0.filter");
+        assert_eq!(&names[names.len() - 2..], &["4.weight", "4.bias"]);
+        assert_eq!(net.parameter("4.bias").unwrap().dims(), &[2]);
+
+        let mut pv = PolicyValueNet::new(3, 3, 2, 4, 1);
+        assert!(pv.named_parameters()[0].0.starts_with("trunk.0."));
+        let last = pv.parameter_count() - 1;
+        pv.flat_set(last, 3.);
+        assert_eq!(pv.parameter("value.6.bias").unwrap().to_vec(), vec![3.]);
+    }
+
+    #[test]
+    fn flatten_round_trip() {
+        let mut net = Sequential::new(&[4]).dense(3).relu().dense(2);
+        assert_eq!(net.parameter_count(), 4 * 3 + 3 + 3 * 2 + 2);
+        let values: Vec<f32> = (0..net.parameter_count()).map(|i| i as f32).collect();
+        net.set_flat_values(&values);
+        assert_eq!(net.flat_values(), values);
+        net.flat_set(13, -1.);
+        assert_eq!(net.flat_get(13), -1.);
+        assert_eq!(net.parameter("0.bias").unwrap().to_vec(), vec![12., -1., 14.]);
+
+        // a copy does not share its weights
+        let copy = net.clone();
+        net.flat_set(0, 5.);
+        assert_eq!(copy.flat_get(0), 0.);
+
+        let mut conv = Conv2::new(3, 1, 1);
+        conv.flat_set(9, 2.);
+        assert_eq!(conv.bias.get(0), 2.);
+        let mut t = Tensor::zeros(&[2, 2]);
+        t.set_flat_values(&[1., 2., 3., 4.]);
+        assert_eq!(t.get_nd(&[1, 1]), 4.);
+    }
+}
@@ -1,5 +1,45 @@
+use std::fmt::{Display, Formatter};
+use std::fmt;
+
 use crate::framework::model::Model;
 
 pub trait Trainer<X, Y, M: Model<X, Y>> {
-    fn fit(&self, model: &mut M, x: &Vec<X>, y: &Vec<Y>);
+    fn fit(&self, model: &mut M, x: &Vec<X>, y: &Vec<Y>) -> TrainReport;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpochMetrics {
+    pub epoch: usize,
+    pub learning_rate: f32,
+    pub train_loss: f32,
+    pub validation_loss: Option<f32>,
+}
+
+impl Display for EpochMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "epoch {} lr {:.2e} train loss {:.5}", self.epoch, self.learning_rate, self.train_loss)?;
+        match self.validation_loss {
+            Some(loss) => write!(f, " validation loss {:.5}", loss),
+            None => Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrainReport {
+    pub epochs: Vec<EpochMetrics>,
+    pub stopped_early: bool,
+}
+
+impl TrainReport {
+    pub fn last(&self) -> Option<&EpochMetrics> {
+        self.epochs.last()
+    }
+
+    pub fn best_validation_loss(&self) -> Option<f32> {
+        self.epochs.iter().filter_map(|e| e.validation_loss).fold(None, |best, loss| match best {
+            Some(best) if best <= loss => Some(best),
+            _ => Some(loss)
+        })
+    }
 }
@@ -0,0 +1,53 @@
+use tensor_lib::autograd::variable::Variable;
+
+use crate::framework::layer::Layer;
+use crate::layers::spec::LayerSpec;
+use crate::relu::Relu;
+
+impl Layer for Relu {
+    fn output_dims(&self, input: &[usize]) -> Vec<usize> {
+        input.to_vec()
+    }
+
+    fn forward(&self, x: &Variable) -> Variable {
+        x.relu()
+    }
+
+    fn spec(&self) -> LayerSpec {
+        LayerSpec::Relu
+    }
+}
+
+pub struct Tanh;
+
+impl Layer for Tanh {
+    fn output_dims(&self, input: &[usize]) -> Vec<usize> {
+        input.to_vec()
+    }
+
+    fn forward(&self, x: &Variable) -> Variable {
+        x.tanh()
+    }
+
+    fn spec(&self) -> LayerSpec {
+        LayerSpec::Tanh
+    }
+}
+
+// probabilities over the first dimension of each sample
+pub struct Softmax;
+
+impl Layer for Softmax {
+    fn output_dims(&self, input: &[usize]) -> Vec<usize> {
+        assert_eq!(input.len(), 1, "softmax of {:?}", input);
+        input.to_vec()
+    }
+
+    fn forward(&self, x: &Variable) -> Variable {
+        x.softmax()
+    }
+
+    fn spec(&self) -> LayerSpec {
+        LayerSpec::Softmax
+    }
+}
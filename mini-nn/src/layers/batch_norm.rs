@@ -0,0 +1,134 @@
+use std::cell::RefCell;
+
+use tensor_lib::autograd::variable::Variable;
+use tensor_lib::tensor::Tensor;
+
+use crate::framework::layer::{Layer, next_state};
+use crate::layers::spec::LayerSpec;
+
+const EPSILON: f32 = 1e-5;
+
+// normalises each channel (the last dimension of a sample) over the batch and the other dimensions
+pub struct BatchNorm {
+    pub gamma: Variable,
+    pub beta: Variable,
+    pub momentum: f32,
+    running_mean: RefCell<Tensor>,
+    running_var: RefCell<Tensor>,
+    training: bool,
+}
+
+impl BatchNorm {
+    pub fn new(dims: &[usize]) -> Self {
+        let mut channel_dims = vec![1; dims.len()];
+        channel_dims[dims.len() - 1] = dims[dims.len() - 1];
+        BatchNorm {
+            gamma: Variable::new(Tensor::full(&channel_dims, 1.)),
+            beta: Variable::new(Tensor::zeros(&channel_dims)),
+            momentum: 0.9,
+            running_mean: RefCell::new(Tensor::zeros(&channel_dims)),
+            running_var: RefCell::new(Tensor::full(&channel_dims, 1.)),
+            training: true,
+        }
+    }
+
+    pub fn running_stats(&self) -> (Tensor, Tensor) {
+        (self.running_mean.borrow().clone(), self.running_var.borrow().clone())
+    }
+
+    fn update(running: &RefCell<Tensor>, batch: &Tensor, momentum: f32) {
+        let value = running.borrow().zip_with(batch, |r, b| momentum * r + (1. - momentum) * b);
+        *running.borrow_mut() = value;
+    }
+}
+
+impl Layer for BatchNorm {
+    fn output_dims(&self, input: &[usize]) -> Vec<usize> {
+        assert_eq!(input.len(), self.gamma.dims().len());
+        input.to_vec()
+    }
+
+    fn forward(&self, x: &Variable) -> Variable {
+        let channel_dims = self.gamma.dims();
+        let epsilon = Variable::constant(Tensor::full(&[1], EPSILON));
+        let (centered, var) = match self.training {
+            true => {
+                let count = (x.value().len() / channel_dims.iter().product::<usize>()) as f32;
+                let mean = x.sum_to(&channel_dims).scale(1. / count);
+                let centered = x - &mean;
+                let var = (&centered * &centered).sum_to(&channel_dims).scale(1. / count);
+                BatchNorm::update(&self.running_mean, &mean.value(), self.momentum);
+                BatchNorm::update(&self.running_var, &var.value(), self.momentum);
+                (centered, var)
+            }
+            false => {
+                let (mean, var) = self.running_stats();
+                (x - &Variable::constant(mean), Variable::constant(var))
+            }
+        };
+        let normalised = &centered * &(&var + &epsilon).powf(-0.5);
+        &(&normalised * &self.gamma) + &self.beta
+    }
+
+    fn parameters(&self) -> Vec<Variable> {
+        vec![self.gamma.clone(), self.beta.clone()]
+    }
+
+    fn parameter_names(&self) -> Vec<String> {
+        vec![String::from("gamma"), String::from("beta")]
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+
+    fn spec(&self) -> LayerSpec {
+        // only the rank and the channels matter
+        LayerSpec::BatchNorm { dims: self.gamma.dims(), momentum: self.momentum }
+    }
+
+    fn state(&self) -> Vec<Tensor> {
+        let (mean, var) = self.running_stats();
+        vec![self.gamma.value(), self.beta.value(), mean, var]
+    }
+
+    fn load_state(&self, state: &mut dyn Iterator<Item=Tensor>) -> Result<(), String> {
+        let dims = self.gamma.dims();
+        self.gamma.set_value(next_state(state, &dims)?);
+        self.beta.set_value(next_state(state, &dims)?);
+        *self.running_mean.borrow_mut() = next_state(state, &dims)?;
+        *self.running_var.borrow_mut() = next_state(state, &dims)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::autograd::variable::Variable;
+    use tensor_lib::tensor::Tensor;
+
+    use crate::framework::layer::Layer;
+    use crate::layers::batch_norm::BatchNorm;
+
+    #[test]
+    fn normalise_channels() {
+        let mut bn = BatchNorm::new(&[2, 1, 2]);
+        // channel 0 holds 1..4, channel 1 holds 10..40, over a batch of 2
+        let x = Tensor::from_vec(vec![1., 2., 10., 20., 3., 4., 30., 40.], &[2, 1, 2, 2]);
+        let y = bn.forward(&Variable::constant(x.clone())).value();
+        let channel = |t: &Tensor, c: usize| (0..8).filter(|i| (i / 2) % 2 == c).map(|i| t.get(i)).collect::<Vec<_>>();
+        for c in 0..2 {
+            let values = channel(&y, c);
+            let mean = values.iter().sum::<f32>() / 4.;
+            let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / 4.;
+            assert!(mean.abs() < 1e-5 && (var - 1.).abs() < 1e-3);
+        }
+        let (mean, var) = bn.running_stats();
+        assert!((mean.get(1) - 2.5).abs() < 1e-4 && (var.get(0) - 0.9 - 0.125).abs() < 1e-4);
+
+        // evaluation uses the running statistics
+        bn.set_training(false);
+        let y = bn.forward(&Variable::constant(x)).value();
+        assert!((y.get(0) - (1. - 0.25) / (1.025_f32 + 1e-5).sqrt()).abs() < 1e-4);
+    }
+}
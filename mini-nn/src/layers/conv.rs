@@ -0,0 +1,58 @@
+use tensor_lib::autograd::variable::Variable;
+use tensor_lib::tensor::Tensor;
+
+use crate::framework::layer::Layer;
+use crate::layers::he_normal;
+use crate::layers::spec::LayerSpec;
+
+// [width, height, channels, batch] -> [width, height, features, batch], same padding
+pub struct Conv {
+    pub filter: Variable,
+    pub bias: Variable,
+}
+
+impl Conv {
+    pub fn same(channels: usize, features: usize, kernel: usize) -> Self {
+        assert_eq!(kernel % 2, 1, "same padding needs an odd kernel");
+        Conv {
+            filter: Variable::new(he_normal(&[kernel, kernel, channels, features], kernel * kernel * channels)),
+            bias: Variable::new(Tensor::zeros(&[1, 1, features])),
+        }
+    }
+
+    pub fn kernel(&self) -> usize {
+        self.filter.dims()[0]
+    }
+
+    pub fn channels(&self) -> usize {
+        self.filter.dims()[2]
+    }
+
+    pub fn features(&self) -> usize {
+        self.filter.dims()[3]
+    }
+}
+
+impl Layer for Conv {
+    fn output_dims(&self, input: &[usize]) -> Vec<usize> {
+        assert!(input.len() == 3 && input[2] == self.channels(),
+                "conv of {} channels on {:?}", self.channels(), input);
+        vec![input[0], input[1], self.features()]
+    }
+
+    fn forward(&self, x: &Variable) -> Variable {
+        &x.conv2d(&self.filter, self.kernel() / 2, 1) + &self.bias
+    }
+
+    fn parameters(&self) -> Vec<Variable> {
+        vec![self.filter.clone(), self.bias.clone()]
+    }
+
+    fn parameter_names(&self) -> Vec<String> {
+        vec![String::from("filter"), String::from("bias")]
+    }
+
+    fn spec(&self) -> LayerSpec {
+        LayerSpec::Conv { channels: self.channels(), features: self.features(), kernel: self.kernel() }
+    }
+}
@@ -0,0 +1,71 @@
+use tensor_lib::autograd::variable::Variable;
+use tensor_lib::tensor::Tensor;
+
+use crate::framework::layer::Layer;
+use crate::layers::he_normal;
+use crate::layers::spec::LayerSpec;
+
+// [inputs, batch] -> [outputs, batch]
+pub struct Dense {
+    pub weight: Variable,
+    pub bias: Variable,
+}
+
+impl Dense {
+    pub fn new(inputs: usize, outputs: usize) -> Self {
+        Dense {
+            weight: Variable::new(he_normal(&[outputs, inputs], inputs)),
+            bias: Variable::new(Tensor::zeros(&[outputs])),
+        }
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.weight.dims()[1]
+    }
+
+    pub fn outputs(&self) -> usize {
+        self.weight.dims()[0]
+    }
+}
+
+impl Layer for Dense {
+    fn output_dims(&self, input: &[usize]) -> Vec<usize> {
+        assert_eq!(input, &[self.inputs()], "dense layer of {} inputs", self.inputs());
+        vec![self.outputs()]
+    }
+
+    fn forward(&self, x: &Variable) -> Variable {
+        &x.matmul(&self.weight) + &self.bias
+    }
+
+    fn parameters(&self) -> Vec<Variable> {
+        vec![self.weight.clone(), self.bias.clone()]
+    }
+
+    fn parameter_names(&self) -> Vec<String> {
+        vec![String::from("weight"), String::from("bias")]
+    }
+
+    fn spec(&self) -> LayerSpec {
+        LayerSpec::Dense { inputs: self.inputs(), outputs: self.outputs() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::autograd::variable::Variable;
+    use tensor_lib::tensor::Tensor;
+
+    use crate::framework::layer::Layer;
+    use crate::layers::dense::Dense;
+
+    #[test]
+    fn forward() {
+        let dense = Dense::new(3, 2);
+        dense.weight.set_value(Tensor::from_vec(vec![1., 0., 0., 1., 1., 1.], &[2, 3]));
+        dense.bias.set_value(Tensor::from_vec(vec![0.5, -0.5], &[2]));
+        let x = Variable::constant(Tensor::from_vec(vec![1., 2., 3., 4., 5., 6.], &[3, 2]));
+        assert_eq!(dense.forward(&x).value().to_vec(), vec![4.5, 4.5, 10.5, 10.5]);
+        assert_eq!(dense.output_dims(&[3]), vec![2]);
+    }
+}
@@ -0,0 +1,22 @@
+use rand_distr::{Distribution, Normal};
+
+use tensor_lib::tensor::Tensor;
+
+pub mod dense;
+pub mod conv;
+pub mod batch_norm;
+pub mod activations;
+pub mod pooling;
+pub mod residual;
+pub mod sequential;
+pub mod policy_value;
+pub mod spec;
+pub mod serialization;
+
+// keeps the variance of the activations through relu layers
+pub fn he_normal(dims: &[usize], fan_in: usize) -> Tensor {
+    let normal = Normal::new(0., (2. / fan_in as f32).sqrt()).unwrap();
+    let mut rng = rand::thread_rng();
+    let len = dims.iter().product();
+    Tensor::from_vec((0..len).map(|_| normal.sample(&mut rng)).collect(), dims)
+}
@@ -0,0 +1,115 @@
+use tensor_lib::autograd::variable::Variable;
+use tensor_lib::tensor::Tensor;
+
+use crate::framework::layer::Layer;
+use crate::framework::parameters::Parameters;
+use crate::layers::sequential::Sequential;
+
+// small AlphaZero style network: a residual trunk shared by a policy and a value head
+pub struct PolicyValueNet {
+    pub trunk: Sequential,
+    pub policy: Sequential,
+    pub value: Sequential,
+}
+
+impl PolicyValueNet {
+    // moves are the board cells plus pass
+    pub fn new(width: usize, height: usize, planes: usize, channels: usize, blocks: usize) -> Self {
+        let mut trunk = Sequential::new(&[width, height, planes])
+            .conv(channels, 3)
+            .batch_norm()
+            .relu();
+        for _ in 0..blocks {
+            trunk = trunk.residual_block(3).relu();
+        }
+        let policy = Sequential::new(trunk.output_dims())
+            .conv(2, 1)
+            .batch_norm()
+            .relu()
+            .flatten()
+            .dense(width * height + 1)
+            .softmax();
+        let value = Sequential::new(trunk.output_dims())
+            .conv(1, 1)
+            .batch_norm()
+            .relu()
+            .global_avg_pool()
+            .dense(channels)
+            .relu()
+            .dense(1)
+            .tanh();
+        PolicyValueNet { trunk, policy, value }
+    }
+
+    // x: [width, height, planes, batch] -> ([moves, batch], [1, batch])
+    pub fn forward(&self, x: &Variable) -> (Variable, Variable) {
+        let features = self.trunk.forward(x);
+        (self.policy.forward(&features), self.value.forward(&features))
+    }
+
+    pub fn parameters(&self) -> Vec<Variable> {
+        let mut res = self.trunk.parameters();
+        res.extend(self.policy.parameters());
+        res.extend(self.value.parameters());
+        res
+    }
+
+    pub fn set_training(&mut self, training: bool) {
+        self.trunk.set_training(training);
+        self.policy.set_training(training);
+        self.value.set_training(training);
+    }
+}
+
+// named after the sub network holding them
+impl Parameters for PolicyValueNet {
+    fn named_parameters(&self) -> Vec<(String, Tensor)> {
+        [("trunk", &self.trunk), ("policy", &self.policy), ("value", &self.value)].iter()
+            .flat_map(|(prefix, net)| net.named_parameters().into_iter()
+                .map(move |(name, t)| (format!("{}.{}", prefix, name), t)))
+            .collect()
+    }
+
+    fn set_parameters(&mut self, values: Vec<Tensor>) {
+        let mut values = values.into_iter();
+        for net in [&mut self.trunk, &mut self.policy, &mut self.value].iter_mut() {
+            let count = Layer::parameters(&**net).len();
+            net.set_parameters(values.by_ref().take(count).collect());
+        }
+    }
+
+    fn flat_update<F: FnOnce(f32) -> f32>(&mut self, index: usize, f: F) {
+        let mut offset = index;
+        for net in [&mut self.trunk, &mut self.policy, &mut self.value].iter_mut() {
+            let count = net.parameter_count();
+            if offset < count {
+                return net.flat_update(offset, f);
+            }
+            offset -= count;
+        }
+        panic!("parameter {} is out of {} values", index, index - offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::autograd::variable::Variable;
+    use tensor_lib::tensor::Tensor;
+
+    use crate::layers::policy_value::PolicyValueNet;
+
+    #[test]
+    fn heads() {
+        let net = PolicyValueNet::new(5, 5, 4, 8, 2);
+        let x = Variable::constant(Tensor::from_vec((0..300).map(|i| (i % 3) as f32).collect(), &[5, 5, 4, 3]));
+        let (policy, value) = net.forward(&x);
+        assert_eq!(policy.dims(), vec![26, 3]);
+        assert_eq!(value.dims(), vec![1, 3]);
+        assert!((policy.value().sum() - 3.).abs() < 1e-4);
+        assert!(value.value().iter().all(|v| v.abs() < 1.));
+
+        // every parameter receives a gradient
+        (&policy.log().mean() + &value.mean()).backward();
+        assert!(net.parameters().iter().all(|p| p.grad().is_some()));
+    }
+}
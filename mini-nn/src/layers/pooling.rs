@@ -0,0 +1,66 @@
+use tensor_lib::autograd::variable::Variable;
+
+use crate::framework::layer::Layer;
+use crate::layers::spec::LayerSpec;
+
+// [width, height, channels, batch] -> [channels, batch]
+pub struct GlobalAvgPool;
+
+impl Layer for GlobalAvgPool {
+    fn output_dims(&self, input: &[usize]) -> Vec<usize> {
+        assert_eq!(input.len(), 3, "global average pooling of {:?}", input);
+        vec![input[2]]
+    }
+
+    fn forward(&self, x: &Variable) -> Variable {
+        let dims = x.dims();
+        let (area, channels) = (dims[0] * dims[1], dims[2]);
+        let batch = dims.get(3).cloned().unwrap_or(1);
+        x.reshape(&[area, channels, batch])
+            .sum_to(&[1, channels, batch])
+            .reshape(&[channels, batch])
+            .scale(1. / area as f32)
+    }
+
+    fn spec(&self) -> LayerSpec {
+        LayerSpec::GlobalAvgPool
+    }
+}
+
+// any sample -> [len, batch]
+pub struct Flatten {
+    pub dims: Vec<usize>,
+}
+
+impl Layer for Flatten {
+    fn output_dims(&self, input: &[usize]) -> Vec<usize> {
+        vec![input.iter().product()]
+    }
+
+    fn forward(&self, x: &Variable) -> Variable {
+        let len: usize = self.dims.iter().product();
+        x.reshape(&[len, x.value().len() / len])
+    }
+
+    fn spec(&self) -> LayerSpec {
+        LayerSpec::Flatten { dims: self.dims.clone() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::autograd::variable::Variable;
+    use tensor_lib::tensor::Tensor;
+
+    use crate::framework::layer::Layer;
+    use crate::layers::pooling::{Flatten, GlobalAvgPool};
+
+    #[test]
+    fn pool_and_flatten() {
+        let x = Variable::constant(Tensor::from_vec((0..16).map(|i| i as f32).collect(), &[2, 2, 2, 2]));
+        assert_eq!(GlobalAvgPool.output_dims(&[2, 2, 2]), vec![2]);
+        assert_eq!(GlobalAvgPool.forward(&x).value().to_vec(), vec![1.5, 5.5, 9.5, 13.5]);
+        let flat = Flatten { dims: vec![2, 2, 2] }.forward(&x);
+        assert_eq!(flat.dims(), vec![8, 2]);
+    }
+}
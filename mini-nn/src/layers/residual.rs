@@ -0,0 +1,63 @@
+use tensor_lib::autograd::variable::Variable;
+
+use crate::framework::layer::Layer;
+use crate::layers::sequential::Sequential;
+use crate::layers::spec::LayerSpec;
+use tensor_lib::tensor::Tensor;
+
+// x + body(x), the body keeps the dimensions
+pub struct Residual {
+    pub body: Sequential,
+}
+
+impl Residual {
+    pub fn new(body: Sequential) -> Self {
+        assert_eq!(body.input_dims(), body.output_dims(), "residual body changes the dimensions");
+        Residual { body }
+    }
+
+    // conv, batch norm, relu, conv, batch norm
+    pub fn block(dims: &[usize], kernel: usize) -> Self {
+        let channels = dims[dims.len() - 1];
+        Residual::new(Sequential::new(dims)
+            .conv(channels, kernel)
+            .batch_norm()
+            .relu()
+            .conv(channels, kernel)
+            .batch_norm())
+    }
+}
+
+impl Layer for Residual {
+    fn output_dims(&self, input: &[usize]) -> Vec<usize> {
+        self.body.output_dims_from(input)
+    }
+
+    fn forward(&self, x: &Variable) -> Variable {
+        x + &self.body.forward(x)
+    }
+
+    fn parameters(&self) -> Vec<Variable> {
+        self.body.parameters()
+    }
+
+    fn parameter_names(&self) -> Vec<String> {
+        self.body.parameter_names().iter().map(|name| format!("body.{}", name)).collect()
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.body.set_training(training)
+    }
+
+    fn spec(&self) -> LayerSpec {
+        LayerSpec::Residual { body: Box::new(self.body.spec()) }
+    }
+
+    fn state(&self) -> Vec<Tensor> {
+        self.body.state()
+    }
+
+    fn load_state(&self, state: &mut dyn Iterator<Item=Tensor>) -> Result<(), String> {
+        self.body.load_state(state)
+    }
+}
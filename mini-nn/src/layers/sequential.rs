@@ -0,0 +1,223 @@
+use tensor_lib::autograd::variable::Variable;
+use tensor_lib::tensor::Tensor;
+
+use crate::framework::layer::Layer;
+use crate::framework::model::Model;
+use crate::layers::activations::{Softmax, Tanh};
+use crate::layers::batch_norm::BatchNorm;
+use crate::layers::conv::Conv;
+use crate::layers::dense::Dense;
+use crate::layers::pooling::{Flatten, GlobalAvgPool};
+use crate::layers::residual::Residual;
+use crate::layers::spec::LayerSpec;
+use crate::relu::Relu;
+
+// layers applied in order, each one built from the output dimensions of the previous
+pub struct Sequential {
+    input_dims: Vec<usize>,
+    output_dims: Vec<usize>,
+    layers: Vec<Box<dyn Layer>>,
+    training: bool,
+}
+
+impl Sequential {
+    pub fn new(input_dims: &[usize]) -> Self {
+        Sequential {
+            input_dims: input_dims.to_vec(),
+            output_dims: input_dims.to_vec(),
+            layers: vec![],
+            training: true,
+        }
+    }
+
+    pub fn input_dims(&self) -> &[usize] {
+        &self.input_dims
+    }
+
+    pub fn output_dims(&self) -> &[usize] {
+        &self.output_dims
+    }
+
+    pub fn output_dims_from(&self, input: &[usize]) -> Vec<usize> {
+        assert_eq!(input, self.input_dims.as_slice());
+        self.output_dims.clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    pub fn push<L: Layer + 'static>(self, layer: L) -> Self {
+        self.push_boxed(Box::new(layer))
+    }
+
+    pub fn push_boxed(mut self, layer: Box<dyn Layer>) -> Self {
+        self.output_dims = layer.output_dims(&self.output_dims);
+        self.layers.push(layer);
+        self
+    }
+
+    fn channels(&self) -> usize {
+        self.output_dims[self.output_dims.len() - 1]
+    }
+
+    pub fn dense(self, outputs: usize) -> Self {
+        assert_eq!(self.output_dims.len(), 1, "dense layer after {:?}, flatten first", self.output_dims);
+        let inputs = self.output_dims[0];
+        self.push(Dense::new(inputs, outputs))
+    }
+
+    pub fn conv(self, features: usize, kernel: usize) -> Self {
+        let channels = self.channels();
+        self.push(Conv::same(channels, features, kernel))
+    }
+
+    pub fn batch_norm(self) -> Self {
+        let layer = BatchNorm::new(&self.output_dims);
+        self.push(layer)
+    }
+
+    pub fn relu(self) -> Self {
+        self.push(Relu::new())
+    }
+
+    pub fn tanh(self) -> Self {
+        self.push(Tanh)
+    }
+
+    pub fn softmax(self) -> Self {
+        self.push(Softmax)
+    }
+
+    pub fn flatten(self) -> Self {
+        let dims = self.output_dims.clone();
+        self.push(Flatten { dims })
+    }
+
+    pub fn global_avg_pool(self) -> Self {
+        self.push(GlobalAvgPool)
+    }
+
+    pub fn residual<F: FnOnce(Sequential) -> Sequential>(self, body: F) -> Self {
+        let layer = Residual::new(body(Sequential::new(&self.output_dims)));
+        self.push(layer)
+    }
+
+    pub fn residual_block(self, kernel: usize) -> Self {
+        let layer = Residual::block(&self.output_dims, kernel);
+        self.push(layer)
+    }
+
+    // a single sample gets a batch dimension of 1
+    pub fn batched(&self, x: &Tensor) -> Tensor {
+        match x.dims() == self.input_dims.as_slice() {
+            true => {
+                let mut dims = self.input_dims.clone();
+                dims.push(1);
+                x.reshape(&dims)
+            }
+            false => x.clone()
+        }
+    }
+}
+
+impl Layer for Sequential {
+    fn output_dims(&self, input: &[usize]) -> Vec<usize> {
+        self.output_dims_from(input)
+    }
+
+    fn forward(&self, x: &Variable) -> Variable {
+        self.layers.iter().fold(x.clone(), |x, layer| layer.forward(&x))
+    }
+
+    fn parameters(&self) -> Vec<Variable> {
+        self.layers.iter().flat_map(|layer| layer.parameters()).collect()
+    }
+
+    // prefixed by the layer index
+    fn parameter_names(&self) -> Vec<String> {
+        self.layers.iter().enumerate()
+            .flat_map(|(i, layer)| layer.parameter_names().into_iter().map(move |name| format!("{}.{}", i, name)))
+            .collect()
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+        for layer in self.layers.iter_mut() {
+            layer.set_training(training);
+        }
+    }
+
+    fn spec(&self) -> LayerSpec {
+        LayerSpec::Sequential {
+            input_dims: self.input_dims.clone(),
+            layers: self.layers.iter().map(|layer| layer.spec()).collect(),
+        }
+    }
+
+    fn state(&self) -> Vec<Tensor> {
+        self.layers.iter().flat_map(|layer| layer.state()).collect()
+    }
+
+    fn load_state(&self, state: &mut dyn Iterator<Item=Tensor>) -> Result<(), String> {
+        self.layers.iter().try_for_each(|layer| layer.load_state(state))
+    }
+}
+
+// deep copy, rebuilt from the spec and the state
+impl Clone for Sequential {
+    fn clone(&self) -> Self {
+        let mut res = self.spec().sequential().unwrap();
+        res.load_state(&mut self.state().into_iter().map(|t| t.deep_clone())).unwrap();
+        res.set_training(self.training);
+        res
+    }
+}
+
+impl Model<Tensor, Tensor> for Sequential {
+    fn predict(&self, x: &Tensor, y: &mut Tensor) {
+        let res = self.forward(&Variable::constant(self.batched(x))).value();
+        y.copy_from(&res);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tensor_lib::tensor::Tensor;
+
+    use crate::framework::layer::Layer;
+    use crate::framework::model::Model;
+    use crate::layers::sequential::Sequential;
+
+    #[test]
+    fn infer_shapes() {
+        let mut net = Sequential::new(&[5, 5, 3])
+            .conv(8, 3)
+            .batch_norm()
+            .relu()
+            .residual_block(3)
+            .relu()
+            .global_avg_pool()
+            .dense(4)
+            .softmax();
+        assert_eq!(net.output_dims(), &[4]);
+        assert_eq!(net.len(), 8);
+        // conv, batch norm, 2 convs and 2 batch norms in the block, dense
+        assert_eq!(net.parameters().len(), 14);
+
+        net.set_training(false);
+        let mut y = Tensor::zeros(&[4]);
+        net.predict(&Tensor::full(&[5, 5, 3], 0.5), &mut y);
+        assert!((y.sum() - 1.).abs() < 1e-5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn dense_needs_flat_input() {
+        let _ = Sequential::new(&[3, 3, 2]).dense(2);
+    }
+}
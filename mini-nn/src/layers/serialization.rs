@@ -0,0 +1,219 @@
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use tensor_lib::tensor::Tensor;
+
+use crate::framework::layer::Layer;
+use crate::layers::policy_value::PolicyValueNet;
+use crate::layers::sequential::Sequential;
+use crate::layers::spec::LayerSpec;
+
+// magic, version, json header length, json header, then the tensors of each graph
+const MAGIC: &[u8; 4] = b"MNNW";
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    version: u32,
+    graphs: Vec<GraphHeader>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GraphHeader {
+    name: String,
+    architecture: LayerSpec,
+    tensors: Vec<Vec<usize>>,
+}
+
+fn invalid<E: ToString>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+fn read_u32<R: Read>(input: &mut R) -> io::Result<u32> {
+    let mut bytes = [0_u8; 4];
+    input.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+pub fn save<W: Write>(out: &mut W, graphs: &[(&str, &Sequential)]) -> io::Result<()> {
+    let states: Vec<Vec<Tensor>> = graphs.iter().map(|(_, g)| g.state()).collect();
+    let header = Header {
+        version: VERSION,
+        graphs: graphs.iter().zip(states.iter())
+            .map(|((name, graph), state)| GraphHeader {
+                name: name.to_string(),
+                architecture: graph.spec(),
+                tensors: state.iter().map(|t| t.dims().to_vec()).collect(),
+            })
+            .collect(),
+    };
+    let json = serde_json::to_vec(&header).map_err(invalid)?;
+    out.write_all(MAGIC)?;
+    out.write_all(&VERSION.to_le_bytes())?;
+    out.write_all(&(json.len() as u32).to_le_bytes())?;
+    out.write_all(&json)?;
+    for tensor in states.iter().flatten() {
+        tensor.write_to(out)?;
+    }
+    out.flush()
+}
+
+// rebuilds the graphs and checks every tensor against the architecture
+pub fn load<R: Read>(input: &mut R) -> io::Result<Vec<(String, Sequential)>> {
+    let mut magic = [0_u8; 4];
+    input.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(invalid("not a mini-nn model file"));
+    }
+    let version = read_u32(input)?;
+    if version != VERSION {
+        return Err(invalid(format!("unsupported model version {} (expected {})", version, VERSION)));
+    }
+    let mut json = vec![0_u8; read_u32(input)? as usize];
+    input.read_exact(&mut json)?;
+    let header: Header = serde_json::from_slice(&json).map_err(invalid)?;
+
+    let mut res = vec![];
+    for graph in header.graphs {
+        let model = graph.architecture.sequential().map_err(invalid)?;
+        let expected: Vec<Vec<usize>> = model.state().iter().map(|t| t.dims().to_vec()).collect();
+        if expected != graph.tensors {
+            return Err(invalid(format!("{}: tensors {:?} do not match the architecture {:?}",
+                                       graph.name, graph.tensors, expected)));
+        }
+        let tensors = graph.tensors.iter()
+            .map(|dims| Tensor::read_expected(input, dims))
+            .collect::<io::Result<Vec<_>>>()?;
+        model.load_state(&mut tensors.into_iter())
+            .map_err(|e| invalid(format!("{}: {}", graph.name, e)))?;
+        res.push((graph.name, model));
+    }
+    Ok(res)
+}
+
+pub fn save_file<P: AsRef<Path>>(path: P, graphs: &[(&str, &Sequential)]) -> io::Result<()> {
+    save(&mut BufWriter::new(File::create(path)?), graphs)
+}
+
+pub fn load_file<P: AsRef<Path>>(path: P) -> io::Result<Vec<(String, Sequential)>> {
+    load(&mut BufReader::new(File::open(path)?))
+}
+
+fn take(graphs: &mut Vec<(String, Sequential)>, name: &str) -> io::Result<Sequential> {
+    match graphs.iter().position(|(n, _)| n == name) {
+        Some(i) => Ok(graphs.remove(i).1),
+        None => Err(invalid(format!("missing graph {}", name)))
+    }
+}
+
+impl Sequential {
+    pub fn save_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        save_file(path, &[("model", self)])
+    }
+
+    // loaded models are in evaluation mode
+    pub fn load_file<P: AsRef<Path>>(path: P) -> io::Result<Sequential> {
+        let mut model = take(&mut load_file(path)?, "model")?;
+        model.set_training(false);
+        Ok(model)
+    }
+}
+
+impl PolicyValueNet {
+    pub fn save_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        save_file(path, &[("trunk", &self.trunk), ("policy", &self.policy), ("value", &self.value)])
+    }
+
+    pub fn load_file<P: AsRef<Path>>(path: P) -> io::Result<PolicyValueNet> {
+        let mut graphs = load_file(path)?;
+        let mut net = PolicyValueNet {
+            trunk: take(&mut graphs, "trunk")?,
+            policy: take(&mut graphs, "policy")?,
+            value: take(&mut graphs, "value")?,
+        };
+        net.set_training(false);
+        Ok(net)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::io;
+    use std::io::Cursor;
+
+    use tensor_lib::autograd::variable::Variable;
+    use tensor_lib::tensor::Tensor;
+
+    use crate::framework::layer::Layer;
+    use crate::layers::policy_value::PolicyValueNet;
+    use crate::layers::sequential::Sequential;
+    use crate::layers::serialization::{load, save};
+
+    #[test]
+    fn round_trip() {
+        let mut model = Sequential::new(&[4, 4, 2]).conv(3, 3).batch_norm().relu().residual_block(1)
+            .flatten().dense(5).softmax();
+        let x = Variable::constant(Tensor::from_vec((0..64).map(|i| (i % 5) as f32).collect(), &[4, 4, 2, 2]));
+        // updates the batch norm statistics
+        model.forward(&x);
+        model.set_training(false);
+
+        let mut bytes = vec![];
+        save(&mut bytes, &[("model", &model)]).unwrap();
+        let (name, mut loaded) = load(&mut Cursor::new(&bytes)).unwrap().remove(0);
+        loaded.set_training(false);
+        assert_eq!(name, "model");
+        assert_eq!(loaded.spec(), model.spec());
+        assert_eq!(loaded.forward(&x).value(), model.forward(&x).value());
+
+        // corrupted version, truncated data and mismatching shapes are rejected
+        let mut wrong = bytes.clone();
+        wrong[4] = 9;
+        assert!(load(&mut Cursor::new(&wrong)).err().unwrap().to_string().contains("version"));
+        assert!(load(&mut Cursor::new(&bytes[..bytes.len() - 4])).is_err());
+        let at = bytes.windows(11).position(|w| w == b"\"outputs\":5").unwrap();
+        let mut wrong = bytes.clone();
+        wrong[at + 10] = b'6';
+        assert!(load(&mut Cursor::new(&wrong)).err().unwrap().to_string().contains("do not match"));
+    }
+
+    #[test]
+    fn corrupted_architecture() {
+        let model = Sequential::new(&[4, 4, 2]).conv(3, 3).residual_block(1).flatten().dense(5);
+        let mut bytes = vec![];
+        save(&mut bytes, &[("model", &model)]).unwrap();
+        let corrupted = |from: &[u8], to: &[u8]| {
+            let at = bytes.windows(from.len()).position(|w| w == from).unwrap();
+            let mut data = bytes.clone();
+            data[at..at + to.len()].copy_from_slice(to);
+            load(&mut Cursor::new(&data)).err().map(|e| e.kind())
+        };
+        assert_eq!(corrupted(b"\"inputs\":48", b"\"inputs\":45"), Some(io::ErrorKind::InvalidData));
+        assert_eq!(corrupted(b"\"kernel\":3", b"\"kernel\":2"), Some(io::ErrorKind::InvalidData));
+        // a conv of the residual body gets 4 features
+        assert_eq!(corrupted(b"\"features\":3,\"kernel\":1", b"\"features\":4,\"kernel\":1"),
+                   Some(io::ErrorKind::InvalidData));
+
+        // the first tensor, the conv filter of 3x3x2x3, stored with other dimensions
+        let header = 12 + u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize;
+        let mut data = bytes.clone();
+        data[header + 4..header + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(load(&mut Cursor::new(&data)).err().map(|e| e.kind()), Some(io::ErrorKind::InvalidData));
+    }
+
+    #[test]
+    fn policy_value_file() {
+        let net = PolicyValueNet::new(3, 3, 2, 4, 1);
+        let path = env::temp_dir().join(format!("policy_value_{}.mnn", std::process::id()));
+        net.save_file(&path).unwrap();
+        let loaded = PolicyValueNet::load_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.parameters().len(), net.parameters().len());
+        assert!(loaded.parameters().iter().zip(net.parameters().iter()).all(|(a, b)| a.value() == b.value()));
+    }
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+use crate::framework::layer::Layer;
+use crate::layers::activations::{Softmax, Tanh};
+use crate::layers::batch_norm::BatchNorm;
+use crate::layers::conv::Conv;
+use crate::layers::dense::Dense;
+use crate::layers::pooling::{Flatten, GlobalAvgPool};
+use crate::layers::residual::Residual;
+use crate::layers::sequential::Sequential;
+use crate::relu::Relu;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum LayerSpec {
+    Dense { inputs: usize, outputs: usize },
+    Conv { channels: usize, features: usize, kernel: usize },
+    BatchNorm { dims: Vec<usize>, momentum: f32 },
+    Relu,
+    Tanh,
+    Softmax,
+    Flatten { dims: Vec<usize> },
+    GlobalAvgPool,
+    Residual { body: Box<LayerSpec> },
+    Sequential { input_dims: Vec<usize>, layers: Vec<LayerSpec> },
+}
+
+impl LayerSpec {
+    // weights are freshly initialised
+    pub fn build(&self) -> Result<Box<dyn Layer>, String> {
+        Ok(match self {
+            LayerSpec::Dense { inputs, outputs } => Box::new(Dense::new(*inputs, *outputs)),
+            LayerSpec::Conv { channels, features, kernel } => {
+                if kernel % 2 == 0 {
+                    return Err(format!("same padding needs an odd kernel, got {}", kernel));
+                }
+                Box::new(Conv::same(*channels, *features, *kernel))
+            }
+            LayerSpec::BatchNorm { dims, momentum } => {
+                let mut layer = BatchNorm::new(dims);
+                layer.momentum = *momentum;
+                Box::new(layer)
+            }
+            LayerSpec::Relu => Box::new(Relu::new()),
+            LayerSpec::Tanh => Box::new(Tanh),
+            LayerSpec::Softmax => Box::new(Softmax),
+            LayerSpec::Flatten { dims } => Box::new(Flatten { dims: dims.clone() }),
+            LayerSpec::GlobalAvgPool => Box::new(GlobalAvgPool),
+            LayerSpec::Residual { body } => {
+                let body = body.sequential()?;
+                if body.input_dims() != body.output_dims() {
+                    return Err(format!("residual body changes the dimensions {:?}", body.input_dims()));
+                }
+                Box::new(Residual::new(body))
+            }
+            LayerSpec::Sequential { .. } => Box::new(self.sequential()?),
+        })
+    }
+
+    // the dimensions are checked before any layer is built
+    pub fn sequential(&self) -> Result<Sequential, String> {
+        match self {
+            LayerSpec::Sequential { input_dims, layers } => {
+                self.output_dims(input_dims)?;
+                layers.iter()
+                    .try_fold(Sequential::new(input_dims), |seq, layer| Ok(seq.push_boxed(layer.build()?)))
+            }
+            other => Err(format!("expected a sequential, got {:?}", other))
+        }
+    }
+
+    // output dimensions of the layer on an input, an error where the layer would panic
+    pub fn output_dims(&self, input: &[usize]) -> Result<Vec<usize>, String> {
+        match self {
+            LayerSpec::Dense { inputs, outputs } => match input == [*inputs] {
+                true => Ok(vec![*outputs]),
+                false => Err(format!("dense layer of {} inputs on {:?}", inputs, input))
+            },
+            LayerSpec::Conv { channels, features, kernel } => {
+                if kernel % 2 == 0 {
+                    return Err(format!("same padding needs an odd kernel, got {}", kernel));
+                }
+                match input.len() == 3 && input[2] == *channels {
+                    true => Ok(vec![input[0], input[1], *features]),
+                    false => Err(format!("conv of {} channels on {:?}", channels, input))
+                }
+            }
+            LayerSpec::BatchNorm { dims, .. } => {
+                match !dims.is_empty() && dims.len() == input.len() && dims.last() == input.last() {
+                    true => Ok(input.to_vec()),
+                    false => Err(format!("batch norm of {:?} on {:?}", dims, input))
+                }
+            }
+            LayerSpec::Relu | LayerSpec::Tanh => Ok(input.to_vec()),
+            LayerSpec::Softmax => match input.len() {
+                1 => Ok(input.to_vec()),
+                _ => Err(format!("softmax of {:?}", input))
+            },
+            LayerSpec::Flatten { dims } => match dims.as_slice() == input {
+                true => Ok(vec![input.iter().product()]),
+                false => Err(format!("flatten of {:?} on {:?}", dims, input))
+            },
+            LayerSpec::GlobalAvgPool => match input.len() {
+                3 => Ok(vec![input[2]]),
+                _ => Err(format!("global average pooling of {:?}", input))
+            },
+            LayerSpec::Residual { body } => {
+                let output = body.output_dims(input)?;
+                match output == input {
+                    true => Ok(output),
+                    false => Err(format!("residual body changes the dimensions {:?} to {:?}", input, output))
+                }
+            }
+            LayerSpec::Sequential { input_dims, layers } => {
+                if input_dims.as_slice() != input {
+                    return Err(format!("sequential of {:?} on {:?}", input_dims, input));
+                }
+                layers.iter().try_fold(input.to_vec(), |dims, layer| layer.output_dims(&dims))
+            }
+        }
+    }
+}
@@ -6,6 +6,7 @@ extern crate chrono;
 pub mod bench;
 pub mod screen;
 pub mod loggers;
+pub mod parallel;
 
 #[cfg(test)]
 mod tests {
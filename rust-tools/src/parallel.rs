@@ -0,0 +1,43 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+// maps the items over scoped worker threads, each one taking the next unprocessed item:
+// results keep the order of the items whatever thread computed them
+pub fn parallel_map<T, R, F>(items: &[T], threads: usize, f: F) -> Vec<R>
+    where T: Sync, R: Send, F: Fn(usize, &T) -> R + Sync
+{
+    let next = AtomicUsize::new(0);
+    let threads = threads.min(items.len()).max(1);
+    let mut results: Vec<(usize, R)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| scope.spawn(|| {
+                let mut res = vec![];
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    match items.get(i) {
+                        Some(item) => res.push((i, f(i, item))),
+                        None => break res,
+                    }
+                }
+            }))
+            .collect();
+        workers.into_iter().flat_map(|w| w.join().unwrap()).collect()
+    });
+    results.sort_by_key(|&(i, _)| i);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use parallel::parallel_map;
+
+    #[test]
+    fn keeps_the_order() {
+        let items: Vec<usize> = (0..100).collect();
+        let expected: Vec<usize> = items.iter().map(|x| x * x).collect();
+        for threads in 0..5 {
+            assert_eq!(parallel_map(&items, threads, |i, x| i * x), expected);
+        }
+        assert!(parallel_map(&[] as &[usize], 4, |_, x| *x).is_empty());
+    }
+}
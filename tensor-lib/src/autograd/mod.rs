@@ -0,0 +1,2 @@
+pub mod variable;
+pub mod ops;
\ No newline at end of file
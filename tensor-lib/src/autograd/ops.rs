@@ -0,0 +1,242 @@
+use std::ops::{Add, Mul, Sub};
+
+use crate::autograd::variable::Variable;
+use crate::tensor::Tensor;
+
+// broadcasting operands get their gradients summed back to their own shape by backward()
+impl Variable {
+    pub fn add(&self, rhs: &Variable) -> Variable {
+        let value = &self.value() + &rhs.value();
+        Variable::from_op(value, vec![self.clone(), rhs.clone()], Box::new(|g| {
+            vec![g.clone(), g.clone()]
+        }))
+    }
+
+    pub fn sub(&self, rhs: &Variable) -> Variable {
+        let value = &self.value() - &rhs.value();
+        Variable::from_op(value, vec![self.clone(), rhs.clone()], Box::new(|g| {
+            vec![g.clone(), g.map(|x| -x)]
+        }))
+    }
+
+    pub fn mul(&self, rhs: &Variable) -> Variable {
+        let (a, b) = (self.value(), rhs.value());
+        let value = &a * &b;
+        Variable::from_op(value, vec![self.clone(), rhs.clone()], Box::new(move |g| {
+            vec![g * &b, g * &a]
+        }))
+    }
+
+    pub fn scale(&self, k: f32) -> Variable {
+        let value = self.value().map(|x| x * k);
+        Variable::from_op(value, vec![self.clone()], Box::new(move |g| {
+            vec![g.map(|x| x * k)]
+        }))
+    }
+
+    // [k, m] x [n, k] -> [n, m]
+    pub fn matmul(&self, rhs: &Variable) -> Variable {
+        let (a, b) = (self.value(), rhs.value());
+        let value = a.matmul(&b);
+        Variable::from_op(value, vec![self.clone(), rhs.clone()], Box::new(move |g| {
+            vec![g.matmul(&b.transpose(0, 1)), a.transpose(0, 1).matmul(g)]
+        }))
+    }
+
+    pub fn conv2d(&self, filter: &Variable, padding: usize, stride: usize) -> Variable {
+        let (input, kernel) = (self.value(), filter.value());
+        let value = input.conv2d(&kernel, padding, stride);
+        Variable::from_op(value, vec![self.clone(), filter.clone()], Box::new(move |g| {
+            let (grad_input, grad_filter) = input.conv2d_backward(&kernel, padding, stride, g);
+            vec![grad_input, grad_filter]
+        }))
+    }
+
+    pub fn relu(&self) -> Variable {
+        let x = self.value();
+        Variable::from_op(x.relu(), vec![self.clone()], Box::new(move |g| {
+            vec![g.zip_with(&x, |g, x| if x > 0. { g } else { 0. })]
+        }))
+    }
+
+    pub fn sum(&self) -> Variable {
+        let dims = self.dims();
+        let value = Tensor::full(&[1], self.value().sum());
+        Variable::from_op(value, vec![self.clone()], Box::new(move |g| {
+            vec![Tensor::full(&dims, g.get(0))]
+        }))
+    }
+
+    pub fn mean(&self) -> Variable {
+        let n = self.value().len() as f32;
+        self.sum().scale(1. / n)
+    }
+
+    // along the first dimension
+    pub fn softmax(&self) -> Variable {
+        let y = self.value().softmax();
+        let value = y.clone();
+        Variable::from_op(value, vec![self.clone()], Box::new(move |g| {
+            let dot = (g * &y).sum_rows();
+            vec![&y * &(g - &dot)]
+        }))
+    }
+
+    pub fn tanh(&self) -> Variable {
+        let y = self.value().map(f32::tanh);
+        let value = y.clone();
+        Variable::from_op(value, vec![self.clone()], Box::new(move |g| {
+            vec![g.zip_with(&y, |g, y| g * (1. - y * y))]
+        }))
+    }
+
+    pub fn powf(&self, p: f32) -> Variable {
+        let x = self.value();
+        Variable::from_op(x.map(|x| x.powf(p)), vec![self.clone()], Box::new(move |g| {
+            vec![g.zip_with(&x, |g, x| g * p * x.powf(p - 1.))]
+        }))
+    }
+
+    pub fn reshape(&self, dims: &[usize]) -> Variable {
+        let from = self.dims();
+        Variable::from_op(self.value().reshape(dims), vec![self.clone()], Box::new(move |g| {
+            vec![g.reshape(&from)]
+        }))
+    }
+
+    // sums the dimensions which are 1 (or missing) in dims
+    pub fn sum_to(&self, dims: &[usize]) -> Variable {
+        let from = self.dims();
+        Variable::from_op(self.value().sum_to(dims), vec![self.clone()], Box::new(move |g| {
+            vec![g.broadcast_to(&from).contiguous()]
+        }))
+    }
+
+    pub fn log(&self) -> Variable {
+        let x = self.value();
+        Variable::from_op(x.ln(), vec![self.clone()], Box::new(move |g| {
+            vec![g / &x]
+        }))
+    }
+}
+
+impl Add for &Variable {
+    type Output = Variable;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Variable::add(self, rhs)
+    }
+}
+
+impl Sub for &Variable {
+    type Output = Variable;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Variable::sub(self, rhs)
+    }
+}
+
+impl Mul for &Variable {
+    type Output = Variable;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Variable::mul(self, rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::autograd::variable::Variable;
+    use crate::tensor::Tensor;
+
+    const EPS: f32 = 1e-2;
+
+    // deterministic values in [-1.1, 0.9], never closer than 0.1 to 0
+    fn sample(dims: &[usize], seed: usize) -> Tensor {
+        let len = dims.iter().product::<usize>();
+        let values = (0..len).map(|i| ((i * 7 + seed) % 11) as f32 / 5. - 1.1).collect();
+        Tensor::from_vec(values, dims)
+    }
+
+    // compares backward() with central differences of a weighted sum of the outputs
+    fn grad_check<F: Fn(&[Variable]) -> Variable>(f: F, inputs: Vec<Tensor>) {
+        let vars: Vec<Variable> = inputs.iter().map(|x| Variable::new(x.clone())).collect();
+        let out = f(&vars);
+        let weights = Variable::constant(sample(&out.dims(), 3));
+        out.mul(&weights).sum().backward();
+
+        let eval = |inputs: &[Tensor]| {
+            let consts: Vec<Variable> = inputs.iter().map(|x| Variable::constant(x.clone())).collect();
+            (&f(&consts).value() * &weights.value()).sum()
+        };
+        for (k, input) in inputs.iter().enumerate() {
+            let grad = vars[k].grad().unwrap();
+            assert_eq!(grad.dims(), input.dims());
+            for i in 0..input.len() {
+                let shifted = |delta: f32| {
+                    let mut xs = inputs.clone();
+                    xs[k] = xs[k].deep_clone();
+                    xs[k].insert(i, input.get(i) + delta);
+                    eval(&xs)
+                };
+                let numeric = (shifted(EPS) - shifted(-EPS)) / (2. * EPS);
+                assert!((numeric - grad.get(i)).abs() <= 1e-2 * (1. + numeric.abs()),
+                        "input {} element {}: numeric {} backward {}", k, i, numeric, grad.get(i));
+            }
+        }
+    }
+
+    #[test]
+    fn add_sub_mul() {
+        grad_check(|v| v[0].add(&v[1]), vec![sample(&[3, 2], 0), sample(&[3, 2], 1)]);
+        grad_check(|v| v[0].sub(&v[1]), vec![sample(&[3, 2], 0), sample(&[3], 1)]);
+        grad_check(|v| v[0].mul(&v[1]), vec![sample(&[3, 2], 0), sample(&[1, 2], 1)]);
+        grad_check(|v| v[0].scale(-3.), vec![sample(&[4], 2)]);
+    }
+
+    #[test]
+    fn matmul() {
+        grad_check(|v| v[0].matmul(&v[1]), vec![sample(&[3, 2], 0), sample(&[4, 3], 1)]);
+    }
+
+    #[test]
+    fn conv2d() {
+        grad_check(|v| v[0].conv2d(&v[1], 0, 1), vec![sample(&[4, 3, 2], 0), sample(&[2, 2, 2, 3], 1)]);
+        grad_check(|v| v[0].conv2d(&v[1], 1, 1), vec![sample(&[3, 3, 2, 2], 4), sample(&[3, 3, 2, 2], 5)]);
+        grad_check(|v| v[0].conv2d(&v[1], 1, 2), vec![sample(&[5, 4, 2], 6), sample(&[3, 2, 2, 2], 7)]);
+    }
+
+    #[test]
+    fn activations() {
+        grad_check(|v| v[0].relu(), vec![sample(&[5, 2], 0)]);
+        grad_check(|v| v[0].softmax(), vec![sample(&[4, 3], 1)]);
+        grad_check(|v| v[0].log(), vec![sample(&[5, 2], 2).map(|x| x.abs())]);
+        grad_check(|v| v[0].tanh(), vec![sample(&[5, 2], 3)]);
+        grad_check(|v| v[0].powf(-0.5), vec![sample(&[5, 2], 4).map(|x| x.abs())]);
+    }
+
+    #[test]
+    fn reductions() {
+        grad_check(|v| v[0].sum(), vec![sample(&[3, 2], 0)]);
+        grad_check(|v| v[0].mean(), vec![sample(&[3, 2, 2], 1)]);
+        grad_check(|v| v[0].sum_to(&[1, 2, 1]), vec![sample(&[3, 2, 2], 2)]);
+        grad_check(|v| v[0].reshape(&[2, 6]).mul(&v[0].reshape(&[2, 6])), vec![sample(&[3, 4], 3)]);
+    }
+
+    #[test]
+    fn accumulation() {
+        let x = Variable::new(Tensor::from_vec(vec![1., 2.], &[2]));
+        let c = Variable::constant(Tensor::full(&[2], 3.));
+        let y = (&(&x * &x) + &(&x * &c)).sum();
+        y.backward();
+        assert_eq!(x.grad().unwrap().to_vec(), vec![5., 7.]);
+        assert!(c.grad().is_none());
+
+        // a second backward adds to the leaf gradients until they are reset
+        y.backward();
+        assert_eq!(x.grad().unwrap().to_vec(), vec![10., 14.]);
+        x.zero_grad();
+        assert!(x.grad().is_none());
+        assert!(!(&c * &c).requires_grad());
+    }
+}
@@ -0,0 +1,171 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Formatter};
+use std::fmt;
+use std::rc::Rc;
+
+use crate::tensor::Tensor;
+
+// gradients of the parents given the gradient of the output
+pub type Backward = Box<dyn Fn(&Tensor) -> Vec<Tensor>>;
+
+struct Node {
+    value: RefCell<Tensor>,
+    grad: RefCell<Option<Tensor>>,
+    requires_grad: bool,
+    parents: Vec<Variable>,
+    backward: Option<Backward>,
+}
+
+// a tensor recording the operations it comes from, gradients accumulate on leaves
+#[derive(Clone)]
+pub struct Variable {
+    node: Rc<Node>,
+}
+
+impl Variable {
+    pub fn new(value: Tensor) -> Self {
+        Variable::leaf(value, true)
+    }
+
+    pub fn constant(value: Tensor) -> Self {
+        Variable::leaf(value, false)
+    }
+
+    fn leaf(value: Tensor, requires_grad: bool) -> Self {
+        Variable {
+            node: Rc::new(Node {
+                value: RefCell::new(value),
+                grad: RefCell::new(None),
+                requires_grad,
+                parents: vec![],
+                backward: None,
+            })
+        }
+    }
+
+    // constants do not record anything
+    pub fn from_op(value: Tensor, parents: Vec<Variable>, backward: Backward) -> Self {
+        if !parents.iter().any(|p| p.requires_grad()) {
+            return Variable::constant(value);
+        }
+        Variable {
+            node: Rc::new(Node {
+                value: RefCell::new(value),
+                grad: RefCell::new(None),
+                requires_grad: true,
+                parents,
+                backward: Some(backward),
+            })
+        }
+    }
+
+    pub fn value(&self) -> Tensor {
+        self.node.value.borrow().clone()
+    }
+
+    // used by optimizers to update parameters in place
+    pub fn set_value(&self, value: Tensor) {
+        assert_eq!(value.dims(), self.node.value.borrow().dims());
+        *self.node.value.borrow_mut() = value;
+    }
+
+    // the buffer is only copied when another tensor shares it
+    pub fn update_value<F: FnOnce(&mut Tensor)>(&self, f: F) {
+        f(&mut self.node.value.borrow_mut());
+    }
+
+    pub fn dims(&self) -> Vec<usize> {
+        self.node.value.borrow().dims().to_vec()
+    }
+
+    pub fn grad(&self) -> Option<Tensor> {
+        self.node.grad.borrow().clone()
+    }
+
+    pub fn zero_grad(&self) {
+        *self.node.grad.borrow_mut() = None;
+    }
+
+    pub fn requires_grad(&self) -> bool {
+        self.node.requires_grad
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.node.backward.is_none()
+    }
+
+    pub fn detach(&self) -> Variable {
+        Variable::constant(self.value())
+    }
+
+    fn id(&self) -> usize {
+        Rc::as_ptr(&self.node) as usize
+    }
+
+    // parents before children
+    fn topological_order(&self) -> Vec<Variable> {
+        let mut order = vec![];
+        let mut visited = HashSet::new();
+        let mut stack = vec![(self.clone(), false)];
+        while let Some((var, expanded)) = stack.pop() {
+            if expanded {
+                order.push(var);
+                continue;
+            }
+            if !visited.insert(var.id()) {
+                continue;
+            }
+            stack.push((var.clone(), true));
+            for parent in var.node.parents.iter().filter(|p| p.requires_grad()) {
+                if !visited.contains(&parent.id()) {
+                    stack.push((parent.clone(), false));
+                }
+            }
+        }
+        order
+    }
+
+    // seeds the output gradient with ones
+    pub fn backward(&self) {
+        if !self.requires_grad() {
+            return;
+        }
+        let mut grads: HashMap<usize, Tensor> = HashMap::new();
+        grads.insert(self.id(), Tensor::full(&self.dims(), 1.));
+        for var in self.topological_order().iter().rev() {
+            let grad = match grads.remove(&var.id()) {
+                None => continue,
+                Some(grad) => grad
+            };
+            let backward = match &var.node.backward {
+                None => {
+                    let mut acc = var.node.grad.borrow_mut();
+                    *acc = Some(match acc.take() {
+                        None => grad,
+                        Some(prev) => &prev + &grad
+                    });
+                    continue;
+                }
+                Some(backward) => backward
+            };
+            for (parent, g) in var.node.parents.iter().zip(backward(&grad)) {
+                if !parent.requires_grad() {
+                    continue;
+                }
+                let g = g.sum_to(&parent.dims());
+                let acc = match grads.remove(&parent.id()) {
+                    None => g,
+                    Some(prev) => &prev + &g
+                };
+                grads.insert(parent.id(), acc);
+            }
+        }
+    }
+}
+
+impl Debug for Variable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Variable({}, grad: {:?})", self.node.value.borrow(), self.grad().map(|g| g.to_vec()))
+    }
+}
@@ -0,0 +1,89 @@
+use crate::kernels::simd::axpy;
+
+// panel of b kept in cache while the rows of a go through it
+const KC: usize = 256;
+const NC: usize = 1024;
+
+// c (m x n) += a (m x k) . b (k x n), all row major
+pub fn gemm(m: usize, n: usize, k: usize, a: &[f32], b: &[f32], c: &mut [f32]) {
+    assert!(a.len() == m * k && b.len() == k * n && c.len() == m * n);
+    for jj in (0..n).step_by(NC) {
+        let j_end = (jj + NC).min(n);
+        for pp in (0..k).step_by(KC) {
+            let p_end = (pp + KC).min(k);
+            for i in 0..m {
+                let c_row = &mut c[i * n + jj..i * n + j_end];
+                for p in pp..p_end {
+                    let alpha = a[i * k + p];
+                    if alpha != 0. {
+                        axpy(alpha, &b[p * n + jj..p * n + j_end], c_row);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// reference triple loop
+pub fn gemm_naive(m: usize, n: usize, k: usize, a: &[f32], b: &[f32], c: &mut [f32]) {
+    for i in 0..m {
+        for j in 0..n {
+            let mut acc = 0.;
+            for p in 0..k {
+                acc += a[i * k + p] * b[p * n + j];
+            }
+            c[i * n + j] += acc;
+        }
+    }
+}
+
+pub fn transpose(rows: usize, cols: usize, a: &[f32]) -> Vec<f32> {
+    let mut res = vec![0.; a.len()];
+    for i in 0..rows {
+        for j in 0..cols {
+            res[j * rows + i] = a[i * cols + j];
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rust_tools::bench::Bench;
+
+    use crate::kernels::gemm::{gemm, gemm_naive, transpose};
+
+    fn matrix(rows: usize, cols: usize, seed: usize) -> Vec<f32> {
+        (0..rows * cols).map(|i| ((i * 13 + seed) % 17) as f32 / 8. - 1.).collect()
+    }
+
+    #[test]
+    fn gemm_matches_naive() {
+        // sizes crossing the block boundaries
+        let (m, n, k) = (7, 1030, 300);
+        let (a, b) = (matrix(m, k, 1), matrix(k, n, 2));
+        let (mut c, mut expected) = (vec![1.; m * n], vec![1.; m * n]);
+        gemm(m, n, k, &a, &b, &mut c);
+        gemm_naive(m, n, k, &a, &b, &mut expected);
+        assert!(c.iter().zip(expected.iter()).all(|(x, y)| (x - y).abs() < 1e-3));
+        assert_eq!(transpose(2, 3, &[1., 2., 3., 4., 5., 6.]), vec![1., 4., 2., 5., 3., 6.]);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_gemm() {
+        let n = 128;
+        let (a, b) = (matrix(n, n, 1), matrix(n, n, 2));
+        let mut c = vec![0.; n * n];
+        let mut bench = Bench::new("gemm 128x128");
+        while bench.for_duration(Duration::from_secs(1)) {
+            gemm(n, n, n, &a, &b, &mut c);
+        }
+        let mut bench = Bench::new("naive matmul 128x128");
+        while bench.for_duration(Duration::from_secs(1)) {
+            gemm_naive(n, n, n, &a, &b, &mut c);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+// convolutions as matrix products: the receptive field of each output position becomes a column
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ConvGeometry {
+    pub width: usize,
+    pub height: usize,
+    pub channels: usize,
+    pub kw: usize,
+    pub kh: usize,
+    pub padding: usize,
+    pub stride: usize,
+}
+
+impl ConvGeometry {
+    pub fn output_width(&self) -> usize {
+        (self.width + 2 * self.padding - self.kw) / self.stride + 1
+    }
+
+    pub fn output_height(&self) -> usize {
+        (self.height + 2 * self.padding - self.kh) / self.stride + 1
+    }
+
+    // rows of the column matrix: one per filter weight of an output feature
+    pub fn patch_len(&self) -> usize {
+        self.kw * self.kh * self.channels
+    }
+
+    // columns of the column matrix
+    pub fn positions(&self) -> usize {
+        self.output_width() * self.output_height()
+    }
+
+    pub fn input_len(&self) -> usize {
+        self.width * self.height * self.channels
+    }
+
+    // calls f(input index, patch row, output position) for every weight inside the input,
+    // padding cells are skipped
+    pub fn for_each<F: FnMut(usize, usize, usize)>(&self, mut f: F) {
+        let (ow, oh) = (self.output_width(), self.output_height());
+        for c in 0..self.channels {
+            for ky in 0..self.kh {
+                for kx in 0..self.kw {
+                    let row = kx + self.kw * (ky + self.kh * c);
+                    for y in 0..oh {
+                        let iy = (y * self.stride + ky).wrapping_sub(self.padding);
+                        if iy >= self.height {
+                            continue;
+                        }
+                        for x in 0..ow {
+                            let ix = (x * self.stride + kx).wrapping_sub(self.padding);
+                            if ix < self.width {
+                                f(ix + self.width * (iy + self.height * c), row, x + ow * y);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // (patch_len x positions) row major matrix of one input image
+    pub fn im2col(&self, input: &[f32]) -> Vec<f32> {
+        assert_eq!(input.len(), self.input_len());
+        let positions = self.positions();
+        let mut cols = vec![0.; self.patch_len() * positions];
+        self.for_each(|i, row, pos| cols[row * positions + pos] = input[i]);
+        cols
+    }
+
+    // adds the columns back to the cells they were read from
+    pub fn col2im(&self, cols: &[f32], input: &mut [f32]) {
+        assert_eq!(input.len(), self.input_len());
+        let positions = self.positions();
+        self.for_each(|i, row, pos| input[i] += cols[row * positions + pos]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kernels::im2col::ConvGeometry;
+
+    #[test]
+    fn columns() {
+        let geometry = ConvGeometry { width: 3, height: 3, channels: 1, kw: 2, kh: 2, padding: 0, stride: 1 };
+        let input: Vec<f32> = (0..9).map(|i| i as f32).collect();
+        let cols = geometry.im2col(&input);
+        // first row: top left weight of the 4 output positions
+        assert_eq!(&cols[..4], &[0., 1., 3., 4.]);
+        assert_eq!(&cols[12..], &[4., 5., 7., 8.]);
+
+        let mut back = vec![0.; 9];
+        geometry.col2im(&cols, &mut back);
+        assert_eq!(back, vec![0., 2., 2., 6., 16., 10., 6., 14., 8.]);
+
+        let strided = ConvGeometry { padding: 1, stride: 2, ..geometry };
+        assert_eq!((strided.output_width(), strided.output_height()), (2, 2));
+        assert_eq!(&strided.im2col(&input)[..4], &[0., 0., 0., 4.]);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod simd;
+pub mod gemm;
+pub mod im2col;
\ No newline at end of file
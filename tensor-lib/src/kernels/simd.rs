@@ -0,0 +1,92 @@
+// vector kernels: AVX2/FMA when the cpu has them, scalar loops otherwise
+
+pub fn axpy(alpha: f32, x: &[f32], y: &mut [f32]) {
+    assert_eq!(x.len(), y.len());
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+            return unsafe { x86::axpy(alpha, x, y) };
+        }
+    }
+    axpy_scalar(alpha, x, y)
+}
+
+pub fn dot(x: &[f32], y: &[f32]) -> f32 {
+    assert_eq!(x.len(), y.len());
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+            return unsafe { x86::dot(x, y) };
+        }
+    }
+    dot_scalar(x, y)
+}
+
+// y += alpha * x
+pub fn axpy_scalar(alpha: f32, x: &[f32], y: &mut [f32]) {
+    for (y, x) in y.iter_mut().zip(x.iter()) {
+        *y += alpha * x;
+    }
+}
+
+pub fn dot_scalar(x: &[f32], y: &[f32]) -> f32 {
+    // independent accumulators let the compiler vectorise the loop
+    let mut acc = [0_f32; 8];
+    let chunks = x.len() / 8 * 8;
+    for (x, y) in x[..chunks].chunks_exact(8).zip(y[..chunks].chunks_exact(8)) {
+        for i in 0..8 {
+            acc[i] += x[i] * y[i];
+        }
+    }
+    let tail: f32 = x[chunks..].iter().zip(y[chunks..].iter()).map(|(x, y)| x * y).sum();
+    acc.iter().sum::<f32>() + tail
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    #[target_feature(enable = "avx2,fma")]
+    pub unsafe fn axpy(alpha: f32, x: &[f32], y: &mut [f32]) {
+        let n = x.len() / 8 * 8;
+        let a = _mm256_set1_ps(alpha);
+        for i in (0..n).step_by(8) {
+            let xv = _mm256_loadu_ps(x.as_ptr().add(i));
+            let yv = _mm256_loadu_ps(y.as_ptr().add(i));
+            _mm256_storeu_ps(y.as_mut_ptr().add(i), _mm256_fmadd_ps(a, xv, yv));
+        }
+        super::axpy_scalar(alpha, &x[n..], &mut y[n..]);
+    }
+
+    #[target_feature(enable = "avx2,fma")]
+    pub unsafe fn dot(x: &[f32], y: &[f32]) -> f32 {
+        let n = x.len() / 8 * 8;
+        let mut acc = _mm256_setzero_ps();
+        for i in (0..n).step_by(8) {
+            let xv = _mm256_loadu_ps(x.as_ptr().add(i));
+            let yv = _mm256_loadu_ps(y.as_ptr().add(i));
+            acc = _mm256_fmadd_ps(xv, yv, acc);
+        }
+        let mut lanes = [0_f32; 8];
+        _mm256_storeu_ps(lanes.as_mut_ptr(), acc);
+        lanes.iter().sum::<f32>() + super::dot_scalar(&x[n..], &y[n..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kernels::simd::{axpy, axpy_scalar, dot, dot_scalar};
+
+    #[test]
+    fn kernels_match_scalar() {
+        let x: Vec<f32> = (0..37).map(|i| (i as f32 * 0.3).sin()).collect();
+        let y: Vec<f32> = (0..37).map(|i| (i as f32 * 0.7).cos()).collect();
+        assert!((dot(&x, &y) - dot_scalar(&x, &y)).abs() < 1e-4);
+        assert!((dot_scalar(&x, &y) - x.iter().zip(y.iter()).map(|(a, b)| a * b).sum::<f32>()).abs() < 1e-4);
+
+        let (mut a, mut b) = (y.clone(), y.clone());
+        axpy(0.5, &x, &mut a);
+        axpy_scalar(0.5, &x, &mut b);
+        assert!(a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 1e-6));
+    }
+}
@@ -1,4 +1,8 @@
 pub mod tensor;
 pub mod tensor_ops;
 pub mod traits;
-pub mod structs;
\ No newline at end of file
+pub mod structs;
+pub mod tensor_math;
+pub mod autograd;
+pub mod kernels;
+pub mod tensor_io;
\ No newline at end of file
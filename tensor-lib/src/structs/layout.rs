@@ -0,0 +1,256 @@
+use std::fmt::{Display, Formatter};
+use std::fmt;
+use std::ops::Range;
+
+use crate::structs::shape4::{NDIMS, Shape4};
+
+// maps logical coordinates to buffer positions: dimensions are listed fastest first
+// (x, y, z, t, ...), a stride of 0 repeats the same values along a broadcast dimension
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Layout {
+    pub dims: Vec<usize>,
+    pub strides: Vec<usize>,
+    pub offset: usize,
+}
+
+impl Layout {
+    pub fn new(dims: &[usize]) -> Self {
+        Layout {
+            dims: dims.to_vec(),
+            strides: Layout::contiguous_strides(dims),
+            offset: 0,
+        }
+    }
+
+    pub fn from_shape4(shape: &Shape4) -> Self {
+        Layout::new(&[shape.x().unwrap(), shape.y().unwrap(), shape.z().unwrap(), shape.t().unwrap()])
+    }
+
+    fn contiguous_strides(dims: &[usize]) -> Vec<usize> {
+        let mut stride = 1;
+        dims.iter().map(|&d| {
+            let res = stride;
+            stride *= d;
+            res
+        }).collect()
+    }
+
+    pub fn ndim(&self) -> usize {
+        self.dims.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.dims.iter().product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // dimensions of size 1 may have any stride
+    pub fn is_contiguous(&self) -> bool {
+        let mut expected = 1;
+        for (&d, &s) in self.dims.iter().zip(self.strides.iter()) {
+            if d != 1 && s != expected {
+                return false;
+            }
+            expected *= d;
+        }
+        true
+    }
+
+    pub fn is_broadcast(&self) -> bool {
+        self.dims.iter().zip(self.strides.iter()).any(|(&d, &s)| d > 1 && s == 0)
+    }
+
+    // 4d summary of the shape: missing dimensions are 1, extra ones are folded into t
+    pub fn shape4(&self) -> Shape4 {
+        let dim = |i: usize| self.dims.get(i).cloned().unwrap_or(1);
+        let t = self.dims.iter().skip(NDIMS - 1).product::<usize>();
+        Shape4::vec4(dim(0), dim(1), dim(2), t)
+    }
+
+    pub fn position(&self, coords: &[usize]) -> usize {
+        assert_eq!(coords.len(), self.ndim(), "{:?} is not a coordinate of {}", coords, self);
+        coords.iter().zip(self.dims.iter()).zip(self.strides.iter())
+            .fold(self.offset, |pos, ((&c, &d), &s)| {
+                assert!(c < d, "{:?} is out of {}", coords, self);
+                pos + c * s
+            })
+    }
+
+    // buffer position of the i-th element in logical order
+    pub fn position_of(&self, index: usize) -> usize {
+        let mut rest = index;
+        let mut pos = self.offset;
+        for (&d, &s) in self.dims.iter().zip(self.strides.iter()) {
+            pos += (rest % d) * s;
+            rest /= d;
+        }
+        assert_eq!(rest, 0, "index {} is out of {}", index, self);
+        pos
+    }
+
+    pub fn positions(&self) -> Positions<'_> {
+        Positions {
+            layout: self,
+            coords: vec![0; self.ndim()],
+            pos: self.offset,
+            remaining: self.len(),
+        }
+    }
+
+    pub fn slice(&self, dim: usize, range: Range<usize>) -> Layout {
+        assert!(range.start <= range.end && range.end <= self.dims[dim], "{:?} is out of {}", range, self);
+        let mut res = self.clone();
+        res.offset += range.start * self.strides[dim];
+        res.dims[dim] = range.end - range.start;
+        res
+    }
+
+    pub fn window(&self, start: &[usize], dims: &[usize]) -> Layout {
+        assert_eq!(start.len(), self.ndim());
+        assert_eq!(dims.len(), self.ndim());
+        (0..self.ndim()).fold(self.clone(), |res, i| res.slice(i, start[i]..start[i] + dims[i]))
+    }
+
+    pub fn permute(&self, order: &[usize]) -> Layout {
+        let mut sorted = order.to_vec();
+        sorted.sort_unstable();
+        assert!(sorted.iter().cloned().eq(0..self.ndim()), "{:?} is not a permutation of {} dimensions", order, self.ndim());
+        Layout {
+            dims: order.iter().map(|&i| self.dims[i]).collect(),
+            strides: order.iter().map(|&i| self.strides[i]).collect(),
+            offset: self.offset,
+        }
+    }
+
+    // same data seen with other dimensions, only when no copy is needed
+    pub fn reshape(&self, dims: &[usize]) -> Option<Layout> {
+        assert_eq!(dims.iter().product::<usize>(), self.len(), "can not reshape {} into {:?}", self, dims);
+        match self.is_contiguous() {
+            true => Some(Layout { offset: self.offset, ..Layout::new(dims) }),
+            false => None
+        }
+    }
+
+    // trailing dimensions of size 1 are added up to ndim
+    pub fn expand_dims(&self, ndim: usize) -> Layout {
+        let mut res = self.clone();
+        while res.ndim() < ndim {
+            res.dims.push(1);
+            res.strides.push(0);
+        }
+        res
+    }
+
+    pub fn broadcast_to(&self, dims: &[usize]) -> Layout {
+        assert!(self.ndim() <= dims.len(), "can not broadcast {} to {:?}", self, dims);
+        let expanded = self.expand_dims(dims.len());
+        let strides = expanded.dims.iter().zip(expanded.strides.iter()).zip(dims.iter())
+            .map(|((&from, &stride), &to)| match from {
+                d if d == to => stride,
+                1 => 0,
+                _ => panic!("can not broadcast {} to {:?}", self, dims),
+            })
+            .collect();
+        Layout { dims: dims.to_vec(), strides, offset: self.offset }
+    }
+
+    // NumPy rules, dimensions being aligned on the fastest one:
+    // sizes must be equal or 1, a missing dimension counts as 1
+    pub fn broadcast_dims(a: &[usize], b: &[usize]) -> Result<Vec<usize>, String> {
+        (0..a.len().max(b.len()))
+            .map(|i| {
+                let da = a.get(i).cloned().unwrap_or(1);
+                let db = b.get(i).cloned().unwrap_or(1);
+                match (da, db) {
+                    _ if da == db => Ok(da),
+                    (1, _) => Ok(db),
+                    (_, 1) => Ok(da),
+                    _ => Err(format!("can not broadcast {:?} with {:?}", a, b)),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let dims = self.dims.iter().map(|d| d.to_string()).collect::<Vec<_>>().join("x");
+        write!(f, "{} (strides {:?}, offset {})", dims, self.strides, self.offset)
+    }
+}
+
+// buffer positions of all the elements, in logical order
+pub struct Positions<'a> {
+    layout: &'a Layout,
+    coords: Vec<usize>,
+    pos: usize,
+    remaining: usize,
+}
+
+impl<'a> Iterator for Positions<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let res = self.pos;
+        for i in 0..self.coords.len() {
+            self.coords[i] += 1;
+            self.pos += self.layout.strides[i];
+            if self.coords[i] < self.layout.dims[i] {
+                break;
+            }
+            self.pos -= self.coords[i] * self.layout.strides[i];
+            self.coords[i] = 0;
+        }
+        Some(res)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::structs::layout::Layout;
+    use crate::structs::shape4::Shape4;
+
+    #[test]
+    fn positions() {
+        let layout = Layout::new(&[3, 2, 2]);
+        assert_eq!(layout.strides, vec![1, 3, 6]);
+        assert!(layout.positions().eq(0..12));
+        assert_eq!(layout.position(&[2, 1, 1]), 11);
+        assert_eq!(layout.shape4(), Shape4::vec3(3, 2, 2));
+
+        let window = layout.window(&[1, 1, 0], &[2, 1, 2]);
+        assert!(!window.is_contiguous());
+        assert_eq!(window.positions().collect::<Vec<_>>(), vec![4, 5, 10, 11]);
+        assert_eq!(window.position_of(3), 11);
+
+        let t = layout.permute(&[1, 0, 2]);
+        assert_eq!(t.dims, vec![2, 3, 2]);
+        assert_eq!(t.positions().take(4).collect::<Vec<_>>(), vec![0, 3, 1, 4]);
+        assert!(t.reshape(&[12]).is_none());
+        assert_eq!(layout.reshape(&[6, 2]).unwrap().strides, vec![1, 6]);
+    }
+
+    #[test]
+    fn broadcasting() {
+        assert_eq!(Layout::broadcast_dims(&[3, 1], &[3, 4, 2]), Ok(vec![3, 4, 2]));
+        assert_eq!(Layout::broadcast_dims(&[1], &[5, 2]), Ok(vec![5, 2]));
+        assert!(Layout::broadcast_dims(&[3], &[4]).is_err());
+
+        let row = Layout::new(&[3]).broadcast_to(&[3, 2]);
+        assert_eq!(row.strides, vec![1, 0]);
+        assert!(row.is_broadcast());
+        assert_eq!(row.positions().collect::<Vec<_>>(), vec![0, 1, 2, 0, 1, 2]);
+        assert_eq!(Layout::new(&[2, 3, 4, 5, 6]).shape4(), Shape4::vec4(2, 3, 4, 30));
+    }
+}
@@ -3,3 +3,4 @@ pub mod shape4;
 pub mod offset4;
 pub mod view4;
 pub mod offset4_ops;
+pub mod layout;
\ No newline at end of file
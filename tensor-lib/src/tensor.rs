@@ -1,19 +1,23 @@
-use std::borrow::Borrow;
-use std::cell::RefCell;
 use std::fmt::{Display, Formatter};
 use std::fmt;
-use std::rc::Rc;
+use std::ops::Range;
+use std::sync::Arc;
 
+use rand::Rng;
 use rand_distr::{Distribution, Normal};
 
+use crate::structs::layout::Layout;
 use crate::structs::offset4::Offset4;
-use crate::structs::shape4::Shape4;
+use crate::structs::shape4::{NDIMS, Shape4};
 use crate::structs::view4::View4;
 use crate::traits::view::View;
 
-#[derive(Debug)]
+// views share the buffer of their parent, the first write to a shared buffer copies it
+#[derive(Debug, Clone)]
 pub struct Tensor {
-    buffer: Vec<f32>,
+    buffer: Arc<Vec<f32>>,
+    layout: Layout,
+    contiguous: bool,
     view: View4,
 }
 
@@ -23,11 +27,11 @@ impl Tensor {
     }
 
     pub fn from_distrib<D: Distribution<f32>>(shape: Shape4, dist: D) -> Self {
-        let mut rng = rand::thread_rng();
-        let mut buffer = vec![0_f32; shape.len()];
-        for i in 0..buffer.len() {
-            buffer[i] = dist.sample(&mut rng);
-        }
+        Self::from_distrib_rng(shape, dist, &mut rand::thread_rng())
+    }
+
+    pub fn from_distrib_rng<D: Distribution<f32>, R: Rng>(shape: Shape4, dist: D, rng: &mut R) -> Self {
+        let buffer = (0..shape.len()).map(|_| dist.sample(rng)).collect();
         Self::from_buffer(buffer, View4::new(shape))
     }
 
@@ -40,49 +44,199 @@ impl Tensor {
     pub fn from_buffer(buffer: Vec<f32>, view: View4) -> Self {
         assert_eq!(buffer.len(), view.shape().len());
         Tensor {
-            buffer,
+            buffer: Arc::new(buffer),
+            layout: Layout::from_shape4(view.shape()),
+            contiguous: true,
             view,
         }
     }
 
-    pub fn view(&self, offset: Offset4, shape: Shape4) -> Tensor {
+    pub fn from_vec(buffer: Vec<f32>, dims: &[usize]) -> Self {
+        let layout = Layout::new(dims);
+        assert_eq!(buffer.len(), layout.len(), "{} values for {}", buffer.len(), layout);
+        Tensor::from_layout(Arc::new(buffer), layout, Offset4::origin())
+    }
+
+    pub fn full(dims: &[usize], value: f32) -> Self {
+        Tensor::from_vec(vec![value; dims.iter().product()], dims)
+    }
+
+    pub fn zeros(dims: &[usize]) -> Self {
+        Tensor::full(dims, 0.)
+    }
+
+    fn from_layout(buffer: Arc<Vec<f32>>, layout: Layout, offset: Offset4) -> Self {
         Tensor {
-            buffer: self.buffer.clone(),
-            view: View4 {
-                offset,
-                shape,
-            },
+            buffer,
+            contiguous: layout.is_contiguous(),
+            view: View4 { offset, shape: layout.shape4() },
+            layout,
         }
     }
 
-    pub fn get_at(&self, offset: Offset4) -> f32 {
-        log::trace!("Offset4.index_from");
+    fn with_layout(&self, layout: Layout) -> Tensor {
+        Tensor::from_layout(self.buffer.clone(), layout, self.view.offset.clone())
+    }
+
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    pub fn dims(&self) -> &[usize] {
+        &self.layout.dims
+    }
+
+    pub fn strides(&self) -> &[usize] {
+        &self.layout.strides
+    }
+
+    pub fn ndim(&self) -> usize {
+        self.layout.ndim()
+    }
+
+    pub fn len(&self) -> usize {
+        self.layout.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layout.is_empty()
+    }
+
+    pub fn is_contiguous(&self) -> bool {
+        self.contiguous
+    }
+
+    pub fn shares_buffer(&self, other: &Tensor) -> bool {
+        Arc::ptr_eq(&self.buffer, &other.buffer)
+    }
+
+    // zero copy sub block starting at offset, relative to this tensor
+    pub fn view(&self, offset: Offset4, shape: Shape4) -> Tensor {
+        assert!(self.ndim() <= NDIMS, "4d view of a {}d tensor", self.ndim());
+        let start = [offset.x(), offset.y(), offset.z(), offset.t()];
+        let dims = [shape.x().unwrap(), shape.y().unwrap(), shape.z().unwrap(), shape.t().unwrap()];
+        let layout = self.layout.expand_dims(NDIMS).window(&start, &dims);
+        Tensor::from_layout(self.buffer.clone(), layout, offset)
+    }
+
+    pub fn slice(&self, dim: usize, range: Range<usize>) -> Tensor {
+        self.with_layout(self.layout.slice(dim, range))
+    }
+
+    pub fn permute(&self, order: &[usize]) -> Tensor {
+        self.with_layout(self.layout.permute(order))
+    }
+
+    pub fn transpose(&self, a: usize, b: usize) -> Tensor {
+        let mut order: Vec<usize> = (0..self.ndim()).collect();
+        order.swap(a, b);
+        self.permute(&order)
+    }
 
-        self.get(self.shape().index(&offset))
+    // zero copy unless the tensor is a non contiguous view
+    pub fn reshape(&self, dims: &[usize]) -> Tensor {
+        match self.layout.reshape(dims) {
+            Some(layout) => self.with_layout(layout),
+            None => Tensor::from_vec(self.to_vec(), dims)
+        }
+    }
+
+    // read only: writing to a broadcast view panics
+    pub fn broadcast_to(&self, dims: &[usize]) -> Tensor {
+        self.with_layout(self.layout.broadcast_to(dims))
+    }
+
+    pub fn contiguous(&self) -> Tensor {
+        match self.contiguous && !self.layout.is_broadcast() {
+            true => self.clone(),
+            false => Tensor::from_vec(self.to_vec(), self.dims())
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=f32> + '_ {
+        self.layout.positions().map(move |pos| self.buffer[pos])
+    }
+
+    pub fn to_vec(&self) -> Vec<f32> {
+        match self.contiguous {
+            true => self.buffer[self.layout.offset..self.layout.offset + self.len()].to_vec(),
+            false => self.iter().collect()
+        }
+    }
+
+    fn position4(&self, offset: &Offset4) -> usize {
+        let n = self.ndim();
+        match n <= NDIMS {
+            true => {
+                let coords = [offset.x(), offset.y(), offset.z(), offset.t()];
+                assert!(coords[n..].iter().all(|&c| c == 0), "{:?} is out of {}", offset, self.layout);
+                self.layout.position(&coords[..n])
+            }
+            false => self.layout.position_of(self.shape().index(offset))
+        }
+    }
+
+    pub fn get_at(&self, offset: Offset4) -> f32 {
+        self.buffer[self.position4(&offset)]
     }
 
     pub fn insert_at(&mut self, offset: Offset4, value: f32) {
-        self.insert(self.shape().index(&offset), value)
+        let pos = self.position4(&offset);
+        self.write(pos, value);
     }
 
-    pub fn get(&self, offset: usize) -> f32 {
-        self.buffer[offset]
+    pub fn get_nd(&self, coords: &[usize]) -> f32 {
+        self.buffer[self.layout.position(coords)]
     }
 
-    pub fn insert(&mut self, offset: usize, value: f32) {
-        self.buffer[offset] = value;
+    pub fn insert_nd(&mut self, coords: &[usize], value: f32) {
+        let pos = self.layout.position(coords);
+        self.write(pos, value);
+    }
+
+    // i-th element in logical order
+    pub fn get(&self, index: usize) -> f32 {
+        match self.contiguous {
+            true => self.buffer[self.layout.offset + index],
+            false => self.buffer[self.layout.position_of(index)]
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, value: f32) {
+        let pos = match self.contiguous {
+            true => self.layout.offset + index,
+            false => self.layout.position_of(index)
+        };
+        self.write(pos, value);
+    }
+
+    fn write(&mut self, pos: usize, value: f32) {
+        self.buffer_mut()[pos] = value;
+    }
+
+    pub(crate) fn buffer_mut(&mut self) -> &mut Vec<f32> {
+        assert!(!self.layout.is_broadcast(), "write to a broadcast view {}", self.layout);
+        Arc::make_mut(&mut self.buffer)
+    }
+
+    pub(crate) fn buffer(&self) -> &[f32] {
+        &self.buffer
     }
 
     pub fn copy_from(&mut self, other: &Tensor) {
-        self.buffer.as_mut_slice().copy_from_slice(
-            other.buffer.as_slice()
-        )
+        assert_eq!(self.len(), other.len());
+        let layout = self.layout.clone();
+        let buffer = self.buffer_mut();
+        for (pos, value) in layout.positions().zip(other.iter()) {
+            buffer[pos] = value;
+        }
     }
 
     pub fn deep_clone(&self) -> Tensor {
-        Tensor::from_buffer(
-            self.buffer.clone(),
-            self.view.clone(),
+        Tensor::from_layout(
+            Arc::new(self.to_vec()),
+            Layout::new(self.dims()),
+            self.view.offset.clone(),
         )
     }
 }
@@ -100,13 +254,13 @@ impl View for Tensor {
 
 impl Display for Tensor {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self.buffer)
+        write!(f, "{:?}", self.to_vec())
     }
 }
 
-impl Clone for Tensor {
-    fn clone(&self) -> Self {
-        self.deep_clone()
+impl PartialEq for Tensor {
+    fn eq(&self, other: &Self) -> bool {
+        self.dims() == other.dims() && self.iter().eq(other.iter())
     }
 }
 
@@ -116,8 +270,10 @@ mod tests {
 
     use rust_tools::bench::Bench;
 
+    use crate::structs::offset4::Offset4;
     use crate::structs::shape4::Shape4;
     use crate::tensor::Tensor;
+    use crate::traits::view::View;
 
     #[test]
     fn test_tensor() {
@@ -132,4 +288,40 @@ mod tests {
         // println!("{:?}", x);
         println!("{}", bench);
     }
+
+    #[test]
+    fn views() {
+        let x = Tensor::from_vec((0..24).map(|i| i as f32).collect(), &[4, 3, 2]);
+        let view = x.view(Offset4::new(1, 1, 0, 0), Shape4::vec3(2, 2, 2));
+        assert!(view.shares_buffer(&x));
+        assert_eq!(view.shape(), &Shape4::vec3(2, 2, 2));
+        assert_eq!(view.to_vec(), vec![5., 6., 9., 10., 17., 18., 21., 22.]);
+        assert_eq!(view.get_at(Offset4::new(1, 0, 1, 0)), 18.);
+
+        let t = x.transpose(0, 1);
+        assert_eq!(t.dims(), &[3, 4, 2]);
+        assert_eq!(t.get_nd(&[2, 1, 0]), x.get_nd(&[1, 2, 0]));
+        assert_eq!(x.slice(2, 1..2).to_vec(), (12..24).map(|i| i as f32).collect::<Vec<_>>());
+
+        // reshaping a contiguous tensor is free, a transposed one is copied
+        assert!(x.reshape(&[12, 2]).shares_buffer(&x));
+        let flat = t.reshape(&[24]);
+        assert!(!flat.shares_buffer(&x));
+        assert_eq!(flat.get(1), 4.);
+        assert_eq!(t.contiguous(), t);
+
+        // writing through a view leaves the parent untouched
+        let mut view = view;
+        view.insert_at(Offset4::new(0, 0, 0, 0), -1.);
+        assert_eq!(view.get(0), -1.);
+        assert_eq!(x.get(5), 5.);
+        assert!(!view.shares_buffer(&x));
+    }
+
+    #[test]
+    #[should_panic]
+    fn broadcast_is_read_only() {
+        let mut row = Tensor::zeros(&[3]).broadcast_to(&[3, 2]);
+        row.insert(0, 1.);
+    }
 }
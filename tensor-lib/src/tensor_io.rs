@@ -0,0 +1,79 @@
+use std::io;
+use std::io::{Read, Write};
+
+use crate::tensor::Tensor;
+
+// self describing binary form: dimension count, dimensions and values, little endian
+impl Tensor {
+    pub fn write_to<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        out.write_all(&(self.ndim() as u32).to_le_bytes())?;
+        for &d in self.dims() {
+            out.write_all(&(d as u32).to_le_bytes())?;
+        }
+        let mut bytes = Vec::with_capacity(4 * self.len());
+        for x in self.iter() {
+            bytes.extend_from_slice(&x.to_le_bytes());
+        }
+        out.write_all(&bytes)
+    }
+
+    pub fn read_from<R: Read>(input: &mut R) -> io::Result<Tensor> {
+        let dims = read_dims(input)?;
+        read_values(input, &dims)
+    }
+
+    // the stored dimensions are checked before the values are allocated
+    pub fn read_expected<R: Read>(input: &mut R, expected: &[usize]) -> io::Result<Tensor> {
+        let dims = read_dims(input)?;
+        if dims != expected {
+            return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                      format!("tensor of {:?} instead of {:?}", dims, expected)));
+        }
+        read_values(input, &dims)
+    }
+}
+
+fn read_dims<R: Read>(input: &mut R) -> io::Result<Vec<usize>> {
+    let ndim = read_u32(input)? as usize;
+    if ndim > 16 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("tensor of {} dimensions", ndim)));
+    }
+    (0..ndim).map(|_| read_u32(input).map(|d| d as usize)).collect()
+}
+
+fn read_values<R: Read>(input: &mut R, dims: &[usize]) -> io::Result<Tensor> {
+    let len = dims.iter().try_fold(4_usize, |n, &d| n.checked_mul(d))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("tensor of {:?} is too large", dims)))?;
+    let mut bytes = vec![0_u8; len];
+    input.read_exact(&mut bytes)?;
+    let values = bytes.chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    Ok(Tensor::from_vec(values, dims))
+}
+
+fn read_u32<R: Read>(input: &mut R) -> io::Result<u32> {
+    let mut bytes = [0_u8; 4];
+    input.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::tensor::Tensor;
+
+    #[test]
+    fn round_trip() {
+        let x = Tensor::from_vec((0..6).map(|i| i as f32 - 2.5).collect(), &[3, 2]).transpose(0, 1);
+        let mut bytes = vec![];
+        x.write_to(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 4 + 8 + 24);
+        assert_eq!(Tensor::read_from(&mut Cursor::new(&bytes)).unwrap(), x);
+        assert!(Tensor::read_from(&mut Cursor::new(&bytes[..20])).is_err());
+        assert_eq!(Tensor::read_expected(&mut Cursor::new(&bytes), &[2, 3]).unwrap(), x);
+        let err = Tensor::read_expected(&mut Cursor::new(&bytes), &[3, 2]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::kernels::gemm::{gemm, transpose};
+use crate::kernels::im2col::ConvGeometry;
+use crate::structs::layout::Layout;
+use crate::tensor::Tensor;
+
+// matrices are [cols, rows]: x is the fastest dimension as in Shape4, rows are stored contiguously
+// convolutions use [width, height, channels, batch] inputs and [kw, kh, in, out] filters
+impl Tensor {
+    pub fn sum(&self) -> f32 {
+        self.iter().sum()
+    }
+
+    pub fn mean(&self) -> f32 {
+        self.sum() / self.len() as f32
+    }
+
+    // reverse of broadcasting: sums the dimensions which are 1 (or missing) in dims
+    pub fn sum_to(&self, dims: &[usize]) -> Tensor {
+        if self.dims() == dims {
+            return self.clone();
+        }
+        let mut res = Tensor::zeros(dims);
+        let layout = Layout::new(dims).broadcast_to(self.dims());
+        let buffer = res.buffer_mut();
+        for (pos, value) in layout.positions().zip(self.iter()) {
+            buffer[pos] += value;
+        }
+        res
+    }
+
+    // [k, m] x [n, k] -> [n, m]
+    pub fn matmul(&self, rhs: &Tensor) -> Tensor {
+        assert!(self.ndim() == 2 && rhs.ndim() == 2, "matmul of {:?} and {:?}", self.dims(), rhs.dims());
+        let (k, m, n) = (self.dims()[0], self.dims()[1], rhs.dims()[0]);
+        assert_eq!(rhs.dims()[1], k, "matmul of {:?} and {:?}", self.dims(), rhs.dims());
+        let mut res = vec![0.; n * m];
+        gemm(m, n, k, &self.to_vec(), &rhs.to_vec(), &mut res);
+        Tensor::from_vec(res, &[n, m])
+    }
+
+    fn conv_geometry(&self, filter: &Tensor, padding: usize, stride: usize) -> (ConvGeometry, usize, usize) {
+        let dim = |t: &Tensor, i: usize| t.dims().get(i).cloned().unwrap_or(1);
+        assert!(self.ndim() <= 4 && filter.ndim() <= 4 && stride > 0);
+        assert_eq!(dim(self, 2), dim(filter, 2), "conv2d of {:?} with {:?}", self.dims(), filter.dims());
+        let geometry = ConvGeometry {
+            width: dim(self, 0),
+            height: dim(self, 1),
+            channels: dim(self, 2),
+            kw: dim(filter, 0),
+            kh: dim(filter, 1),
+            padding,
+            stride,
+        };
+        assert!(geometry.kw <= geometry.width + 2 * padding && geometry.kh <= geometry.height + 2 * padding);
+        (geometry, dim(filter, 3), dim(self, 3))
+    }
+
+    fn conv_dims(&self, geometry: &ConvGeometry, features: usize, batch: usize) -> Vec<usize> {
+        let dims = [geometry.output_width(), geometry.output_height(), features, batch];
+        dims[..self.ndim().max(3)].to_vec()
+    }
+
+    // im2col then one matrix product per image
+    pub fn conv2d(&self, filter: &Tensor, padding: usize, stride: usize) -> Tensor {
+        let (geometry, features, batch) = self.conv_geometry(filter, padding, stride);
+        let (k, p) = (geometry.patch_len(), geometry.positions());
+        let (input, kernel) = (self.to_vec(), filter.to_vec());
+        let mut res = vec![0.; features * p * batch];
+        for (image, out) in input.chunks(geometry.input_len()).zip(res.chunks_mut(features * p)) {
+            gemm(features, p, k, &kernel, &geometry.im2col(image), out);
+        }
+        Tensor::from_vec(res, &self.conv_dims(&geometry, features, batch))
+    }
+
+    // gradients of the input and of the filter
+    pub fn conv2d_backward(&self, filter: &Tensor, padding: usize, stride: usize, grad: &Tensor) -> (Tensor, Tensor) {
+        let (geometry, features, _) = self.conv_geometry(filter, padding, stride);
+        let (k, p) = (geometry.patch_len(), geometry.positions());
+        let (input, grad) = (self.to_vec(), grad.to_vec());
+        let kernel_t = transpose(features, k, &filter.to_vec());
+        let mut grad_input = vec![0.; input.len()];
+        let mut grad_filter = vec![0.; features * k];
+        let images = input.chunks(geometry.input_len())
+            .zip(grad_input.chunks_mut(geometry.input_len()))
+            .zip(grad.chunks(features * p));
+        for ((image, grad_image), g) in images {
+            let cols_t = transpose(k, p, &geometry.im2col(image));
+            gemm(features, k, p, g, &cols_t, &mut grad_filter);
+            let mut grad_cols = vec![0.; k * p];
+            gemm(k, p, features, &kernel_t, g, &mut grad_cols);
+            geometry.col2im(&grad_cols, grad_image);
+        }
+        (Tensor::from_vec(grad_input, self.dims()), Tensor::from_vec(grad_filter, filter.dims()))
+    }
+
+    // reference implementation, one product at a time
+    pub fn conv2d_direct(&self, filter: &Tensor, padding: usize, stride: usize) -> Tensor {
+        let (geometry, features, batch) = self.conv_geometry(filter, padding, stride);
+        let (k, p) = (geometry.patch_len(), geometry.positions());
+        let (input, kernel) = (self.to_vec(), filter.to_vec());
+        let mut res = vec![0.; features * p * batch];
+        for (image, out) in input.chunks(geometry.input_len()).zip(res.chunks_mut(features * p)) {
+            for o in 0..features {
+                geometry.for_each(|i, row, pos| out[o * p + pos] += image[i] * kernel[o * k + row]);
+            }
+        }
+        Tensor::from_vec(res, &self.conv_dims(&geometry, features, batch))
+    }
+
+    pub fn relu(&self) -> Tensor {
+        self.map(|x| x.max(0.))
+    }
+
+    pub fn exp(&self) -> Tensor {
+        self.map(f32::exp)
+    }
+
+    pub fn ln(&self) -> Tensor {
+        self.map(f32::ln)
+    }
+
+    // along the first dimension, each row of a matrix sums to 1
+    pub fn softmax(&self) -> Tensor {
+        let n = self.dims()[0];
+        let mut values = self.to_vec();
+        for row in values.chunks_mut(n) {
+            let max = row.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+            row.iter_mut().for_each(|x| *x = (*x - max).exp());
+            let sum: f32 = row.iter().sum();
+            row.iter_mut().for_each(|x| *x /= sum);
+        }
+        Tensor::from_vec(values, self.dims())
+    }
+
+    // adds a last dimension indexing the tensors, which share the same dimensions
+    pub fn stack(tensors: &[Tensor]) -> Tensor {
+        assert!(!tensors.is_empty());
+        let mut dims = tensors[0].dims().to_vec();
+        let mut values = Vec::with_capacity(tensors[0].len() * tensors.len());
+        for t in tensors {
+            assert_eq!(t.dims(), dims.as_slice(), "stack of different dimensions");
+            values.extend(t.iter());
+        }
+        dims.push(tensors.len());
+        Tensor::from_vec(values, &dims)
+    }
+
+    // sums the first dimension, keeping it with size 1
+    pub fn sum_rows(&self) -> Tensor {
+        let mut dims = self.dims().to_vec();
+        dims[0] = 1;
+        self.sum_to(&dims)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rust_tools::bench::Bench;
+
+    use crate::tensor::Tensor;
+
+    #[test]
+    fn matmul() {
+        // [[1, 2, 3], [4, 5, 6]] x [[1, 0], [0, 1], [1, 1]]
+        let a = Tensor::from_vec(vec![1., 2., 3., 4., 5., 6.], &[3, 2]);
+        let b = Tensor::from_vec(vec![1., 0., 0., 1., 1., 1.], &[2, 3]);
+        assert_eq!(a.matmul(&b).to_vec(), vec![4., 5., 10., 11.]);
+        assert_eq!(b.transpose(0, 1).matmul(&a.transpose(0, 1)), a.matmul(&b).transpose(0, 1));
+    }
+
+    #[test]
+    fn conv2d() {
+        let input = Tensor::from_vec((0..9).map(|i| i as f32).collect(), &[3, 3, 1]);
+        let filter = Tensor::full(&[2, 2, 1, 1], 1.);
+        assert_eq!(input.conv2d(&filter, 0, 1).to_vec(), vec![8., 12., 20., 24.]);
+        let same = input.conv2d(&Tensor::full(&[3, 3, 1, 2], 1.), 1, 1);
+        assert_eq!(same.dims(), &[3, 3, 2]);
+        assert_eq!(same.get(0), 8.);
+        assert_eq!(same.get(4), 36.);
+
+        let input = Tensor::from_vec((0..150).map(|i| (i % 7) as f32 - 3.).collect(), &[5, 5, 3, 2]);
+        let filter = Tensor::from_vec((0..54).map(|i| (i % 5) as f32 - 2.).collect(), &[3, 3, 3, 2]);
+        for &(padding, stride) in &[(0, 1), (1, 1), (1, 2), (2, 3)] {
+            assert_eq!(input.conv2d(&filter, padding, stride), input.conv2d_direct(&filter, padding, stride));
+        }
+    }
+
+    #[test]
+    fn reductions() {
+        let x = Tensor::from_vec(vec![1., 2., 3., 4., 5., 6.], &[3, 2]);
+        assert_eq!(x.sum(), 21.);
+        assert_eq!(x.sum_to(&[3]).to_vec(), vec![5., 7., 9.]);
+        assert_eq!(x.sum_rows().to_vec(), vec![6., 15.]);
+        let stacked = Tensor::stack(&[x.clone(), x.map(|v| -v)]);
+        assert_eq!(stacked.dims(), &[3, 2, 2]);
+        assert_eq!(stacked.get(6), -1.);
+        let p = x.softmax();
+        assert!((p.sum_rows().sum() - 2.).abs() < 1e-6);
+        assert!(p.get(2) > p.get(1));
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_conv2d() {
+        let input = Tensor::from_vec((0..128 * 128 * 8).map(|i| (i % 7) as f32).collect(), &[128, 128, 8]);
+        let filter = Tensor::full(&[3, 3, 8, 16], 0.1);
+        let mut bench = Bench::new("conv2d im2col 128x128x8 -> 16");
+        while bench.for_duration(Duration::from_secs(1)) {
+            input.conv2d(&filter, 1, 1);
+        }
+        let mut bench = Bench::new("conv2d direct 128x128x8 -> 16");
+        while bench.for_duration(Duration::from_secs(1)) {
+            input.conv2d_direct(&filter, 1, 1);
+        }
+    }
+}
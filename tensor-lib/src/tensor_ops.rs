@@ -1,19 +1,51 @@
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+
+use crate::structs::layout::Layout;
 use crate::tensor::Tensor;
-use crate::traits::view::View;
 
+impl Tensor {
+    pub fn map<F: Fn(f32) -> f32>(&self, f: F) -> Tensor {
+        Tensor::from_vec(self.iter().map(f).collect(), self.dims())
+    }
+
+    // element wise operation with NumPy style broadcasting
+    pub fn zip_with<F: Fn(f32, f32) -> f32>(&self, rhs: &Tensor, f: F) -> Tensor {
+        if self.dims() == rhs.dims() && self.is_contiguous() && rhs.is_contiguous() {
+            let values = self.to_vec().into_iter().zip(rhs.iter()).map(|(a, b)| f(a, b)).collect();
+            return Tensor::from_vec(values, self.dims());
+        }
+        let dims = Layout::broadcast_dims(self.dims(), rhs.dims()).unwrap();
+        let (a, b) = (self.broadcast_to(&dims), rhs.broadcast_to(&dims));
+        Tensor::from_vec(a.iter().zip(b.iter()).map(|(a, b)| f(a, b)).collect(), &dims)
+    }
+
+    // rhs is broadcast to the shape of self
+    pub fn zip_assign<F: Fn(f32, f32) -> f32>(&mut self, rhs: &Tensor, f: F) {
+        let rhs = match self.dims() == rhs.dims() {
+            true => rhs.clone(),
+            false => rhs.broadcast_to(self.dims())
+        };
+        let layout = self.layout().clone();
+        if layout.is_contiguous() && rhs.is_contiguous() {
+            let start = layout.offset;
+            let values = &mut self.buffer_mut()[start..start + layout.len()];
+            for (a, b) in values.iter_mut().zip(rhs.buffer()[rhs.layout().offset..].iter()) {
+                *a = f(*a, *b);
+            }
+            return;
+        }
+        let buffer = self.buffer_mut();
+        for (pos, b) in layout.positions().zip(rhs.iter()) {
+            buffer[pos] = f(buffer[pos], b);
+        }
+    }
+}
 
 impl<'a> Add for &'a Tensor {
     type Output = Tensor;
 
     fn add(self, rhs: Self) -> Self::Output {
-        assert_eq!(self.shape(), rhs.shape());
-        let mut res = self.clone();
-        for i in 0..res.shape().len() {
-            let val = res.get(i);
-            res.insert(i, val + rhs.get(i));
-        }
-        res
+        self.zip_with(rhs, |a, b| a + b)
     }
 }
 
@@ -21,13 +53,7 @@ impl<'a> Sub for &'a Tensor {
     type Output = Tensor;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        assert_eq!(self.shape(), rhs.shape());
-        let mut res = self.clone();
-        for i in 0..res.shape().len() {
-            let val = res.get(i);
-            res.insert(i, val - rhs.get(i));
-        }
-        res
+        self.zip_with(rhs, |a, b| a - b)
     }
 }
 
@@ -35,13 +61,7 @@ impl<'a> Mul for &'a Tensor {
     type Output = Tensor;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        assert_eq!(self.shape(), rhs.shape());
-        let mut res = self.clone();
-        for i in 0..res.shape().len() {
-            let val = res.get(i);
-            res.insert(i, val * rhs.get(i));
-        }
-        res
+        self.zip_with(rhs, |a, b| a * b)
     }
 }
 
@@ -49,55 +69,67 @@ impl<'a> Div for &'a Tensor {
     type Output = Tensor;
 
     fn div(self, rhs: Self) -> Self::Output {
-        assert_eq!(self.shape(), rhs.shape());
-        let mut res = self.clone();
-        for i in 0..res.shape().len() {
-            let val = res.get(i);
-            res.insert(i, val / rhs.get(i));
-        }
-        res
+        self.zip_with(rhs, |a, b| a / b)
     }
 }
 
 
 impl AddAssign for Tensor {
     fn add_assign(&mut self, rhs: Self) {
-        assert_eq!(self.shape(), rhs.shape());
-        for i in 0..self.shape().len() {
-            let val = self.get(i);
-            self.insert(i, val + rhs.get(i));
-        }
+        self.zip_assign(&rhs, |a, b| a + b)
     }
 }
 
 
 impl SubAssign for Tensor {
     fn sub_assign(&mut self, rhs: Self) {
-        assert_eq!(self.shape(), rhs.shape());
-        for i in 0..self.shape().len() {
-            let val = self.get(i);
-            self.insert(i, val - rhs.get(i));
-        }
+        self.zip_assign(&rhs, |a, b| a - b)
     }
 }
 
 impl MulAssign for Tensor {
     fn mul_assign(&mut self, rhs: Self) {
-        assert_eq!(self.shape(), rhs.shape());
-        for i in 0..self.shape().len() {
-            let val = self.get(i);
-            self.insert(i, val * rhs.get(i));
-        }
+        self.zip_assign(&rhs, |a, b| a * b)
     }
 }
 
 
 impl DivAssign for Tensor {
     fn div_assign(&mut self, rhs: Self) {
-        assert_eq!(self.shape(), rhs.shape());
-        for i in 0..self.shape().len() {
-            let val = self.get(i);
-            self.insert(i, val / rhs.get(i));
-        }
+        self.zip_assign(&rhs, |a, b| a / b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::structs::offset4::Offset4;
+    use crate::structs::shape4::Shape4;
+    use crate::tensor::Tensor;
+
+    #[test]
+    fn broadcasting() {
+        let x = Tensor::from_vec(vec![1., 2., 3., 4., 5., 6.], &[3, 2]);
+        let bias = Tensor::from_vec(vec![10., 20., 30.], &[3]);
+        assert_eq!((&x + &bias).to_vec(), vec![11., 22., 33., 14., 25., 36.]);
+
+        let column = Tensor::from_vec(vec![1., 2.], &[1, 2]);
+        let outer = &bias * &column;
+        assert_eq!(outer.dims(), &[3, 2]);
+        assert_eq!(outer.to_vec(), vec![10., 20., 30., 20., 40., 60.]);
+
+        let mut y = x.transpose(0, 1);
+        y -= Tensor::full(&[1], 1.);
+        assert_eq!(y.to_vec(), vec![0., 3., 1., 4., 2., 5.]);
+
+        // the 4d api broadcasts the same way
+        let mut z = Tensor::new(Shape4::vec2(3, 2), 1.);
+        z /= Tensor::new(Shape4::vec2(1, 2), 2.);
+        assert_eq!(z.get_at(Offset4::new(2, 1, 0, 0)), 0.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn incompatible_shapes() {
+        let _ = &Tensor::zeros(&[3]) + &Tensor::zeros(&[4]);
     }
 }